@@ -0,0 +1,10 @@
+//! Scans back to back and prints how it went, e.g. `cargo run --example
+//! soak -- 100` to run a hundred scans.
+
+fn main() {
+    let n = std::env::args()
+        .nth(1)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(10);
+    println!("{:?}", wifiscanner::scan_n_times(n));
+}
@@ -0,0 +1,674 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::channel::{common_5ghz_channels, is_dfs};
+use crate::dedup::ssids;
+use crate::signal::dbm_to_mw;
+use crate::{Band, MacAddr, SecurityKind, Wifi, CHANNELS_2_4, CHANNELS_6};
+
+/// Signal change in dB below which [`diff`] doesn't report a network
+pub const DEFAULT_SIGNAL_DELTA_DBM: i32 = 5;
+
+/// Per channel aggregate returned by [`channel_histogram`]
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct ChannelStats {
+    /// number of networks seen on the channel
+    pub network_count: usize,
+    /// strongest signal seen on the channel in dBm
+    pub strongest_dbm: Option<i32>,
+    /// sum of the signals in mW, networks without a signal don't contribute
+    pub summed_linear_power: f64,
+}
+
+/// Aggregates networks per channel for the given band, e.g. to draw a heatmap.
+///
+/// Networks whose channel can't be parsed or isn't in `band` are skipped.
+pub fn channel_histogram(wifis: &[Wifi], band: Band) -> BTreeMap<u16, ChannelStats> {
+    let mut histogram: BTreeMap<u16, ChannelStats> = BTreeMap::new();
+    for wifi in wifis {
+        let channel = match wifi.channel_number() {
+            Some(channel) if wifi.band() == Some(band) => channel,
+            _ => continue,
+        };
+        let stats = histogram.entry(channel).or_default();
+        stats.network_count += 1;
+        if let Some(dbm) = wifi.signal_dbm() {
+            stats.strongest_dbm = Some(stats.strongest_dbm.map_or(dbm, |s| s.max(dbm)));
+            stats.summed_linear_power += dbm_to_mw(f64::from(dbm));
+        }
+    }
+    histogram
+}
+
+/// Recommends the least congested 20 MHz 5 GHz channel for an access point,
+/// the one where the networks seen add up to the least power in mW (see
+/// [`channel_histogram`]), the lowest channel on a tie. 5 GHz channels don't
+/// overlap, so only networks on the channel itself count. With `avoid_dfs`
+/// the DFS channels (see [`Wifi::is_dfs_channel`]) aren't considered, an
+/// access point on them must leave when it detects radar. `None` when no
+/// channel qualifies.
+pub fn recommend_channel_5g(wifis: &[Wifi], avoid_dfs: bool) -> Option<u16> {
+    let histogram = channel_histogram(wifis, Band::Band5GHz);
+    common_5ghz_channels()
+        .into_iter()
+        .filter(|&channel| !(avoid_dfs && is_dfs(channel)))
+        .map(|channel| {
+            let power = histogram
+                .get(&channel)
+                .map_or(0.0, |stats| stats.summed_linear_power);
+            (channel, power)
+        })
+        // the first of equally quiet channels wins
+        .reduce(|best, candidate| {
+            if candidate.1 < best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .map(|(channel, _)| channel)
+}
+
+/// How congested `band` is as a whole, from 0 (no network heard) towards 1
+/// (every channel saturated).
+///
+/// Each of the band's `n` channels gets a load `L = 1 - exp(-P / P_sat)`
+/// from the summed power `P` in mW of the networks seen on it (see
+/// [`channel_histogram`]), with `P_sat` the power of a -50 dBm signal. On a
+/// channel where no network has a signal level, each of its `N` networks
+/// counts as a -70 dBm one, `P = N * P(-70 dBm)`. A -60 dBm network loads
+/// its channel 0.10, ten of them 0.63. The index is the mean of the average
+/// and the highest load, `(ΣL / n + max L) / 2`, so a saturated channel
+/// counts even when the rest of the band is free: one -60 dBm network on
+/// 2.4 GHz gives 0.05, three on every channel 0.26 and 42 stacked on one
+/// channel 0.53. The channels are those of [`CHANNELS_2_4`],
+/// [`CHANNELS_6`] or the common 5 GHz channels [`recommend_channel_5g`]
+/// picks from, plus any other channel a network was seen on.
+///
+/// [`CHANNELS_2_4`]: crate::CHANNELS_2_4
+/// [`CHANNELS_6`]: crate::CHANNELS_6
+pub fn band_congestion_index(wifis: &[Wifi], band: Band) -> f64 {
+    let histogram = channel_histogram(wifis, band);
+    if histogram.is_empty() {
+        return 0.0;
+    }
+    let standard = match band {
+        Band::Band2_4GHz => CHANNELS_2_4.to_vec(),
+        Band::Band5GHz => common_5ghz_channels(),
+        Band::Band6GHz => CHANNELS_6.to_vec(),
+    };
+    let other = histogram
+        .keys()
+        .filter(|channel| !standard.contains(channel))
+        .count();
+    let channels = (standard.len() + other) as f64;
+
+    let saturation = dbm_to_mw(-50.0);
+    let loads: Vec<f64> = histogram
+        .values()
+        .map(|stats| {
+            let power = if stats.strongest_dbm.is_some() {
+                stats.summed_linear_power
+            } else {
+                stats.network_count as f64 * dbm_to_mw(-70.0)
+            };
+            1.0 - (-power / saturation).exp()
+        })
+        .collect();
+    let average = loads.iter().sum::<f64>() / channels;
+    let highest = loads.iter().copied().fold(0.0, f64::max);
+    (average + highest) / 2.0
+}
+
+/// Number of networks per band, returned by [`band_summary`]
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub struct BandSummary {
+    /// networks on 2.4 GHz
+    pub count_2_4: usize,
+    /// networks on 5 GHz
+    pub count_5: usize,
+    /// networks on 6 GHz
+    pub count_6: usize,
+    /// networks whose band isn't known, see [`Wifi::band`]
+    pub unknown: usize,
+}
+
+/// Counts the networks on each band, for a one-line overview of a scan.
+pub fn band_summary(wifis: &[Wifi]) -> BandSummary {
+    let mut summary = BandSummary::default();
+    for wifi in wifis {
+        let count = match wifi.band() {
+            Some(Band::Band2_4GHz) => &mut summary.count_2_4,
+            Some(Band::Band5GHz) => &mut summary.count_5,
+            Some(Band::Band6GHz) => &mut summary.count_6,
+            None => &mut summary.unknown,
+        };
+        *count += 1;
+    }
+    summary
+}
+
+/// Aggregate figures of a scan, for a quick report of the surroundings.
+/// Fields a backend left empty or that can't be parsed are left out of the
+/// figures they feed.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct ScanStats {
+    /// number of networks
+    pub total: usize,
+    /// number of different SSIDs, hidden networks left out
+    pub unique_ssids: usize,
+    /// networks per security scheme, see [`Wifi::security_kind`]
+    pub by_security: BTreeMap<SecurityKind, usize>,
+    /// networks whose security scheme isn't recognised
+    pub unknown_security: usize,
+    /// strongest signal in dBm
+    pub strongest_dbm: Option<i32>,
+    /// weakest signal in dBm
+    pub weakest_dbm: Option<i32>,
+    /// median signal in dBm, the mean of the middle two rounded down for an
+    /// even number of networks
+    pub median_dbm: Option<i32>,
+    /// channel number with the most networks and how many, the lowest
+    /// channel on a tie. Channels of different bands with the same number
+    /// count as one.
+    pub busiest_channel: Option<(u16, usize)>,
+}
+
+impl From<&[Wifi]> for ScanStats {
+    fn from(wifis: &[Wifi]) -> Self {
+        let mut stats = ScanStats {
+            total: wifis.len(),
+            unique_ssids: ssids(wifis, false).len(),
+            ..Default::default()
+        };
+        let mut signals = Vec::new();
+        let mut channels: BTreeMap<u16, usize> = BTreeMap::new();
+        for wifi in wifis {
+            match wifi.security_kind() {
+                Some(kind) => *stats.by_security.entry(kind).or_default() += 1,
+                None => stats.unknown_security += 1,
+            }
+            signals.extend(wifi.signal_dbm());
+            if let Some(channel) = wifi.channel_number() {
+                *channels.entry(channel).or_default() += 1;
+            }
+        }
+
+        signals.sort_unstable();
+        stats.weakest_dbm = signals.first().copied();
+        stats.strongest_dbm = signals.last().copied();
+        let middle = signals.len() / 2;
+        stats.median_dbm = match signals.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(signals[middle]),
+            _ => Some((signals[middle - 1] + signals[middle]).div_euclid(2)),
+        };
+        // max_by_key keeps the last maximum, reversing keeps the lowest channel
+        stats.busiest_channel = channels.into_iter().rev().max_by_key(|(_, count)| *count);
+        stats
+    }
+}
+
+/// Differences between two scans, returned by [`diff`]
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct ScanDiff {
+    /// networks only present in the current scan
+    pub appeared: Vec<Wifi>,
+    /// networks only present in the previous scan
+    pub disappeared: Vec<Wifi>,
+    /// networks whose signal moved, as (BSSID, previous dBm, current dBm)
+    pub signal_changes: Vec<(MacAddr, i32, i32)>,
+}
+
+/// Compares two scans by BSSID, reporting signal changes of at least
+/// [`DEFAULT_SIGNAL_DELTA_DBM`].
+///
+/// Networks without a valid mac address can't be matched up and are ignored.
+pub fn diff(previous: &[Wifi], current: &[Wifi]) -> ScanDiff {
+    diff_with_delta(previous, current, DEFAULT_SIGNAL_DELTA_DBM)
+}
+
+/// Same as [`diff`] but only reports signal changes of at least `min_delta_dbm`.
+pub fn diff_with_delta(previous: &[Wifi], current: &[Wifi], min_delta_dbm: i32) -> ScanDiff {
+    let by_mac = |wifis: &[Wifi]| -> HashMap<MacAddr, Wifi> {
+        wifis
+            .iter()
+            .filter_map(|wifi| Some((wifi.mac_addr()?, wifi.clone())))
+            .collect()
+    };
+    let before = by_mac(previous);
+    let after = by_mac(current);
+
+    let mut result = ScanDiff::default();
+    for wifi in current {
+        let Some(mac) = wifi.mac_addr() else {
+            continue;
+        };
+        match before.get(&mac) {
+            None => result.appeared.push(wifi.clone()),
+            Some(old) => {
+                if let (Some(from), Some(to)) = (old.signal_dbm(), wifi.signal_dbm()) {
+                    if (to - from).abs() >= min_delta_dbm {
+                        result.signal_changes.push((mac, from, to));
+                    }
+                }
+            }
+        }
+    }
+    result.disappeared = previous
+        .iter()
+        .filter(|wifi| wifi.mac_addr().is_some_and(|mac| !after.contains_key(&mac)))
+        .cloned()
+        .collect();
+    result
+}
+
+/// Compares the network names of two scans, returning the (added, removed)
+/// SSIDs, each sorted and listed once. Unlike [`diff`] a network moving to
+/// another access point or band isn't a change. Hidden networks are ignored.
+pub fn ssid_diff(previous: &[Wifi], current: &[Wifi]) -> (Vec<String>, Vec<String>) {
+    let before = ssids(previous, false);
+    let after = ssids(current, false);
+    let only_in = |a: &[String], b: &[String]| -> Vec<String> {
+        a.iter()
+            .filter(|ssid| b.binary_search(ssid).is_err())
+            .cloned()
+            .collect()
+    };
+    (only_in(&after, &before), only_in(&before, &after))
+}
+
+/// Clusters the networks whose BSSIDs only differ in their low `mask_bits`
+/// bits, e.g. the SSIDs an access point derives from one base BSSID by
+/// counting up the last octet (`mask_bits` 4 groups up to 16 of them).
+/// Groups keep the order networks were first seen in. Networks without a
+/// valid mac address aren't grouped and are left out.
+pub fn group_by_base_bssid(wifis: &[Wifi], mask_bits: u8) -> Vec<Vec<Wifi>> {
+    let mask = u64::MAX.checked_shl(u32::from(mask_bits)).unwrap_or(0);
+    let mut groups: Vec<(u64, Vec<Wifi>)> = Vec::new();
+    for wifi in wifis {
+        let Some(MacAddr(octets)) = wifi.mac_addr() else {
+            continue;
+        };
+        let base = octets.iter().fold(0, |acc, &o| (acc << 8) | u64::from(o)) & mask;
+        match groups.iter_mut().find(|(b, _)| *b == base) {
+            Some((_, group)) => group.push(wifi.clone()),
+            None => groups.push((base, vec![wifi.clone()])),
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// How two hotspots near each other's channel get in each other's way, see
+/// [`interference_report`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterferenceKind {
+    /// same channel: the hotspots hear each other and take turns
+    CoChannel,
+    /// overlapping 2.4 GHz channels: their frames collide and get corrupted
+    AdjacentChannel,
+}
+
+/// Rough severity of an [`InterferencePair`], from least to most harmful
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InterferenceSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+/// Two hotspots interfering with each other, returned by
+/// [`interference_report`]
+#[derive(Debug, PartialEq, Clone)]
+pub struct InterferencePair {
+    pub first: Wifi,
+    pub second: Wifi,
+    pub kind: InterferenceKind,
+    /// difference of the channel numbers, 0 for [`InterferenceKind::CoChannel`]
+    pub channel_distance: u16,
+    pub severity: InterferenceSeverity,
+}
+
+/// Lists the pairs of hotspots whose channels overlap, the most severe
+/// first and pairs of the same severity in scan order.
+///
+/// Channels are taken as 20 MHz wide. Hotspots on the same channel of a
+/// band interfere co-channel. On 2.4 GHz, channels less than 5 apart
+/// overlap and interfere adjacent-channel, so 1, 6 and 11 don't. The
+/// channels of the other bands don't overlap. Hotspots without a known
+/// channel and band are left out.
+///
+/// The severity comes from the weaker of the two signals, as a hotspot
+/// barely heard here disturbs little: `High` at -67 dBm or more, `Medium`
+/// at -80 dBm or more, `Low` below that or when a signal isn't known.
+/// Adjacent channels up to 2 apart rank one level higher: unlike co-channel
+/// hotspots, which defer to each other, they can't decode each other's
+/// frames and talk over them.
+pub fn interference_report(wifis: &[Wifi]) -> Vec<InterferencePair> {
+    let located: Vec<(&Wifi, Band, u16)> = wifis
+        .iter()
+        .filter_map(|wifi| Some((wifi, wifi.band()?, wifi.channel_number()?)))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (i, &(first, band, channel)) in located.iter().enumerate() {
+        for &(second, other_band, other_channel) in &located[i + 1..] {
+            let distance = channel.abs_diff(other_channel);
+            let kind = match band {
+                _ if band != other_band => continue,
+                _ if distance == 0 => InterferenceKind::CoChannel,
+                Band::Band2_4GHz if distance < 5 => InterferenceKind::AdjacentChannel,
+                _ => continue,
+            };
+            let weaker = first
+                .signal_dbm()
+                .zip(second.signal_dbm())
+                .map(|(a, b)| a.min(b));
+            let mut severity = match weaker {
+                Some(dbm) if dbm >= -67 => InterferenceSeverity::High,
+                Some(dbm) if dbm >= -80 => InterferenceSeverity::Medium,
+                _ => InterferenceSeverity::Low,
+            };
+            if kind == InterferenceKind::AdjacentChannel && distance <= 2 {
+                severity = match severity {
+                    InterferenceSeverity::Low => InterferenceSeverity::Medium,
+                    _ => InterferenceSeverity::High,
+                };
+            }
+            pairs.push(InterferencePair {
+                first: first.clone(),
+                second: second.clone(),
+                kind,
+                channel_distance: distance,
+                severity,
+            });
+        }
+    }
+    // stable, so pairs of the same severity stay in scan order
+    pairs.sort_by_key(|pair| std::cmp::Reverse(pair.severity));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(channel: &str, signal_level: &str) -> Wifi {
+        Wifi {
+            channel: channel.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_recommend_5ghz_channel() {
+        // every non-DFS channel is busy, channel 40 the least, and only 52
+        // among the DFS ones
+        let mut wifis: Vec<Wifi> = [36, 44, 48, 149, 153, 157, 161, 165]
+            .iter()
+            .map(|channel| wifi(&channel.to_string(), "-60"))
+            .collect();
+        // two weak networks weigh less than a strong one
+        wifis.push(wifi("40", "-85"));
+        wifis.push(wifi("40", "-85"));
+        wifis.push(wifi("52", "-50"));
+        assert_eq!(Some(40), recommend_channel_5g(&wifis, true));
+        assert_eq!(Some(56), recommend_channel_5g(&wifis, false));
+
+        // 2.4 GHz networks don't count, ties go to the lowest channel
+        let two_four = vec![wifi("1", "-30"), wifi("36", "-80")];
+        assert_eq!(Some(40), recommend_channel_5g(&two_four, true));
+        assert_eq!(Some(36), recommend_channel_5g(&[], true));
+    }
+
+    #[test]
+    fn should_compute_band_congestion_index() {
+        let two_four = |channel: u16| wifi(&channel.to_string(), "-60");
+        assert_eq!(0.0, band_congestion_index(&[], Band::Band2_4GHz));
+        // 5 GHz networks don't load the 2.4 GHz band
+        assert_eq!(
+            0.0,
+            band_congestion_index(&[wifi("36", "-40")], Band::Band2_4GHz)
+        );
+
+        // load of a channel where the networks add up to `mw`
+        let load = |mw: f64| 1.0 - (-mw / 1e-5).exp();
+        let lone = band_congestion_index(&[two_four(6)], Band::Band2_4GHz);
+        let mut crowded = Vec::new();
+        for channel in 1..=14 {
+            crowded.extend([two_four(channel), two_four(channel), two_four(channel)]);
+        }
+        let crowded = band_congestion_index(&crowded, Band::Band2_4GHz);
+        let stacked = band_congestion_index(&vec![two_four(6); 42], Band::Band2_4GHz);
+        assert!((lone - (load(1e-6) / 14.0 + load(1e-6)) / 2.0).abs() < 1e-9);
+        assert!(lone < 0.06);
+        assert!((crowded - load(3e-6)).abs() < 1e-9);
+        // a saturated channel makes the band congested even when the others
+        // are free
+        assert!((stacked - (load(42e-6) / 14.0 + load(42e-6)) / 2.0).abs() < 1e-9);
+        assert!(lone < crowded && crowded < stacked && stacked < 1.0);
+        assert!(stacked > 0.5);
+
+        // a stronger network loads its channel more
+        let near = band_congestion_index(&[wifi("6", "-40")], Band::Band2_4GHz);
+        assert!(near > lone);
+        // without any signal level the networks are counted as -70 dBm ones
+        let unknown = band_congestion_index(&vec![wifi("6", ""); 3], Band::Band2_4GHz);
+        assert!((unknown - (load(3e-7) / 14.0 + load(3e-7)) / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn should_report_interference() {
+        let named = |ssid: &str, channel: &str, signal_level: &str| Wifi {
+            ssid: ssid.to_string(),
+            ..wifi(channel, signal_level)
+        };
+        let wifis = vec![
+            named("home", "1", "-50"),
+            named("neighbour", "1", "-75"),
+            named("upstairs", "3", "-60"),
+            named("cafe", "6", "-55"),
+            named("far", "5", "-88"),
+            named("office", "36", "-60"),
+            named("lab", "36", "-62"),
+            named("next door", "40", "-50"),
+            named("no signal", "11", ""),
+            named("bridge", "11", "-40"),
+            named("unknown", "", "-40"),
+        ];
+        let report = interference_report(&wifis);
+        let summary: Vec<(&str, &str, InterferenceKind, u16, InterferenceSeverity)> = report
+            .iter()
+            .map(|pair| {
+                (
+                    pair.first.ssid.as_str(),
+                    pair.second.ssid.as_str(),
+                    pair.kind,
+                    pair.channel_distance,
+                    pair.severity,
+                )
+            })
+            .collect();
+
+        use InterferenceKind::{AdjacentChannel, CoChannel};
+        use InterferenceSeverity::{High, Low, Medium};
+        assert_eq!(
+            vec![
+                // overlapping by 2 channels or less ranks a level higher
+                ("home", "upstairs", AdjacentChannel, 2, High),
+                ("neighbour", "upstairs", AdjacentChannel, 2, High),
+                ("upstairs", "cafe", AdjacentChannel, 3, High),
+                ("office", "lab", CoChannel, 0, High),
+                ("home", "neighbour", CoChannel, 0, Medium),
+                ("upstairs", "far", AdjacentChannel, 2, Medium),
+                ("cafe", "far", AdjacentChannel, 1, Medium),
+                ("home", "far", AdjacentChannel, 4, Low),
+                ("neighbour", "far", AdjacentChannel, 4, Low),
+                ("no signal", "bridge", CoChannel, 0, Low),
+            ],
+            summary
+        );
+        assert!(interference_report(&wifis[..1]).is_empty());
+    }
+
+    #[test]
+    fn should_compute_scan_stats() {
+        let data = std::fs::read_to_string("tests/fixtures/airport/airport_stats.txt").unwrap();
+        let wifis = crate::parse_airport(&data).unwrap();
+        let stats = ScanStats::from(wifis.as_slice());
+
+        assert_eq!(7, stats.total);
+        assert_eq!(6, stats.unique_ssids);
+        assert_eq!(
+            BTreeMap::from([
+                (SecurityKind::Open, 1),
+                (SecurityKind::Wep, 1),
+                (SecurityKind::Wpa2, 3),
+                (SecurityKind::Wpa3, 1),
+            ]),
+            stats.by_security
+        );
+        assert_eq!(1, stats.unknown_security);
+        assert_eq!(Some(-45), stats.strongest_dbm);
+        assert_eq!(Some(-88), stats.weakest_dbm);
+        assert_eq!(Some(-67), stats.median_dbm);
+        assert_eq!(Some((6, 3)), stats.busiest_channel);
+
+        // the mean of the middle two, ties go to the lowest channel
+        let wifis = [wifi("11", "-60"), wifi("1", "-71"), wifi("", "")];
+        let stats = ScanStats::from(&wifis[..]);
+        assert_eq!(Some(-66), stats.median_dbm);
+        assert_eq!(Some((1, 1)), stats.busiest_channel);
+        assert_eq!(ScanStats::default(), ScanStats::from(&[][..]));
+    }
+
+    #[test]
+    fn should_aggregate_per_channel() {
+        let wifis = vec![
+            wifi("1", "-40"),
+            wifi("1", "-50"),
+            wifi("6", "-70"),
+            wifi("6", ""),
+            wifi("36", "-60"),
+            wifi("", "-30"),
+        ];
+
+        let histogram = channel_histogram(&wifis, Band::Band2_4GHz);
+        assert_eq!(vec![1, 6], histogram.keys().copied().collect::<Vec<_>>());
+
+        let ch1 = &histogram[&1];
+        assert_eq!(2, ch1.network_count);
+        assert_eq!(Some(-40), ch1.strongest_dbm);
+        assert!((ch1.summed_linear_power - 0.000_11).abs() < 1e-12);
+
+        let ch6 = &histogram[&6];
+        assert_eq!(2, ch6.network_count);
+        assert_eq!(Some(-70), ch6.strongest_dbm);
+        assert!((ch6.summed_linear_power - 1e-7).abs() < 1e-15);
+
+        let histogram = channel_histogram(&wifis, Band::Band5GHz);
+        assert_eq!(1, histogram.len());
+        assert_eq!(1, histogram[&36].network_count);
+    }
+
+    #[test]
+    fn should_count_networks_per_band() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_bands.txt").unwrap();
+        let wifis = crate::parse_nmcli(&data).unwrap();
+
+        assert_eq!(
+            BandSummary {
+                count_2_4: 3,
+                count_5: 2,
+                count_6: 1,
+                unknown: 1,
+            },
+            band_summary(&wifis)
+        );
+        assert_eq!(BandSummary::default(), band_summary(&[]));
+    }
+
+    #[test]
+    fn should_diff_scans() {
+        let with_mac = |mac: &str, signal_level: &str| Wifi {
+            mac: mac.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        };
+        let previous = vec![
+            with_mac("00:00:00:00:00:01", "-50"),
+            with_mac("00:00:00:00:00:02", "-60"),
+            with_mac("00:00:00:00:00:03", "-70"),
+        ];
+        let current = vec![
+            with_mac("00:00:00:00:00:02", "-62"),
+            with_mac("00:00:00:00:00:03", "-80"),
+            with_mac("00:00:00:00:00:04", "-40"),
+        ];
+
+        let result = diff(&previous, &current);
+        assert_eq!(vec![current[2].clone()], result.appeared);
+        assert_eq!(vec![previous[0].clone()], result.disappeared);
+        assert_eq!(
+            vec![("00:00:00:00:00:03".parse().unwrap(), -70, -80)],
+            result.signal_changes
+        );
+
+        let result = diff_with_delta(&previous, &current, 1);
+        assert_eq!(2, result.signal_changes.len());
+    }
+
+    #[test]
+    fn should_diff_ssids() {
+        let fixture = |name: &str| {
+            let data = std::fs::read_to_string(format!("tests/fixtures/nmcli/{name}")).unwrap();
+            crate::parse_nmcli(&data).unwrap()
+        };
+        let previous = fixture("nmcli01.txt");
+        let current = fixture("nmcli_ssids.txt");
+
+        let (added, removed) = ssid_diff(&previous, &current);
+        assert_eq!(vec!["cafe", "office"], added);
+        assert_eq!(vec!["Cafe Guest", "hello"], removed);
+
+        assert_eq!((vec![], vec![]), ssid_diff(&current, &current));
+    }
+
+    #[test]
+    fn should_group_by_base_bssid() {
+        let with_mac = |mac: &str| Wifi {
+            mac: mac.to_string(),
+            ..Default::default()
+        };
+        let wifis = vec![
+            with_mac("00:11:22:33:44:50"),
+            with_mac("aa:bb:cc:dd:ee:01"),
+            with_mac("00:11:22:33:44:51"),
+            with_mac("not a mac"),
+            with_mac("00:11:22:33:44:5f"),
+            with_mac("00:11:22:33:44:60"),
+        ];
+        let macs = |groups: Vec<Vec<Wifi>>| -> Vec<Vec<String>> {
+            groups
+                .into_iter()
+                .map(|group| group.into_iter().map(|wifi| wifi.mac).collect())
+                .collect()
+        };
+
+        assert_eq!(
+            vec![
+                vec![
+                    "00:11:22:33:44:50",
+                    "00:11:22:33:44:51",
+                    "00:11:22:33:44:5f"
+                ],
+                vec!["aa:bb:cc:dd:ee:01"],
+                vec!["00:11:22:33:44:60"],
+            ],
+            macs(group_by_base_bssid(&wifis, 4))
+        );
+        assert_eq!(2, group_by_base_bssid(&wifis, 8).len());
+        assert_eq!(5, group_by_base_bssid(&wifis, 0).len());
+        assert_eq!(1, group_by_base_bssid(&wifis, 64).len());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+use crate::{MacAddr, Wifi};
+
+/// Scrubs what could identify the place a scan was made in, so the scan can
+/// be shared:
+///
+/// - each SSID becomes `ssid-` and a hash, the same for every hotspot with
+///   that SSID; hidden SSIDs stay empty. Mesh IDs, the SSIDs of mesh
+///   backhaul links, are hashed the same way.
+/// - each BSSID gets a made up device part, and a made up OUI too unless
+///   `keep_oui`. Its lowest 4 bits, and its locally administered and
+///   multicast bits, are kept, so the SSIDs of one access point still group
+///   together, see [`crate::group_by_base_bssid`]. Mac addresses that don't
+///   parse are cleared.
+/// - the raw SSID, device name and location are cleared
+///
+/// The hashes are keyed with a random key for each call, so they can't be
+/// reversed by hashing a list of SSIDs, but two calls give different
+/// results: anonymize a dataset in one go.
+pub fn anonymize(wifis: &mut [Wifi], keep_oui: bool) {
+    let key = RandomState::new();
+    for wifi in wifis.iter_mut() {
+        if !wifi.is_hidden() {
+            wifi.ssid = anonymize_ssid(&key, &wifi.ssid);
+        }
+        if let Some(mesh) = &mut wifi.mesh {
+            mesh.mesh_id = anonymize_ssid(&key, &mesh.mesh_id);
+        }
+        wifi.mac = wifi
+            .mac_addr()
+            .map(|mac| anonymize_mac(&key, mac, keep_oui).to_string())
+            .unwrap_or_default();
+        wifi.transmitted_bssid = wifi
+            .transmitted_bssid
+            .map(|mac| anonymize_mac(&key, mac, keep_oui));
+        wifi.ssid_raw = None;
+        wifi.device_name = None;
+        wifi.location = None;
+    }
+}
+
+/// `ssid-` and a hash of `ssid`, the same for the same SSID
+fn anonymize_ssid(key: &impl BuildHasher, ssid: &str) -> String {
+    format!("ssid-{:08x}", key.hash_one(ssid) as u32)
+}
+
+/// Replaces the bits of `mac` [`anonymize`] doesn't keep with a hash of them
+fn anonymize_mac(key: &impl BuildHasher, MacAddr(mac): MacAddr, keep_oui: bool) -> MacAddr {
+    let mut base = mac;
+    base[5] &= 0xf0;
+    let digest = key.hash_one(base).to_be_bytes();
+    let device = [digest[0], digest[1], (digest[2] & 0xf0) | (mac[5] & 0x0f)];
+    let oui = if keep_oui {
+        [mac[0], mac[1], mac[2]]
+    } else {
+        [(digest[3] & 0xfc) | (mac[0] & 0x03), digest[4], digest[5]]
+    };
+    MacAddr([oui[0], oui[1], oui[2], device[0], device[1], device[2]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MeshInfo;
+
+    fn wifi(mac: &str, ssid: &str) -> Wifi {
+        Wifi {
+            mac: mac.to_string(),
+            ssid: ssid.to_string(),
+            device_name: Some("Lobby AP".to_string()),
+            location: Some((51.5007, -0.1246)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_anonymize_consistently() {
+        let mut wifis = vec![
+            wifi("f0:9f:c2:12:34:50", "Office"),
+            wifi("F0:9F:C2:12:34:51", "Office-Guest"),
+            wifi("3c:84:6a:ab:cd:ef", "Office"),
+            wifi("3c:84:6a:ab:cd:e0", ""),
+            wifi("not a mac", "Home"),
+            Wifi {
+                mesh: Some(MeshInfo {
+                    mesh_id: "Office".to_string(),
+                    active_path_protocol: Some(1),
+                }),
+                ..wifi("3c:84:6a:00:00:01", "")
+            },
+        ];
+        anonymize(&mut wifis, true);
+
+        assert_eq!(wifis[0].ssid, wifis[2].ssid);
+        assert_ne!(wifis[0].ssid, wifis[1].ssid);
+        assert!(wifis[0].ssid.starts_with("ssid-"));
+        assert!(!wifis[0].ssid.contains("Office"));
+        assert_eq!("", wifis[3].ssid);
+        let mesh = wifis[5].mesh.as_ref().unwrap();
+        assert_eq!(wifis[0].ssid, mesh.mesh_id);
+        assert_eq!(Some(1), mesh.active_path_protocol);
+
+        let macs: Vec<MacAddr> = wifis[..4].iter().map(|w| w.mac_addr().unwrap()).collect();
+        assert_eq!([0xf0, 0x9f, 0xc2], macs[0].0[..3]);
+        assert_eq!([0x3c, 0x84, 0x6a], macs[2].0[..3]);
+        assert_ne!("f0:9f:c2:12:34:50", wifis[0].mac);
+        // the SSIDs of one access point still share a base BSSID
+        assert_eq!(macs[0].0[..5], macs[1].0[..5]);
+        assert_eq!([0x0, 0x1], [macs[0].0[5] & 0x0f, macs[1].0[5] & 0x0f]);
+        assert_eq!(3, crate::group_by_base_bssid(&wifis, 4).len());
+        assert_eq!("", wifis[4].mac);
+
+        assert!(wifis
+            .iter()
+            .all(|w| w.location.is_none() && w.device_name.is_none()));
+    }
+
+    #[test]
+    fn should_anonymize_oui() {
+        let mut wifis = vec![
+            wifi("02:11:22:33:44:55", "mesh"),
+            wifi("00:11:22:33:44:56", "home"),
+        ];
+        anonymize(&mut wifis, false);
+
+        let macs: Vec<MacAddr> = wifis.iter().map(|w| w.mac_addr().unwrap()).collect();
+        assert!(macs[0].is_locally_administered());
+        assert!(!macs[1].is_locally_administered());
+        assert!(!macs[0].is_multicast() && !macs[1].is_multicast());
+        assert_eq!(0x5, macs[0].0[5] & 0x0f);
+    }
+}
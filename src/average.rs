@@ -0,0 +1,113 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::signal::mw_to_dbm;
+use crate::Wifi;
+
+/// Scans `samples` times, waiting `gap` between scans, and returns each
+/// hotspot once with its signal level averaged over the scans it showed up
+/// in. The average is taken in milliwatts and converted back to dBm, so a
+/// single strong reading counts for more than in a plain mean of dBm. The
+/// other fields come from the latest scan the hotspot was in.
+pub fn scan_averaged(samples: usize, gap: Duration) -> anyhow::Result<Vec<Wifi>> {
+    scan_averaged_with(samples, gap, crate::scan)
+}
+
+fn scan_averaged_with<F>(samples: usize, gap: Duration, mut scan: F) -> anyhow::Result<Vec<Wifi>>
+where
+    F: FnMut() -> anyhow::Result<Vec<Wifi>>,
+{
+    anyhow::ensure!(samples > 0, "scan_averaged needs at least one sample");
+    let mut scans = Vec::with_capacity(samples);
+    for i in 0..samples {
+        if i > 0 {
+            thread::sleep(gap);
+        }
+        scans.push(scan()?);
+    }
+    Ok(average_scans(scans))
+}
+
+/// Merges the scans, keeping the hotspots in the order they were first seen
+pub(crate) fn average_scans(scans: Vec<Vec<Wifi>>) -> Vec<Wifi> {
+    // (latest entry, summed power in mW, number of readings)
+    let mut merged: Vec<(Wifi, f64, u32)> = Vec::new();
+    for wifi in scans.into_iter().flatten() {
+        let power = wifi.signal_mw();
+        let entry = match merged
+            .iter_mut()
+            .find(|(seen, _, _)| same_hotspot(seen, &wifi))
+        {
+            Some(entry) => {
+                entry.0 = wifi;
+                entry
+            }
+            None => {
+                merged.push((wifi, 0.0, 0));
+                merged.last_mut().unwrap()
+            }
+        };
+        if let Some(power) = power {
+            entry.1 += power;
+            entry.2 += 1;
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(mut wifi, power, readings)| {
+            if readings > 0 {
+                let dbm = mw_to_dbm(power / f64::from(readings));
+                wifi.signal_level = format!("{:.0}", dbm);
+            }
+            wifi
+        })
+        .collect()
+}
+
+/// Same BSSID, or same SSID and channel for backends without BSSIDs
+fn same_hotspot(a: &Wifi, b: &Wifi) -> bool {
+    if a.mac.is_empty() && b.mac.is_empty() {
+        a.ssid == b.ssid && a.channel == b.channel
+    } else {
+        a.mac.eq_ignore_ascii_case(&b.mac)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(mac: &str, signal_level: &str) -> Wifi {
+        Wifi {
+            mac: mac.to_string(),
+            ssid: format!("ssid-{mac}"),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_average_in_linear_power() {
+        let mut scans = vec![
+            vec![wifi("aa", "-40"), wifi("bb", "-70")],
+            vec![wifi("aa", "-50")],
+            vec![wifi("bb", "-70"), wifi("aa", "-60"), wifi("cc", "-80")],
+        ]
+        .into_iter();
+        let wifis = scan_averaged_with(3, Duration::ZERO, || Ok(scans.next().unwrap())).unwrap();
+
+        let signals: Vec<(&str, &str)> = wifis
+            .iter()
+            .map(|w| (w.mac.as_str(), w.signal_level.as_str()))
+            .collect();
+        // mean of 1e-4, 1e-5 and 1e-6 mW is 3.7e-5 mW, -44.3 dBm, where the
+        // mean of the dBm values would have been -50
+        assert_eq!(vec![("aa", "-44"), ("bb", "-70"), ("cc", "-80")], signals);
+    }
+
+    #[test]
+    fn should_need_a_sample() {
+        assert!(scan_averaged_with(0, Duration::ZERO, || Ok(vec![])).is_err());
+    }
+}
@@ -0,0 +1,497 @@
+use std::cell::Cell;
+use std::env;
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::{BackendCapabilities, Wifi, WifiError};
+
+/// A way of scanning for hotspots. The crate ships one per tool it knows
+/// (`nmcli`, `iw`, `system_profiler`, ...), [`register_backend`] adds more.
+pub trait ScanBackend: Send + Sync {
+    /// Short name for logs, e.g. `nmcli`
+    fn name(&self) -> &str;
+
+    /// Whether the backend can run here, e.g. because its tool is installed
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError>;
+
+    /// Like [`ScanBackend::scan`], also returning how many entries of the
+    /// output couldn't be parsed and were left out.
+    fn scan_partial(&self) -> Result<(Vec<Wifi>, usize), WifiError> {
+        Ok((self.scan()?, 0))
+    }
+
+    /// Whether the backend's tool is deprecated and may be dropped by the
+    /// OS, e.g. macOS `airport`
+    fn is_deprecated(&self) -> bool {
+        false
+    }
+
+    /// Which [`Wifi`] fields the backend fills in, see
+    /// [`crate::backend_capabilities`]. None unless the backend tells.
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::default()
+    }
+}
+
+/// How a scan went, returned by [`scan_with_meta`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScanMeta {
+    /// name of the backend that gave the result, e.g. `nmcli`
+    pub backend_used: String,
+    /// whether that backend is deprecated, see [`ScanBackend::is_deprecated`]
+    pub deprecated: bool,
+    /// things worth telling the user, e.g. that backends tried before
+    /// failed or that some entries couldn't be parsed
+    pub warnings: Vec<String>,
+    /// how long the successful backend took, from a monotonic clock
+    pub duration: Duration,
+    /// part of `duration` spent waiting for the backend's command. Zero for
+    /// registered backends, the crate can't tell what they spend on what.
+    pub command_duration: Duration,
+    /// rest of `duration`, spent parsing the output
+    pub parse_duration: Duration,
+    /// every backend tried in order with how it went, e.g.
+    /// `["nmcli: failed", "iw: ok"]`. More than one entry means the scan fell
+    /// back.
+    pub fallback_chain: Vec<String>,
+}
+
+thread_local! {
+    /// Time spent running commands since the current backend started
+    static COMMAND_TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Counts `elapsed` as time spent running a command, for
+/// [`ScanMeta::command_duration`]
+pub(crate) fn add_command_time(elapsed: Duration) {
+    COMMAND_TIME.with(|time| time.set(time.get() + elapsed));
+}
+
+/// Same as [`crate::scan`], also telling which backend was used and what
+/// went wrong on the way, so an app can warn when it fell back to a
+/// deprecated tool.
+pub fn scan_with_meta() -> anyhow::Result<(Vec<Wifi>, ScanMeta)> {
+    let (wifis, _, meta) = scan_partial_meta()?;
+    Ok((wifis, meta))
+}
+
+static REGISTRY: RwLock<Vec<Arc<dyn ScanBackend>>> = RwLock::new(Vec::new());
+
+/// Adds a backend for [`crate::scan`] to use. Registered backends are tried
+/// in the order they were registered, before the built-in ones.
+pub fn register_backend(backend: impl ScanBackend + 'static) {
+    REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Arc::new(backend));
+}
+
+/// Returns the registered backends followed by the built-in ones for this OS
+pub(crate) fn backends() -> Vec<Arc<dyn ScanBackend>> {
+    let mut backends = REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    backends.extend(crate::sys::builtin_backends());
+    backends
+}
+
+/// Environment variable naming the only backend to scan with, e.g. `iw`
+pub const BACKEND_ENV: &str = "WIFISCANNER_BACKEND";
+
+/// Scans with the first available backend, moving on to the next one when a
+/// backend fails. Returns the last error when they all fail.
+///
+/// When [`BACKEND_ENV`] is set only the backend it names is used, failing
+/// with [`WifiError::BackendUnavailable`] if there is no such backend or it
+/// can't run here.
+pub(crate) fn scan_partial() -> Result<(Vec<Wifi>, usize), WifiError> {
+    let (wifis, skipped, _) = scan_partial_meta()?;
+    Ok((wifis, skipped))
+}
+
+/// The registered and built-in backends taken together, scanning like
+/// [`crate::scan`], so code scanning with a [`ScanBackend`] can be tested
+/// with a [`crate::ReplayBackend`]
+pub(crate) struct DefaultBackend;
+
+impl ScanBackend for DefaultBackend {
+    fn name(&self) -> &str {
+        "default"
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        Ok(scan_partial()?.0)
+    }
+
+    fn scan_partial(&self) -> Result<(Vec<Wifi>, usize), WifiError> {
+        scan_partial()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        selected_capabilities()
+    }
+}
+
+fn scan_partial_meta() -> Result<(Vec<Wifi>, usize, ScanMeta), WifiError> {
+    crate::rate_limit::rate_limited(scan_available)
+}
+
+/// Scans with the backend [`BACKEND_ENV`] names, or the available ones
+fn scan_available() -> Result<(Vec<Wifi>, usize, ScanMeta), WifiError> {
+    scan_backends(candidates(backends(), env::var(BACKEND_ENV).ok())?)
+}
+
+/// The capabilities of the backend [`crate::scan`] would try first
+pub(crate) fn selected_capabilities() -> BackendCapabilities {
+    capabilities_of(backends(), env::var(BACKEND_ENV).ok())
+}
+
+/// The capabilities of the first of the [`candidates`], none when there is
+/// no such backend
+fn capabilities_of(
+    backends: Vec<Arc<dyn ScanBackend>>,
+    name: Option<String>,
+) -> BackendCapabilities {
+    candidates(backends, name)
+        .ok()
+        .and_then(|candidates| Some(candidates.first()?.capabilities()))
+        .unwrap_or_default()
+}
+
+/// The `backends` to try in turn: only the one `name` asks for when it is
+/// set, else the available ones
+fn candidates(
+    backends: Vec<Arc<dyn ScanBackend>>,
+    name: Option<String>,
+) -> Result<Vec<Arc<dyn ScanBackend>>, WifiError> {
+    match name {
+        Some(name) if !name.is_empty() => {
+            let backend = backends
+                .into_iter()
+                .find(|backend| backend.name() == name)
+                .filter(|backend| backend.is_available())
+                .ok_or(WifiError::BackendUnavailable(name))?;
+            tracing::debug!("{BACKEND_ENV} asks for {}", backend.name());
+            Ok(vec![backend])
+        }
+        _ => Ok(backends
+            .into_iter()
+            .filter(|backend| backend.is_available())
+            .collect()),
+    }
+}
+
+/// Scans with the first of `candidates` that succeeds
+fn scan_backends(
+    candidates: Vec<Arc<dyn ScanBackend>>,
+) -> Result<(Vec<Wifi>, usize, ScanMeta), WifiError> {
+    let mut error = WifiError::CommandNotFound;
+    let mut warnings = Vec::new();
+    let mut fallback_chain = Vec::new();
+    for backend in candidates {
+        tracing::debug!("scanning with {}", backend.name());
+        COMMAND_TIME.with(|time| time.set(Duration::ZERO));
+        let start = Instant::now();
+        let result = backend.scan_partial();
+        let duration = start.elapsed();
+        match result {
+            Ok((wifis, skipped)) => {
+                fallback_chain.push(format!("{}: ok", backend.name()));
+                if backend.is_deprecated() {
+                    warnings.push(format!(
+                        "using deprecated {}, results may be incomplete",
+                        backend.name()
+                    ));
+                }
+                if skipped > 0 {
+                    warnings.push(format!("{skipped} entries could not be parsed"));
+                }
+                let command_duration = COMMAND_TIME.with(Cell::get).min(duration);
+                let meta = ScanMeta {
+                    backend_used: backend.name().to_string(),
+                    deprecated: backend.is_deprecated(),
+                    warnings,
+                    duration,
+                    command_duration,
+                    parse_duration: duration.saturating_sub(command_duration),
+                    fallback_chain,
+                };
+                return Ok((wifis, skipped, meta));
+            }
+            Err(e) => {
+                tracing::debug!("{} failed: {e}", backend.name());
+                warnings.push(format!("{} failed: {e}", backend.name()));
+                fallback_chain.push(format!("{}: failed", backend.name()));
+                error = e;
+            }
+        }
+    }
+    Err(error)
+}
+
+/// Whether `program` is in the `PATH` or in the sbin directories, which often
+/// aren't in a regular user's `PATH`
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+pub(crate) fn command_exists(program: &str) -> bool {
+    let path = env::var_os("PATH").unwrap_or_default();
+    env::split_paths(&path)
+        .chain(["/usr/sbin", "/sbin"].map(Into::into))
+        .any(|dir| is_executable(&dir.join(program)))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Platform;
+
+    struct Fake {
+        name: &'static str,
+        available: bool,
+    }
+
+    impl ScanBackend for Fake {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn is_available(&self) -> bool {
+            self.available
+        }
+
+        fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+            Ok(vec![Wifi {
+                ssid: self.name.to_string(),
+                ..Default::default()
+            }])
+        }
+    }
+
+    fn fake(name: &'static str, available: bool) -> Arc<dyn ScanBackend> {
+        Arc::new(Fake { name, available })
+    }
+
+    fn names(backends: &[Arc<dyn ScanBackend>]) -> Vec<&str> {
+        backends.iter().map(|backend| backend.name()).collect()
+    }
+
+    #[test]
+    fn should_scan_with_first_available_backend() {
+        let backends = vec![
+            fake("unavailable", false),
+            fake("fake", true),
+            fake("later", true),
+        ];
+
+        let candidates = candidates(backends, None).unwrap();
+        assert_eq!(vec!["fake", "later"], names(&candidates));
+        let (wifis, _, _) = scan_backends(candidates).unwrap();
+        assert_eq!(1, wifis.len());
+        assert_eq!("fake", wifis[0].ssid);
+    }
+
+    struct Builtin {
+        name: &'static str,
+        platform: Platform,
+        available: bool,
+    }
+
+    impl ScanBackend for Builtin {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn is_available(&self) -> bool {
+            self.available
+        }
+
+        fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+            Ok(Vec::new())
+        }
+
+        fn capabilities(&self) -> BackendCapabilities {
+            self.platform.capabilities()
+        }
+    }
+
+    #[test]
+    fn should_tell_capabilities_of_backend_that_would_scan() {
+        let builtin = |name, platform, available| -> Arc<dyn ScanBackend> {
+            Arc::new(Builtin {
+                name,
+                platform,
+                available,
+            })
+        };
+        let backends = || {
+            vec![
+                builtin("nmcli", Platform::LinuxNmcli, false),
+                builtin("iw", Platform::LinuxIw, true),
+                builtin("iwlist", Platform::LinuxIwlist, true),
+            ]
+        };
+
+        let iw = capabilities_of(backends(), None);
+        assert_eq!(Platform::LinuxIw.capabilities(), iw);
+        assert!(iw.bss_color);
+        let iwlist = capabilities_of(backends(), Some("iwlist".to_string()));
+        assert_eq!(Platform::LinuxIwlist.capabilities(), iwlist);
+        assert!(!iwlist.bss_color);
+        assert_eq!(
+            BackendCapabilities::default(),
+            capabilities_of(backends(), Some("nmcli".to_string()))
+        );
+
+        let mut registered = vec![fake("fake", true)];
+        registered.extend(backends());
+        assert_eq!(
+            BackendCapabilities::default(),
+            capabilities_of(registered, None)
+        );
+    }
+
+    #[test]
+    fn should_scan_with_backend_from_env() {
+        let backends = || {
+            vec![
+                fake("fake", true),
+                fake("forced", true),
+                fake("forced-unavailable", false),
+            ]
+        };
+
+        let forced = candidates(backends(), Some("forced".to_string())).unwrap();
+        assert_eq!(vec!["forced"], names(&forced));
+        assert_eq!(
+            vec!["fake", "forced"],
+            names(&candidates(backends(), Some(String::new())).unwrap())
+        );
+        assert_eq!(
+            Some(WifiError::BackendUnavailable(
+                "forced-unavailable".to_string()
+            )),
+            candidates(backends(), Some("forced-unavailable".to_string())).err()
+        );
+        assert_eq!(
+            Some(WifiError::BackendUnavailable("wpa_cli".to_string())),
+            candidates(backends(), Some("wpa_cli".to_string())).err()
+        );
+    }
+
+    struct Deprecated;
+
+    impl ScanBackend for Deprecated {
+        fn name(&self) -> &str {
+            "deprecated"
+        }
+
+        fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+            Ok(vec![Wifi::default()])
+        }
+
+        fn is_deprecated(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn should_flag_deprecated_backend() {
+        let (wifis, _, meta) = scan_backends(vec![Arc::new(Deprecated)]).unwrap();
+        assert_eq!(1, wifis.len());
+        assert_eq!("deprecated", meta.backend_used);
+        assert!(meta.deprecated);
+        assert_eq!(
+            vec!["using deprecated deprecated, results may be incomplete".to_string()],
+            meta.warnings
+        );
+    }
+
+    /// Sleeps as if running a command, then as if parsing its output
+    struct Slow;
+
+    impl ScanBackend for Slow {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+            let start = Instant::now();
+            std::thread::sleep(Duration::from_millis(20));
+            add_command_time(start.elapsed());
+            std::thread::sleep(Duration::from_millis(10));
+            Ok(vec![Wifi::default()])
+        }
+    }
+
+    #[test]
+    fn should_time_command_and_parsing() {
+        let (_, _, meta) = scan_backends(vec![Arc::new(Slow)]).unwrap();
+        assert!(
+            meta.command_duration >= Duration::from_millis(20),
+            "{meta:?}"
+        );
+        assert!(meta.parse_duration >= Duration::from_millis(10), "{meta:?}");
+        assert_eq!(meta.duration, meta.command_duration + meta.parse_duration);
+        assert!(!meta.deprecated);
+    }
+
+    struct Failing;
+
+    impl ScanBackend for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+            Err(WifiError::NoMatch)
+        }
+    }
+
+    #[test]
+    fn should_record_fallback_chain() {
+        let fallback = Fake {
+            name: "fallback",
+            available: true,
+        };
+        let (wifis, _, meta) = scan_backends(vec![Arc::new(Failing), Arc::new(fallback)]).unwrap();
+
+        assert_eq!("fallback", wifis[0].ssid);
+        assert_eq!("fallback", meta.backend_used);
+        assert_eq!(
+            vec!["failing: failed".to_string(), "fallback: ok".to_string()],
+            meta.fallback_chain
+        );
+        assert_eq!(1, meta.warnings.len());
+
+        let (_, _, meta) = scan_backends(vec![Arc::new(Fake {
+            name: "first",
+            available: true,
+        })])
+        .unwrap();
+        assert_eq!(vec!["first: ok".to_string()], meta.fallback_chain);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_find_commands() {
+        assert!(command_exists("sh"));
+        assert!(!command_exists("wifiscanner-no-such-command"));
+    }
+}
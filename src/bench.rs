@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use crate::backend::DefaultBackend;
+use crate::ScanBackend;
+
+/// How [`scan_n_times`] went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanBench {
+    /// scans that returned hotspots, or an empty list
+    pub successes: usize,
+    /// scans that failed
+    pub failures: usize,
+    /// quickest scan, failed ones included
+    pub min: Duration,
+    /// slowest scan, failed ones included
+    pub max: Duration,
+    /// average time of a scan, failed ones included
+    pub mean: Duration,
+    /// hotspots found by all the successful scans together
+    pub total_networks: usize,
+}
+
+/// Runs [`crate::scan`] `n` times back to back and tells how many scans
+/// failed and how long they took, e.g. to benchmark a backend or to catch a
+/// driver that fails now and then. Failures are logged at debug level and
+/// don't stop the run. All the durations are zero when `n` is zero.
+pub fn scan_n_times(n: usize) -> ScanBench {
+    scan_n_times_with(n, &DefaultBackend)
+}
+
+fn scan_n_times_with(n: usize, backend: &dyn ScanBackend) -> ScanBench {
+    let mut bench = ScanBench::default();
+    let mut total = Duration::ZERO;
+    for i in 0..n {
+        let start = Instant::now();
+        let result = backend.scan();
+        let took = start.elapsed();
+        match result {
+            Ok(wifis) => {
+                bench.successes += 1;
+                bench.total_networks += wifis.len();
+            }
+            Err(e) => {
+                tracing::debug!("scan {} of {n} failed: {e}", i + 1);
+                bench.failures += 1;
+            }
+        }
+        bench.min = if i == 0 { took } else { bench.min.min(took) };
+        bench.max = bench.max.max(took);
+        total += took;
+    }
+    if n > 0 {
+        bench.mean = total.div_f64(n as f64);
+    }
+    bench
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReplayBackend, Wifi, WifiError};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::thread;
+
+    /// Replays scans, the `n`th call taking `n` milliseconds
+    struct SlowBackend {
+        replay: ReplayBackend,
+        calls: AtomicU64,
+    }
+
+    impl ScanBackend for SlowBackend {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+            let call = self.calls.fetch_add(1, Ordering::Relaxed) + 1;
+            thread::sleep(Duration::from_millis(call));
+            self.replay.scan()
+        }
+    }
+
+    #[test]
+    fn should_aggregate_scans() {
+        let snapshots = [1, 3, 5].map(|count| vec![Wifi::default(); count]).to_vec();
+        let slow = SlowBackend {
+            replay: ReplayBackend::new(snapshots).fail_at(1).fail_at(3),
+            calls: AtomicU64::new(0),
+        };
+        let bench = scan_n_times_with(5, &slow);
+
+        assert_eq!(5, slow.calls.load(Ordering::Relaxed));
+        assert_eq!(3, bench.successes);
+        assert_eq!(2, bench.failures);
+        assert_eq!(1 + 3 + 5, bench.total_networks);
+        assert!(bench.min >= Duration::from_millis(1));
+        assert!(bench.max >= Duration::from_millis(5));
+        assert!(bench.mean >= Duration::from_millis(3));
+        assert!(bench.min <= bench.mean && bench.mean <= bench.max);
+    }
+
+    #[test]
+    fn should_bench_no_scans() {
+        let replay = ReplayBackend::new(vec![]).fail_at(0);
+        assert_eq!(ScanBench::default(), scan_n_times_with(0, &replay));
+        // the failure wasn't used up
+        assert!(replay.scan().is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::io;
+
+use crate::{Wifi, WifiError};
+
+/// Like [`crate::scan`] but always returns something to show: the networks
+/// that could be parsed, and the error if anything went wrong. A command that
+/// fails outright gives no networks, output with some unparseable entries
+/// gives the good ones along with [`WifiError::PartialParse`].
+pub fn try_scan() -> (Vec<Wifi>, Option<WifiError>) {
+    try_scan_with(|| Ok(crate::backend::scan_partial()?))
+}
+
+fn try_scan_with<F>(scan: F) -> (Vec<Wifi>, Option<WifiError>)
+where
+    F: FnOnce() -> anyhow::Result<(Vec<Wifi>, usize)>,
+{
+    match scan() {
+        Ok((wifis, 0)) => (wifis, None),
+        Ok((wifis, skipped)) => (wifis, Some(WifiError::PartialParse(skipped))),
+        Err(e) => (Vec::new(), Some(to_wifi_error(e))),
+    }
+}
+
+/// Keeps a [`WifiError`] as is, a missing command becomes
+/// [`WifiError::CommandNotFound`] and anything else
+/// [`WifiError::FailedToParse`].
+pub(crate) fn to_wifi_error(error: anyhow::Error) -> WifiError {
+    let error = match error.downcast::<WifiError>() {
+        Ok(error) => return error,
+        Err(error) => error,
+    };
+    match error.downcast_ref::<io::Error>() {
+        Some(e) if e.kind() == io::ErrorKind::NotFound => WifiError::CommandNotFound,
+        _ => {
+            tracing::debug!("scan failed: {error}");
+            WifiError::FailedToParse
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(ssid: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_return_partial_results_with_warning() {
+        let (wifis, error) = try_scan_with(|| Ok((vec![wifi("good"), wifi("also good")], 2)));
+        assert_eq!(vec![wifi("good"), wifi("also good")], wifis);
+        assert_eq!(Some(WifiError::PartialParse(2)), error);
+
+        let (wifis, error) = try_scan_with(|| Ok((vec![wifi("good")], 0)));
+        assert_eq!(1, wifis.len());
+        assert_eq!(None, error);
+    }
+
+    #[test]
+    fn should_return_error_when_scan_fails() {
+        let (wifis, error) = try_scan_with(|| Err(io::Error::from(io::ErrorKind::NotFound).into()));
+        assert!(wifis.is_empty());
+        assert_eq!(Some(WifiError::CommandNotFound), error);
+
+        let (_, error) = try_scan_with(|| Err(WifiError::HeaderNotFound("SSID").into()));
+        assert_eq!(Some(WifiError::HeaderNotFound("SSID")), error);
+    }
+}
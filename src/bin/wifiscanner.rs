@@ -1,10 +1,546 @@
+use std::borrow::Cow;
+use std::env;
+use std::fmt::Write;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use wifiscanner::{Band, Wifi, WifiFilter};
+
+const USAGE: &str = "usage: wifiscanner [--group-by-band | --group-by-vendor] [--sort ssid|signal]
+                   [--watch SECONDS]
+                   [--columns mac,ssid,channel,signal,security,band,bars]
+                   [--bars] [--pretty] [--ndjson] [--min-signal DBM]
+                   [--band 2.4|5|6] [--interface NAME]
+       wifiscanner --list-interfaces";
+
+/// Characters of a `bars` column
+const BAR_WIDTH: usize = 8;
+
+/// Moves the cursor home and clears the screen, so `--watch` redraws in place
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortOrder {
+    Ssid,
+    Signal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Mac,
+    Ssid,
+    Channel,
+    Signal,
+    Security,
+    Band,
+    Bars,
+}
+
+impl Column {
+    fn parse(name: &str) -> Result<Column, String> {
+        match name {
+            "mac" => Ok(Column::Mac),
+            "ssid" => Ok(Column::Ssid),
+            "channel" => Ok(Column::Channel),
+            "signal" => Ok(Column::Signal),
+            "security" => Ok(Column::Security),
+            "band" => Ok(Column::Band),
+            "bars" => Ok(Column::Bars),
+            _ => Err(format!("unknown column {name:?}")),
+        }
+    }
+
+    fn value(self, network: &Wifi) -> Cow<'_, str> {
+        match self {
+            Column::Mac => Cow::Borrowed(&network.mac),
+            Column::Ssid => Cow::Borrowed(&network.ssid),
+            Column::Channel => Cow::Owned(network.channel_frequency_display()),
+            Column::Signal => Cow::Borrowed(&network.signal_level),
+            Column::Security => Cow::Borrowed(&network.security),
+            Column::Band => Cow::Borrowed(band_name(network.band())),
+            Column::Bars => Cow::Owned(wifiscanner::signal_bars(network, BAR_WIDTH)),
+        }
+    }
+}
+
+const DEFAULT_COLUMNS: [Column; 5] = [
+    Column::Mac,
+    Column::Ssid,
+    Column::Channel,
+    Column::Signal,
+    Column::Security,
+];
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    group_by_band: bool,
+    /// one section per manufacturer, the biggest first
+    group_by_vendor: bool,
+    sort: Option<SortOrder>,
+    columns: Vec<Column>,
+    /// rescan interval, the table is redrawn until interrupted
+    watch: Option<Duration>,
+    /// box-drawn table, needs the `pretty-table` feature
+    pretty: bool,
+    /// one JSON object per network and line, needs the `serde` feature
+    ndjson: bool,
+    /// only show networks at least this strong
+    min_signal: Option<i32>,
+    /// only show networks on this band
+    band: Option<Band>,
+    /// scan with this interface rather than the default one
+    interface: Option<String>,
+    /// print the wireless interfaces instead of scanning
+    list_interfaces: bool,
+}
+
+impl Args {
+    /// Networks not matching every filter flag aren't shown
+    fn filter(&self) -> WifiFilter {
+        WifiFilter {
+            min_dbm: self.min_signal,
+            band: self.band,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            group_by_band: false,
+            group_by_vendor: false,
+            sort: None,
+            columns: DEFAULT_COLUMNS.to_vec(),
+            watch: None,
+            pretty: false,
+            ndjson: false,
+            min_signal: None,
+            band: None,
+            interface: None,
+            list_interfaces: false,
+        }
+    }
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut bars = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--group-by-band" => parsed.group_by_band = true,
+            "--group-by-vendor" => parsed.group_by_vendor = true,
+            "--sort" => {
+                parsed.sort = match args.next().as_deref() {
+                    Some("ssid") => Some(SortOrder::Ssid),
+                    Some("signal") => Some(SortOrder::Signal),
+                    other => return Err(format!("invalid --sort value {other:?}")),
+                }
+            }
+            "--columns" => {
+                let columns = args.next().ok_or("missing --columns list")?;
+                parsed.columns = columns
+                    .split(',')
+                    .map(|name| Column::parse(name.trim()))
+                    .collect::<Result<_, _>>()?;
+            }
+            "--watch" => {
+                let interval = args.next().ok_or("missing --watch interval")?;
+                parsed.watch = Some(parse_interval(&interval)?);
+            }
+            "--bars" => bars = true,
+            "--pretty" if cfg!(feature = "pretty-table") => parsed.pretty = true,
+            "--pretty" => return Err("--pretty needs the pretty-table feature".to_string()),
+            "--ndjson" if cfg!(feature = "serde") => parsed.ndjson = true,
+            "--ndjson" => return Err("--ndjson needs the serde feature".to_string()),
+            "--min-signal" => {
+                let dbm = args.next().ok_or("missing --min-signal level")?;
+                let dbm = dbm
+                    .parse()
+                    .map_err(|_| format!("invalid --min-signal level {dbm:?}, expected dBm"))?;
+                parsed.min_signal = Some(dbm);
+            }
+            "--band" => {
+                let band = args.next().ok_or("missing --band")?;
+                parsed.band = Some(band.parse().map_err(|e| format!("invalid --band: {e}"))?);
+            }
+            "--interface" => {
+                parsed.interface = Some(args.next().ok_or("missing --interface name")?);
+            }
+            "--list-interfaces" => parsed.list_interfaces = true,
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    if parsed.group_by_band && parsed.group_by_vendor {
+        return Err("--group-by-band and --group-by-vendor don't go together".to_string());
+    }
+    if bars && !parsed.columns.contains(&Column::Bars) {
+        parsed.columns.push(Column::Bars);
+    }
+    Ok(parsed)
+}
+
+/// Parses a positive number of seconds, e.g. `2` or `0.5`
+fn parse_interval(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse::<f64>()
+        .ok()
+        .filter(|s| s.is_finite() && *s > 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| format!("invalid --watch interval {seconds:?}"))
+}
+
+fn sort(networks: &mut [Wifi], order: Option<SortOrder>) {
+    match order {
+        Some(SortOrder::Ssid) => wifiscanner::sort_by_ssid(networks),
+        Some(SortOrder::Signal) => wifiscanner::sort_by_signal(networks),
+        None => {}
+    }
+}
+
+/// One `--watch` refresh: clears the screen and redraws the table, strongest
+/// signal first unless another order was asked for. `--ndjson` output is
+/// appended instead so it can be streamed.
+fn render_refresh(mut networks: Vec<Wifi>, args: &Args) -> String {
+    sort(&mut networks, args.sort.or(Some(SortOrder::Signal)));
+    if args.ndjson {
+        return render(&networks, args);
+    }
+    format!("{CLEAR_SCREEN}{}", render(&networks, args))
+}
+
+fn band_name(band: Option<Band>) -> &'static str {
+    match band {
+        Some(Band::Band2_4GHz) => "2.4 GHz",
+        Some(Band::Band5GHz) => "5 GHz",
+        Some(Band::Band6GHz) => "6 GHz",
+        None => "",
+    }
+}
+
+/// Returns the widest value of each column, so every row lines up
+fn column_widths(networks: &[Wifi], columns: &[Column]) -> Vec<usize> {
+    columns
+        .iter()
+        .map(|column| {
+            networks
+                .iter()
+                .map(|network| column.value(network).chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+fn format_network(network: &Wifi, columns: &[Column], widths: &[usize]) -> String {
+    let mut line = String::new();
+    for (i, (column, width)) in columns.iter().zip(widths).enumerate() {
+        if i > 0 {
+            line.push(' ');
+        }
+        let _ = write!(line, "{:width$}", column.value(network));
+    }
+    line.trim_end().to_string()
+}
+
+fn render(networks: &[Wifi], args: &Args) -> String {
+    #[cfg(feature = "serde")]
+    if args.ndjson {
+        return wifiscanner::to_ndjson(networks);
+    }
+    #[cfg(feature = "pretty-table")]
+    if args.pretty {
+        return wifiscanner::format_pretty_table(networks);
+    }
+    let widths = column_widths(networks, &args.columns);
+    let mut out = String::from("== List of networks\n");
+    if args.group_by_vendor {
+        for (vendor, section) in wifiscanner::group_by_vendor(networks) {
+            let _ = writeln!(out, "-- {vendor} ({})", section.len());
+            for network in &section {
+                let _ = writeln!(out, "{}", format_network(network, &args.columns, &widths));
+            }
+        }
+        return out;
+    }
+    if !args.group_by_band {
+        for network in networks {
+            let _ = writeln!(out, "{}", format_network(network, &args.columns, &widths));
+        }
+        return out;
+    }
+
+    let bands = [
+        Some(Band::Band2_4GHz),
+        Some(Band::Band5GHz),
+        Some(Band::Band6GHz),
+        None,
+    ];
+    for band in bands {
+        let mut section = networks.iter().filter(|n| n.band() == band).peekable();
+        if section.peek().is_none() {
+            continue;
+        }
+        let header = if band.is_some() {
+            band_name(band)
+        } else {
+            "(unknown band)"
+        };
+        let _ = writeln!(out, "-- {header}");
+        for network in section {
+            let _ = writeln!(out, "{}", format_network(network, &args.columns, &widths));
+        }
+    }
+    out
+}
+
+/// One interface name per line
+fn render_interfaces(interfaces: &[String]) -> String {
+    interfaces.iter().map(|name| format!("{name}\n")).collect()
+}
+
+/// Scans with the `--interface` one, or the default interface
+fn scan(args: &Args) -> anyhow::Result<Vec<Wifi>> {
+    match &args.interface {
+        Some(interface) => wifiscanner::scan_on_interface(interface),
+        None => wifiscanner::scan(),
+    }
+}
+
 fn main() {
-    let networks = wifiscanner::scan().expect("Cannot scan network");
-    println!("== List of networks");
-    for network in networks {
-        println!(
-            "{} {:20} {:10} {:4} {}",
-            network.mac, network.ssid, network.channel, network.signal_level, network.security
+    let args = parse_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{e}\n{USAGE}");
+        process::exit(2);
+    });
+    if args.list_interfaces {
+        match wifiscanner::list_interfaces() {
+            Ok(interfaces) => print!("{}", render_interfaces(&interfaces)),
+            Err(e) => {
+                eprintln!("Cannot list interfaces: {e}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    let filter = args.filter();
+    if let Some(interval) = args.watch {
+        // runs until Ctrl-C, the default SIGINT handling is enough since the
+        // terminal is only ever written to
+        loop {
+            match scan(&args) {
+                Ok(mut networks) => {
+                    networks.retain(|network| network.matches(&filter));
+                    print!("{}", render_refresh(networks, &args))
+                }
+                Err(e) => println!("{CLEAR_SCREEN}Cannot scan network: {e}"),
+            }
+            thread::sleep(interval);
+        }
+    }
+
+    let mut networks = scan(&args).unwrap_or_else(|e| {
+        eprintln!("Cannot scan network: {e}");
+        process::exit(1);
+    });
+    networks.retain(|network| network.matches(&filter));
+    sort(&mut networks, args.sort);
+    print!("{}", render(&networks, &args));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(ssid: &str, channel: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            channel: channel.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_parse_args() {
+        let args = parse_args(vec!["--group-by-band".to_string()]).unwrap();
+        assert!(args.group_by_band);
+        assert!(parse_args(vec!["--nope".to_string()]).is_err());
+
+        let args = parse_args(vec!["--sort".to_string(), "ssid".to_string()]).unwrap();
+        assert_eq!(Some(SortOrder::Ssid), args.sort);
+        assert!(parse_args(vec!["--sort".to_string()]).is_err());
+    }
+
+    #[test]
+    fn should_parse_filters() {
+        let args = |list: &[&str]| parse_args(list.iter().map(|arg| arg.to_string()));
+        let parsed = args(&["--min-signal", "-70", "--band", "5"]).unwrap();
+        assert_eq!(Some(-70), parsed.min_signal);
+        assert_eq!(Some(Band::Band5GHz), parsed.band);
+        assert_eq!(
+            Some(Band::Band2_4GHz),
+            args(&["--band", "2.4"]).unwrap().band
+        );
+
+        let err = args(&["--min-signal", "strong"]).unwrap_err();
+        assert_eq!(r#"invalid --min-signal level "strong", expected dBm"#, err);
+        assert!(args(&["--band", "3"])
+            .unwrap_err()
+            .starts_with("invalid --band"));
+        assert!(args(&["--band"]).is_err());
+    }
+
+    #[test]
+    fn should_parse_interface_flags() {
+        let args = parse_args(["--interface", "wlan1"].map(String::from)).unwrap();
+        assert_eq!(Some("wlan1".to_string()), args.interface);
+        assert!(parse_args(vec!["--interface".to_string()]).is_err());
+        assert!(
+            parse_args(vec!["--list-interfaces".to_string()])
+                .unwrap()
+                .list_interfaces
         );
+
+        let interfaces = ["wlan0".to_string(), "wlan1".to_string()];
+        assert_eq!("wlan0\nwlan1\n", render_interfaces(&interfaces));
+    }
+
+    #[test]
+    fn should_parse_watch_interval() {
+        assert_eq!(Ok(Duration::from_secs(2)), parse_interval("2"));
+        assert_eq!(Ok(Duration::from_millis(500)), parse_interval("0.5"));
+        assert!(parse_interval("0").is_err());
+        assert!(parse_interval("-1").is_err());
+        assert!(parse_interval("soon").is_err());
+
+        let args = parse_args(vec!["--watch".to_string(), "2".to_string()]).unwrap();
+        assert_eq!(Some(Duration::from_secs(2)), args.watch);
+        assert!(parse_args(vec!["--watch".to_string()]).is_err());
+    }
+
+    #[test]
+    fn should_render_watch_refresh_by_signal() {
+        let mut weak = wifi("weak", "6");
+        weak.signal_level = "-80".to_string();
+        let mut strong = wifi("strong", "36");
+        strong.signal_level = "-40".to_string();
+
+        let out = render_refresh(vec![weak, strong], &Args::default());
+        assert!(out.starts_with(CLEAR_SCREEN));
+        let lines: Vec<&str> = out[CLEAR_SCREEN.len()..].lines().collect();
+        assert_eq!(3, lines.len());
+        assert!(lines[1].contains("strong"));
+        assert!(lines[2].contains("weak"));
+    }
+
+    #[test]
+    fn should_fit_columns_to_the_data() {
+        let long = "a-network-name-longer-than-twenty-characters";
+        let networks = vec![wifi(long, "36"), wifi("short", "6")];
+        let out = render(&networks, &Args::default());
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[1].contains(long));
+        // the channel column starts at the same place on every row
+        assert_eq!(lines[1].find("36"), lines[2].find('6'));
+    }
+
+    #[test]
+    fn should_select_columns() {
+        let args = parse_args(vec!["--columns".to_string(), "band,ssid".to_string()]).unwrap();
+        assert_eq!(vec![Column::Band, Column::Ssid], args.columns);
+        assert!(parse_args(vec!["--columns".to_string(), "ssid,nope".to_string()]).is_err());
+
+        let out = render(&[wifi("five", "36"), wifi("two", "6")], &args);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!("5 GHz   five", lines[1]);
+        assert_eq!("2.4 GHz two", lines[2]);
+    }
+
+    #[test]
+    fn should_add_bars_column() {
+        let args = parse_args(["--bars", "--columns", "ssid"].map(String::from)).unwrap();
+        assert_eq!(vec![Column::Ssid, Column::Bars], args.columns);
+        let args = parse_args(["--columns", "bars,ssid", "--bars"].map(String::from)).unwrap();
+        assert_eq!(vec![Column::Bars, Column::Ssid], args.columns);
+
+        let mut strong = wifi("strong", "36");
+        strong.signal_level = "-45".to_string();
+        let args = parse_args(["--bars", "--columns", "ssid"].map(String::from)).unwrap();
+        let out = render(&[strong, wifi("unknown", "6")], &args);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!("strong  ████████", lines[1]);
+        assert_eq!("unknown ░░░░░░░░", lines[2]);
+    }
+
+    #[test]
+    fn should_group_by_band() {
+        let networks = vec![wifi("five", "36"), wifi("two", "6"), wifi("unknown", "")];
+        let args = Args {
+            group_by_band: true,
+            ..Default::default()
+        };
+
+        let out = render(&networks, &args);
+        let two = out.find("-- 2.4 GHz").unwrap();
+        let five = out.find("-- 5 GHz").unwrap();
+        let unknown = out.find("-- (unknown band)").unwrap();
+        assert!(two < five && five < unknown);
+        assert!(!out.contains("-- 6 GHz"));
+        assert!(out[two..five].contains("two"));
+        assert!(out[five..unknown].contains("five"));
+        assert!(out[unknown..].contains("unknown"));
+    }
+
+    #[test]
+    fn should_group_by_vendor() {
+        let network = |ssid: &str, mac: &str| Wifi {
+            mac: mac.to_string(),
+            ..wifi(ssid, "6")
+        };
+        let networks = vec![
+            network("archer", "50:c7:bf:44:55:66"),
+            network("lobby", "24:a4:3c:10:20:30"),
+            network("random", "12:34:56:78:9a:bc"),
+            network("warehouse", "fc:ec:da:77:88:99"),
+        ];
+        let args = parse_args(vec!["--group-by-vendor".to_string()]).unwrap();
+
+        let out = render(&networks, &args);
+        let ubiquiti = out.find("-- Ubiquiti (2)").unwrap();
+        let tp_link = out.find("-- TP-Link (1)").unwrap();
+        let unknown = out.find("-- Unknown vendor (1)").unwrap();
+        assert!(ubiquiti < tp_link && tp_link < unknown);
+        assert!(out[ubiquiti..tp_link].contains("lobby"));
+        assert!(out[ubiquiti..tp_link].contains("warehouse"));
+        assert!(out[tp_link..unknown].contains("archer"));
+        assert!(out[unknown..].contains("random"));
+
+        let both = ["--group-by-vendor", "--group-by-band"].map(String::from);
+        assert!(parse_args(both).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_render_ndjson() {
+        let args = parse_args(vec!["--ndjson".to_string()]).unwrap();
+        let out = render(&[wifi("five", "36"), wifi("two", "6")], &args);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(2, lines.len());
+        assert!(lines.iter().all(|l| l.starts_with('{') && l.ends_with('}')));
+        assert!(lines[0].contains(r#""ssid":"five""#));
+
+        assert_eq!("", render(&[], &args));
+        assert_eq!("", render_refresh(vec![], &args));
+    }
+
+    #[test]
+    fn should_keep_flat_table_by_default() {
+        let networks = vec![wifi("five", "36"), wifi("two", "6")];
+        let out = render(&networks, &Args::default());
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(3, lines.len());
+        assert!(lines[1].contains("five"));
+        assert!(lines[2].contains("two"));
     }
 }
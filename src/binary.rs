@@ -0,0 +1,205 @@
+//! Compact binary encoding of scan results, for links where JSON is too
+//! big. A buffer starts with [`MAGIC`] and the format [`VERSION`], then the
+//! number of hotspots. Each hotspot is its length followed by the
+//! [`Wifi::field_values`], every one its length followed by its UTF-8 bytes.
+//! Lengths and counts are LEB128 varints. Readers skip the bytes of a
+//! hotspot past the fields they know and leave the fields an older writer
+//! didn't write unknown, so fields can be added without a new version.
+//! Derived fields like `band` are read back from the ones they come from.
+
+use crate::fields::FIELDS;
+use crate::{Wifi, WifiError};
+
+/// First bytes of an encoded buffer
+const MAGIC: &[u8; 3] = b"WFS";
+/// Version of the encoding, bumped when old readers can't read it any more
+const VERSION: u8 = 1;
+
+/// Encodes `wifis`, see [`from_bytes`] to decode them.
+pub fn to_bytes(wifis: &[Wifi]) -> Vec<u8> {
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+    write_varint(&mut out, wifis.len());
+    for wifi in wifis {
+        let mut record = Vec::new();
+        for value in wifi.field_values() {
+            write_varint(&mut record, value.len());
+            record.extend(value.as_bytes());
+        }
+        write_varint(&mut out, record.len());
+        out.extend(record);
+    }
+    out
+}
+
+/// Decodes hotspots encoded by [`to_bytes`]. Fails with
+/// [`WifiError::InvalidValue`] when the buffer is of a newer version and
+/// with [`WifiError::FailedToParse`] when it is truncated or corrupt.
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<Wifi>, WifiError> {
+    let mut reader = Reader(bytes);
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(WifiError::FailedToParse);
+    }
+    let version = reader.take(1)?[0];
+    if version != VERSION {
+        return Err(WifiError::InvalidValue {
+            expected: "binary format version 1",
+            value: version.to_string(),
+        });
+    }
+
+    let count = reader.varint()?;
+    let mut wifis = Vec::new();
+    for _ in 0..count {
+        let len = reader.varint()?;
+        let mut record = Reader(reader.take(len)?);
+        let mut wifi = Wifi::default();
+        for field in FIELDS {
+            if record.0.is_empty() {
+                break;
+            }
+            let len = record.varint()?;
+            let value =
+                std::str::from_utf8(record.take(len)?).map_err(|_| WifiError::FailedToParse)?;
+            (field.set)(&mut wifi, value);
+        }
+        wifis.push(wifi);
+    }
+    if !reader.0.is_empty() {
+        return Err(WifiError::FailedToParse);
+    }
+    Ok(wifis)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// The bytes left to decode
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], WifiError> {
+        if len > self.0.len() {
+            return Err(WifiError::FailedToParse);
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<usize, WifiError> {
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= usize::from(byte & 0x7f)
+                .checked_shl(shift)
+                .ok_or(WifiError::FailedToParse)?;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(WifiError::FailedToParse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifis() -> Vec<Wifi> {
+        vec![
+            Wifi {
+                mac: "11:22:33:44:55:66".to_string(),
+                ssid: "Café".to_string(),
+                channel: "6".to_string(),
+                signal_level: "-40".to_string(),
+                security: "WPA2".to_string(),
+                wifi_generation: Some(6),
+                noise_dbm: Some(-92),
+                bss_color: Some(17),
+                tx_rate_mbps: Some(286.8),
+                rx_rate_mbps: Some(143.4),
+                max_tx_power_dbm: Some(20),
+                ..Default::default()
+            },
+            Wifi {
+                mac: "aa:bb:cc:dd:ee:ff".to_string(),
+                ssid: "x".repeat(200),
+                channel: "36".to_string(),
+                signal_level: "-71".to_string(),
+                // not the one channel 36 is on
+                reported_frequency_mhz: Some(5190),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn should_round_trip() {
+        let bytes = to_bytes(&wifis());
+        assert_eq!(b"WFS\x01\x02", &bytes[..5]);
+        assert_eq!(wifis(), from_bytes(&bytes).unwrap());
+        assert!(from_bytes(&to_bytes(&[])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_read_records_of_older_writers() {
+        let mut record = Vec::new();
+        for value in ["11:22:33:44:55:66", "home", "6"] {
+            write_varint(&mut record, value.len());
+            record.extend(value.as_bytes());
+        }
+        let mut bytes = b"WFS\x01\x01".to_vec();
+        write_varint(&mut bytes, record.len());
+        bytes.extend(record);
+
+        let expected = Wifi {
+            mac: "11:22:33:44:55:66".to_string(),
+            ssid: "home".to_string(),
+            channel: "6".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(vec![expected], from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn should_skip_unknown_fields() {
+        let mut bytes = to_bytes(&wifis()[..1]);
+        // a field a newer writer added
+        bytes[5] += 3;
+        bytes.extend([2, b'o', b'k']);
+        assert_eq!(wifis()[..1], from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn should_reject_corrupt_buffers() {
+        let bytes = to_bytes(&wifis());
+        for len in 0..bytes.len() {
+            assert_eq!(
+                Err(WifiError::FailedToParse),
+                from_bytes(&bytes[..len]),
+                "{len} bytes"
+            );
+        }
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(Err(WifiError::FailedToParse), from_bytes(&trailing));
+
+        let mut newer = bytes.clone();
+        newer[3] = 2;
+        assert_eq!(
+            Err(WifiError::InvalidValue {
+                expected: "binary format version 1",
+                value: "2".to_string(),
+            }),
+            from_bytes(&newer)
+        );
+        assert_eq!(Err(WifiError::FailedToParse), from_bytes(b"JSON"));
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::Wifi;
+
+/// Last successful scan and when it finished
+type Cached = Option<(Instant, Vec<Wifi>)>;
+
+static CACHE: Mutex<Cached> = Mutex::new(None);
+
+/// Same as [`crate::scan`] but returns the last result when it is younger
+/// than `ttl`, so several parts of an app can ask for the hotspots without
+/// each running the backend.
+///
+/// The cache is shared by the whole process and only this function uses it,
+/// plain [`crate::scan`] always scans. It is safe to call from several
+/// threads: a thread finding the cache stale scans while holding the lock,
+/// so the others wait for its result rather than scanning as well. Failed
+/// scans aren't cached.
+pub fn scan_cached(ttl: Duration) -> anyhow::Result<Vec<Wifi>> {
+    scan_cached_with(&CACHE, ttl, Instant::now, crate::scan)
+}
+
+fn scan_cached_with(
+    cache: &Mutex<Cached>,
+    ttl: Duration,
+    now: impl Fn() -> Instant,
+    scan: impl FnOnce() -> anyhow::Result<Vec<Wifi>>,
+) -> anyhow::Result<Vec<Wifi>> {
+    let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((scanned_at, wifis)) = cache.as_ref() {
+        if now().saturating_duration_since(*scanned_at) < ttl {
+            return Ok(wifis.clone());
+        }
+    }
+    let wifis = scan()?;
+    *cache = Some((now(), wifis.clone()));
+    Ok(wifis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn should_scan_once_within_ttl() {
+        let cache = Mutex::new(None);
+        let start = Instant::now();
+        let elapsed = Cell::new(Duration::ZERO);
+        let now = || start + elapsed.get();
+        let runs = Cell::new(0);
+        let scan = || {
+            runs.set(runs.get() + 1);
+            Ok(vec![Wifi::default(); runs.get()])
+        };
+        let ttl = Duration::from_secs(1);
+
+        assert_eq!(1, scan_cached_with(&cache, ttl, now, scan).unwrap().len());
+        elapsed.set(Duration::from_millis(500));
+        assert_eq!(1, scan_cached_with(&cache, ttl, now, scan).unwrap().len());
+        assert_eq!(1, runs.get());
+
+        elapsed.set(Duration::from_millis(1500));
+        assert_eq!(2, scan_cached_with(&cache, ttl, now, scan).unwrap().len());
+        assert_eq!(2, runs.get());
+
+        // errors are returned but don't replace the cached result
+        elapsed.set(Duration::from_secs(5));
+        assert!(scan_cached_with(&cache, ttl, now, || anyhow::bail!("no wifi")).is_err());
+        assert_eq!(
+            2,
+            scan_cached_with(&cache, Duration::MAX, now, scan)
+                .unwrap()
+                .len()
+        );
+    }
+}
@@ -0,0 +1,603 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Wifi, WifiError};
+
+/// Where the secondary 20 MHz channel of a 40 MHz bonded channel sits
+/// relative to the primary one, from the HT Operation element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelOffset {
+    /// secondary channel is 4 channels above the primary
+    Above,
+    /// secondary channel is 4 channels below the primary
+    Below,
+    /// no secondary channel, the hotspot uses 20 MHz
+    None,
+}
+
+/// Frequency band a hotspot operates on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Band {
+    /// 2.4 GHz (channels 1 to 14)
+    Band2_4GHz,
+    /// 5 GHz (channels 32 to 177)
+    Band5GHz,
+    /// 6 GHz (channels 1 to 233)
+    Band6GHz,
+}
+
+impl Band {
+    /// Whether `channel` exists on this band
+    fn has_channel(self, channel: u16) -> bool {
+        match self {
+            Band::Band2_4GHz => (1..=14).contains(&channel),
+            Band::Band5GHz => (32..=177).contains(&channel),
+            Band::Band6GHz => channel == 2 || (channel % 4 == 1 && channel <= 233),
+        }
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Band::Band2_4GHz => "2.4 GHz",
+            Band::Band5GHz => "5 GHz",
+            Band::Band6GHz => "6 GHz",
+        })
+    }
+}
+
+impl FromStr for Band {
+    type Err = WifiError;
+
+    /// Parses `2.4`, `5` or `6`, optionally followed by `g` or `GHz` as in
+    /// `5g` and `2.4 GHz`, in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let band: String = s.split_whitespace().collect::<String>().to_lowercase();
+        let number = band
+            .strip_suffix("ghz")
+            .or_else(|| band.strip_suffix('g'))
+            .unwrap_or(&band);
+        match number {
+            "2.4" | "2" => Ok(Band::Band2_4GHz),
+            "5" => Ok(Band::Band5GHz),
+            "6" => Ok(Band::Band6GHz),
+            _ => Err(WifiError::InvalidValue {
+                expected: "band (2.4, 5 or 6)",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Every 2.4 GHz channel, 14 is only allowed in Japan
+pub const CHANNELS_2_4: &[u16] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14];
+
+/// Every 20 MHz 5 GHz channel allowed somewhere, including Japan's legacy
+/// 34 to 46
+pub const CHANNELS_5: &[u16] = &[
+    32, 34, 36, 38, 40, 42, 44, 46, 48, 52, 56, 60, 64, 68, 72, 76, 80, 84, 88, 92, 96, 100, 104,
+    108, 112, 116, 120, 124, 128, 132, 136, 140, 144, 149, 153, 157, 161, 165, 169, 173, 177,
+];
+
+/// Whether the 5 GHz `channel` is a DFS channel (52 to 64 and 100 to 144),
+/// on which a hotspot must move off when it detects radar
+pub(crate) fn is_dfs(channel: u16) -> bool {
+    matches!(channel, 52..=64 | 100..=144)
+}
+
+/// The channels of [`CHANNELS_5`] most regulatory domains allow: 36 to 64,
+/// 100 to 144 and 149 to 165, less Japan's legacy 38, 42 and 46
+pub(crate) fn common_5ghz_channels() -> Vec<u16> {
+    CHANNELS_5
+        .iter()
+        .copied()
+        .filter(|channel| matches!(channel, 36..=64 | 100..=144 | 149..=165))
+        .filter(|channel| !matches!(channel, 38 | 42 | 46))
+        .collect()
+}
+
+/// Every 20 MHz 6 GHz channel, plus channel 2 at 5935 MHz
+pub const CHANNELS_6: &[u16] = &[
+    1, 2, 5, 9, 13, 17, 21, 25, 29, 33, 37, 41, 45, 49, 53, 57, 61, 65, 69, 73, 77, 81, 85, 89, 93,
+    97, 101, 105, 109, 113, 117, 121, 125, 129, 133, 137, 141, 145, 149, 153, 157, 161, 165, 169,
+    173, 177, 181, 185, 189, 193, 197, 201, 205, 209, 213, 217, 221, 225, 229, 233,
+];
+
+/// Whether `channel` is in the channel list of `band`, e.g. to flag
+/// impossible channels a backend reported. Stricter than [`Wifi::band`],
+/// which only looks at the range of channel numbers.
+pub fn is_valid_channel(channel: u16, band: Band) -> bool {
+    let channels = match band {
+        Band::Band2_4GHz => CHANNELS_2_4,
+        Band::Band5GHz => CHANNELS_5,
+        Band::Band6GHz => CHANNELS_6,
+    };
+    channels.binary_search(&channel).is_ok()
+}
+
+/// Converts a centre frequency in MHz to its channel number, `None` if the
+/// frequency isn't a Wi-Fi channel.
+pub fn frequency_to_channel(mhz: u32) -> Option<u16> {
+    let channel = match mhz {
+        2484 => 14,
+        2412..=2472 => (mhz - 2407) / 5,
+        5935 => 2,
+        5160..=5885 => (mhz - 5000) / 5,
+        5955..=7115 => (mhz - 5950) / 5,
+        _ => return None,
+    };
+    u16::try_from(channel).ok()
+}
+
+/// Band of a centre frequency in MHz, over the same ranges as
+/// [`frequency_to_channel`]
+fn frequency_band(mhz: u32) -> Option<Band> {
+    match mhz {
+        2412..=2484 => Some(Band::Band2_4GHz),
+        5160..=5885 => Some(Band::Band5GHz),
+        5935..=7115 => Some(Band::Band6GHz),
+        _ => None,
+    }
+}
+
+/// Converts a channel number on `band` to its centre frequency in MHz, the
+/// inverse of [`frequency_to_channel`]. `None` if the channel doesn't exist
+/// on the band.
+pub fn channel_to_frequency(channel: u16, band: Band) -> Option<u32> {
+    if !band.has_channel(channel) {
+        return None;
+    }
+    let channel = u32::from(channel);
+    Some(match band {
+        Band::Band2_4GHz if channel == 14 => 2484,
+        Band::Band2_4GHz => 2407 + channel * 5,
+        Band::Band5GHz => 5000 + channel * 5,
+        Band::Band6GHz if channel == 2 => 5935,
+        Band::Band6GHz => 5950 + channel * 5,
+    })
+}
+
+impl Wifi {
+    /// Returns the primary channel number, ignoring any suffix the backend
+    /// appends to it (e.g. `149,+1` on macOS). Derived from
+    /// [`Wifi::reported_frequency_mhz`] when the backend didn't give a channel.
+    pub fn channel_number(&self) -> Option<u16> {
+        let digits: String = self
+            .channel
+            .trim()
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits
+            .parse()
+            .ok()
+            .or_else(|| frequency_to_channel(self.reported_frequency_mhz?))
+    }
+
+    /// Returns the band inferred from the channel number, `None` if the
+    /// channel couldn't be parsed or isn't in a known range.
+    ///
+    /// Channels 1 to 14 are 2.4 GHz, 32 to 177 are 5 GHz and the remaining
+    /// 6 GHz channels (2, and every fourth channel from 1 to 233) are 6 GHz.
+    /// Some 6 GHz channels share their number with a 2.4 or 5 GHz one, so the
+    /// band given in parentheses, as in macOS' `37 (6GHz, 160MHz)`, wins when
+    /// the channel exists on it. Only its leading number is read, which keeps
+    /// this working when macOS localizes the unit. A frequency the backend
+    /// reported in [`Wifi::reported_frequency_mhz`] wins over both.
+    pub fn band(&self) -> Option<Band> {
+        if let Some(band) = self.reported_frequency_mhz.and_then(frequency_band) {
+            return Some(band);
+        }
+        let channel = self.channel_number()?;
+        if let Some(hint) = band_hint(&self.channel) {
+            if hint.has_channel(channel) {
+                return Some(hint);
+            }
+        }
+        [Band::Band2_4GHz, Band::Band5GHz, Band::Band6GHz]
+            .into_iter()
+            .find(|band| band.has_channel(channel))
+    }
+
+    /// Whether the hotspot is on `band`, see [`Wifi::band`]. False when the
+    /// band isn't known.
+    pub fn is_on_band(&self, band: Band) -> bool {
+        self.band() == Some(band)
+    }
+
+    /// Returns the centre frequency of the primary channel in MHz, the one
+    /// the backend reported in [`Wifi::reported_frequency_mhz`] or else from
+    /// the channel number and [`Wifi::band`].
+    pub fn frequency_mhz(&self) -> Option<u32> {
+        self.reported_frequency_mhz
+            .or_else(|| channel_to_frequency(self.channel_number()?, self.band()?))
+    }
+
+    /// Returns the channel with its frequency for display, e.g.
+    /// `149 (5745 MHz)`, or the channel as the backend reported it when the
+    /// frequency isn't known.
+    pub fn channel_frequency_display(&self) -> String {
+        match (self.channel_number(), self.frequency_mhz()) {
+            (Some(channel), Some(mhz)) => format!("{channel} ({mhz} MHz)"),
+            _ => self.channel.clone(),
+        }
+    }
+
+    /// Whether the primary channel is a 5 GHz DFS channel (52 to 64 and 100
+    /// to 144), on which the hotspot must move off when it detects radar.
+    /// Other bands have no DFS channels. `None` when the channel or band
+    /// isn't known.
+    pub fn is_dfs_channel(&self) -> Option<bool> {
+        let channel = self.channel_number()?;
+        Some(match self.band()? {
+            Band::Band5GHz => is_dfs(channel),
+            Band::Band2_4GHz | Band::Band6GHz => false,
+        })
+    }
+
+    /// Returns the global operating class (Annex E of 802.11) the hotspot
+    /// operates in, which tells band, channel and width in one number, e.g.
+    /// 81 for 2.4 GHz channels at 20 MHz or 128 for 5 GHz ones at 80 MHz.
+    ///
+    /// The current operating class `iw` reports wins. Otherwise it is derived
+    /// from the channel and its width: the one macOS appends (`149 (5GHz,
+    /// 80MHz)` or `149,+1`), else 40 MHz for an `iw` secondary channel
+    /// offset, else 20 MHz. `None` when the channel isn't known or no class
+    /// fits, e.g. a 2.4 GHz 40 MHz channel whose secondary isn't known.
+    pub fn operating_class(&self) -> Option<u8> {
+        if let Some(class) = self.current_operating_class {
+            return Some(class);
+        }
+        let channel = self.channel_number()?;
+        let band = self.band()?;
+        if !is_valid_channel(channel, band) {
+            return None;
+        }
+        let (width, offset) = self.channel_width();
+        Some(match (band, width) {
+            (Band::Band2_4GHz, 20) if channel == 14 => 82,
+            (Band::Band2_4GHz, 20) => 81,
+            (Band::Band2_4GHz, 40) => match offset? {
+                ChannelOffset::Above if channel <= 9 => 83,
+                ChannelOffset::Below if (5..=13).contains(&channel) => 84,
+                _ => return None,
+            },
+            (Band::Band5GHz, 20) => match channel {
+                36..=48 => 115,
+                52..=64 => 118,
+                100..=144 => 121,
+                149..=177 => 125,
+                _ => return None,
+            },
+            (Band::Band5GHz, 40) => {
+                // the lower channel of each pair has its secondary above
+                let first = if channel < 149 { 36 } else { 149 };
+                let lower = channel >= first && (channel - first) % 8 == 0;
+                match offset {
+                    Some(ChannelOffset::Above) if !lower => return None,
+                    Some(ChannelOffset::Below) if lower => return None,
+                    _ => {}
+                }
+                let (above, below) = match channel {
+                    36..=48 => (116, 117),
+                    52..=64 => (119, 120),
+                    100..=144 => (122, 123),
+                    149..=177 => (126, 127),
+                    _ => return None,
+                };
+                if lower {
+                    above
+                } else {
+                    below
+                }
+            }
+            (Band::Band5GHz, 80) => 128,
+            (Band::Band5GHz, 160) => 129,
+            (Band::Band6GHz, 20) if channel == 2 => 136,
+            (Band::Band6GHz, _) if channel == 2 => return None,
+            (Band::Band6GHz, 20) => 131,
+            (Band::Band6GHz, 40) => 132,
+            (Band::Band6GHz, 80) => 133,
+            (Band::Band6GHz, 160) => 134,
+            (Band::Band6GHz, 320) => 137,
+            _ => return None,
+        })
+    }
+
+    /// Width in MHz the hotspot operates with and the side of its secondary
+    /// channel when known, see [`Wifi::operating_class`]
+    fn channel_width(&self) -> (u16, Option<ChannelOffset>) {
+        let leading_number = |s: &str| -> Option<u16> {
+            let digits: String = s
+                .trim()
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            digits.parse().ok()
+        };
+        if let Some((_, hint)) = self.channel.split_once('(') {
+            // the width comes last, the band may have a decimal comma
+            if let Some(width) = hint
+                .rsplit_once(',')
+                .and_then(|(_, width)| leading_number(width))
+            {
+                return (width, None);
+            }
+        } else if let Some((_, suffix)) = self.channel.split_once(',') {
+            match suffix.trim() {
+                "+1" => return (40, Some(ChannelOffset::Above)),
+                "-1" => return (40, Some(ChannelOffset::Below)),
+                suffix => {
+                    if let Some(width) = leading_number(suffix) {
+                        return (width, None);
+                    }
+                }
+            }
+        }
+        match self.secondary_channel_offset {
+            Some(offset @ (ChannelOffset::Above | ChannelOffset::Below)) => (40, Some(offset)),
+            _ => (20, None),
+        }
+    }
+
+    /// Whether the hotspot is on 2.4 GHz channel 14 (2484 MHz), which is
+    /// only allowed in Japan and only for 802.11b.
+    pub fn is_channel_14(&self) -> bool {
+        self.frequency_mhz() == Some(2484)
+    }
+}
+
+/// Reads the band from the number right after the first `(`, e.g. `5` in
+/// `149 (5GHz, 80MHz)` or `2` in `6 (2,4 ГГц, 20 МГц)`.
+fn band_hint(channel: &str) -> Option<Band> {
+    let (_, hint) = channel.split_once('(')?;
+    let digits: String = hint
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    match digits.as_str() {
+        "2" => Some(Band::Band2_4GHz),
+        "5" => Some(Band::Band5GHz),
+        "6" => Some(Band::Band6GHz),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi_on(channel: &str) -> Wifi {
+        Wifi {
+            channel: channel.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_parse_channel_number() {
+        assert_eq!(Some(10), wifi_on("10").channel_number());
+        assert_eq!(Some(149), wifi_on("149,+1").channel_number());
+        assert_eq!(None, wifi_on("").channel_number());
+    }
+
+    #[test]
+    fn should_convert_frequency_to_channel() {
+        assert_eq!(Some(1), frequency_to_channel(2412));
+        assert_eq!(Some(14), frequency_to_channel(2484));
+        assert_eq!(Some(149), frequency_to_channel(5745));
+        assert_eq!(Some(37), frequency_to_channel(6135));
+        assert_eq!(None, frequency_to_channel(3000));
+    }
+
+    #[test]
+    fn should_validate_channels() {
+        assert!(is_valid_channel(1, Band::Band2_4GHz));
+        assert!(is_valid_channel(14, Band::Band2_4GHz));
+        assert!(!is_valid_channel(15, Band::Band2_4GHz));
+        assert!(!is_valid_channel(0, Band::Band2_4GHz));
+
+        assert!(is_valid_channel(36, Band::Band5GHz));
+        assert!(is_valid_channel(165, Band::Band5GHz));
+        assert!(!is_valid_channel(37, Band::Band5GHz));
+        assert!(!is_valid_channel(148, Band::Band5GHz));
+        assert!(!is_valid_channel(6, Band::Band5GHz));
+
+        assert!(is_valid_channel(2, Band::Band6GHz));
+        assert!(is_valid_channel(37, Band::Band6GHz));
+        assert!(is_valid_channel(233, Band::Band6GHz));
+        assert!(!is_valid_channel(36, Band::Band6GHz));
+        assert!(!is_valid_channel(237, Band::Band6GHz));
+
+        // every listed channel is on its band and has a frequency
+        for (band, channels) in [
+            (Band::Band2_4GHz, CHANNELS_2_4),
+            (Band::Band5GHz, CHANNELS_5),
+            (Band::Band6GHz, CHANNELS_6),
+        ] {
+            assert!(
+                channels.windows(2).all(|w| w[0] < w[1]),
+                "{band:?} not sorted"
+            );
+            for &channel in channels {
+                assert!(channel_to_frequency(channel, band).is_some(), "{channel}");
+            }
+        }
+    }
+
+    #[test]
+    fn should_convert_channel_to_frequency() {
+        for mhz in [2412, 2472, 2484, 5180, 5745, 5885, 5935, 5955, 6135, 7115] {
+            let channel = frequency_to_channel(mhz).unwrap();
+            let band = match mhz {
+                ..=2484 => Band::Band2_4GHz,
+                5935.. => Band::Band6GHz,
+                _ => Band::Band5GHz,
+            };
+            assert_eq!(Some(mhz), channel_to_frequency(channel, band), "{mhz}");
+        }
+        assert_eq!(None, channel_to_frequency(36, Band::Band2_4GHz));
+
+        // channel 14 sits 12 MHz above channel 13 instead of 5
+        assert_eq!(Some(2484), channel_to_frequency(14, Band::Band2_4GHz));
+        assert_eq!(Some(2472), channel_to_frequency(13, Band::Band2_4GHz));
+    }
+
+    #[test]
+    fn should_tell_band() {
+        assert!(wifi_on("6").is_on_band(Band::Band2_4GHz));
+        assert!(!wifi_on("6").is_on_band(Band::Band5GHz));
+        assert!(wifi_on("37 (6GHz, 160MHz)").is_on_band(Band::Band6GHz));
+        assert!(!wifi_on("").is_on_band(Band::Band2_4GHz));
+    }
+
+    #[test]
+    fn should_derive_operating_class() {
+        let with_offset = |channel: &str, offset: ChannelOffset| Wifi {
+            channel: channel.to_string(),
+            secondary_channel_offset: Some(offset),
+            ..Default::default()
+        };
+
+        // 20 MHz
+        assert_eq!(Some(81), wifi_on("6").operating_class());
+        assert_eq!(Some(82), wifi_on("14").operating_class());
+        assert_eq!(Some(115), wifi_on("36").operating_class());
+        assert_eq!(Some(118), wifi_on("60 (5GHz, 20MHz)").operating_class());
+        assert_eq!(Some(121), wifi_on("112").operating_class());
+        assert_eq!(Some(125), wifi_on("165").operating_class());
+        assert_eq!(Some(131), wifi_on("37 (6GHz, 20MHz)").operating_class());
+        assert_eq!(Some(136), wifi_on("2 (6GHz, 20MHz)").operating_class());
+        assert_eq!(
+            Some(81),
+            with_offset("11", ChannelOffset::None).operating_class()
+        );
+
+        // 40 MHz
+        assert_eq!(
+            Some(83),
+            with_offset("1", ChannelOffset::Above).operating_class()
+        );
+        assert_eq!(
+            Some(84),
+            with_offset("11", ChannelOffset::Below).operating_class()
+        );
+        assert_eq!(Some(116), wifi_on("36 (5GHz, 40MHz)").operating_class());
+        assert_eq!(Some(117), wifi_on("48,-1").operating_class());
+        assert_eq!(Some(126), wifi_on("149,+1").operating_class());
+        assert_eq!(Some(132), wifi_on("5 (6GHz, 40MHz)").operating_class());
+
+        // 80 MHz and more
+        assert_eq!(Some(128), wifi_on("149 (5GHz, 80MHz)").operating_class());
+        assert_eq!(Some(129), wifi_on("36 (5GHz, 160MHz)").operating_class());
+        assert_eq!(Some(134), wifi_on("37 (6GHz, 160MHz)").operating_class());
+        assert_eq!(Some(137), wifi_on("37 (6GHz, 320MHz)").operating_class());
+
+        // what iw reports wins
+        let reported = Wifi {
+            current_operating_class: Some(128),
+            ..with_offset("36", ChannelOffset::Above)
+        };
+        assert_eq!(Some(128), reported.operating_class());
+
+        // unknown channel, secondary on the wrong side or unknown on 2.4 GHz
+        assert_eq!(None, wifi_on("").operating_class());
+        assert_eq!(None, wifi_on("37").operating_class());
+        assert_eq!(None, wifi_on("40,+1").operating_class());
+        assert_eq!(None, wifi_on("6 (2GHz, 40MHz)").operating_class());
+        assert_eq!(
+            None,
+            with_offset("13", ChannelOffset::Above).operating_class()
+        );
+    }
+
+    #[test]
+    fn should_tell_channel_14() {
+        assert!(wifi_on("14").is_channel_14());
+        assert_eq!("14 (2484 MHz)", wifi_on("14").channel_frequency_display());
+        assert!(!wifi_on("13").is_channel_14());
+        assert!(!wifi_on("149").is_channel_14());
+        assert!(!wifi_on("").is_channel_14());
+    }
+
+    #[test]
+    fn should_display_channel_with_frequency() {
+        assert_eq!("149 (5745 MHz)", wifi_on("149").channel_frequency_display());
+        assert_eq!("6 (2437 MHz)", wifi_on("6").channel_frequency_display());
+        assert_eq!(
+            "37 (6135 MHz)",
+            wifi_on("37 (6GHz, 160MHz)").channel_frequency_display()
+        );
+        assert_eq!("n/a", wifi_on("n/a").channel_frequency_display());
+    }
+
+    #[test]
+    fn should_parse_band() {
+        for alias in ["2.4", "2", "2g", "2.4G", "2.4ghz", "2.4 GHz"] {
+            assert_eq!(Ok(Band::Band2_4GHz), alias.parse(), "{alias}");
+        }
+        for alias in ["5", "5g", "5GHz"] {
+            assert_eq!(Ok(Band::Band5GHz), alias.parse(), "{alias}");
+        }
+        for alias in ["6", "6g", " 6 ghz "] {
+            assert_eq!(Ok(Band::Band6GHz), alias.parse(), "{alias}");
+        }
+        for band in [Band::Band2_4GHz, Band::Band5GHz, Band::Band6GHz] {
+            assert_eq!(Ok(band), band.to_string().parse());
+        }
+
+        let err = "60g".parse::<Band>().unwrap_err();
+        assert_eq!("\"60g\" is not a valid band (2.4, 5 or 6)", err.to_string());
+    }
+
+    #[test]
+    fn should_tell_dfs_channels() {
+        assert_eq!(Some(true), wifi_on("52").is_dfs_channel());
+        assert_eq!(Some(true), wifi_on("144").is_dfs_channel());
+        assert_eq!(Some(false), wifi_on("48").is_dfs_channel());
+        assert_eq!(Some(false), wifi_on("149,+1").is_dfs_channel());
+        assert_eq!(Some(false), wifi_on("6").is_dfs_channel());
+        assert_eq!(None, wifi_on("").is_dfs_channel());
+    }
+
+    #[test]
+    fn should_list_common_5ghz_channels() {
+        assert_eq!(
+            vec![
+                36, 40, 44, 48, 52, 56, 60, 64, 100, 104, 108, 112, 116, 120, 124, 128, 132, 136,
+                140, 144, 149, 153, 157, 161, 165,
+            ],
+            common_5ghz_channels()
+        );
+        let dfs: Vec<u16> = common_5ghz_channels()
+            .into_iter()
+            .filter(|&c| is_dfs(c))
+            .collect();
+        assert_eq!(16, dfs.len());
+    }
+
+    #[test]
+    fn should_infer_band() {
+        assert_eq!(Some(Band::Band2_4GHz), wifi_on("6").band());
+        assert_eq!(Some(Band::Band5GHz), wifi_on("112").band());
+        assert_eq!(None, wifi_on("200").band());
+        assert_eq!(None, wifi_on("n/a").band());
+        assert_eq!(Some(Band::Band6GHz), wifi_on("193").band());
+    }
+
+    #[test]
+    fn should_infer_band_from_localized_channel() {
+        assert_eq!(
+            Some(Band::Band2_4GHz),
+            wifi_on("6 (2,4 ГГц, 20 МГц)").band()
+        );
+        assert_eq!(Some(Band::Band5GHz), wifi_on("149 (5 GHz, 80 MHz)").band());
+        // 37 is both a 5 GHz and a 6 GHz channel, the hint decides
+        assert_eq!(Some(Band::Band6GHz), wifi_on("37 (6 ГГц, 160 МГц)").band());
+        assert_eq!(Some(Band::Band5GHz), wifi_on("37").band());
+        // a hint that doesn't fit the channel is ignored
+        assert_eq!(Some(Band::Band5GHz), wifi_on("36 (2GHz, 20MHz)").band());
+    }
+}
@@ -0,0 +1,133 @@
+/// The tool a connect command is built for, one per OS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(test), allow(dead_code))]
+enum Tool {
+    Nmcli,
+    Networksetup,
+    Netsh,
+}
+
+#[cfg(target_os = "macos")]
+const CURRENT_TOOL: Tool = Tool::Networksetup;
+#[cfg(target_os = "windows")]
+const CURRENT_TOOL: Tool = Tool::Netsh;
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const CURRENT_TOOL: Tool = Tool::Nmcli;
+
+impl crate::Wifi {
+    /// Returns the shell command joining this network on the current OS,
+    /// with `password` for a secured one. Nothing is run, the SSID and the
+    /// password are quoted so the command can be pasted as is.
+    ///
+    /// - Linux: `nmcli device wifi connect '<ssid>' password '<password>'`,
+    ///   with the BSSID instead of the SSID for a hidden network
+    /// - macOS: `networksetup -setairportnetwork en0 '<ssid>' '<password>'`,
+    ///   `en0` being the usual Wi-Fi interface
+    /// - Windows: `netsh wlan connect name="<ssid>"`, which joins a saved
+    ///   profile of that name; `netsh` takes no password, so `password` is
+    ///   ignored
+    pub fn connect_command(&self, password: Option<&str>) -> String {
+        self.connect_command_for(CURRENT_TOOL, password)
+    }
+
+    fn connect_command_for(&self, tool: Tool, password: Option<&str>) -> String {
+        match tool {
+            Tool::Nmcli => {
+                let target = if self.is_hidden() && !self.mac.is_empty() {
+                    &self.mac
+                } else {
+                    &self.ssid
+                };
+                let mut command = format!("nmcli device wifi connect {}", sh_quote(target));
+                if let Some(password) = password {
+                    command.push_str(&format!(" password {}", sh_quote(password)));
+                }
+                command
+            }
+            Tool::Networksetup => {
+                let mut command = format!(
+                    "networksetup -setairportnetwork en0 {}",
+                    sh_quote(&self.ssid)
+                );
+                if let Some(password) = password {
+                    command.push_str(&format!(" {}", sh_quote(password)));
+                }
+                command
+            }
+            Tool::Netsh => format!(
+                "netsh wlan connect name=\"{}\"",
+                self.ssid.replace('"', "\"\"")
+            ),
+        }
+    }
+}
+
+/// Single-quotes `value` for a POSIX shell
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Wifi;
+
+    fn wifi(ssid: &str, security: &str) -> Wifi {
+        Wifi {
+            mac: "00:11:22:33:44:55".to_string(),
+            ssid: ssid.to_string(),
+            security: security.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_build_nmcli_connect_command() {
+        assert_eq!(
+            "nmcli device wifi connect 'Free Wifi'",
+            wifi("Free Wifi", "").connect_command_for(Tool::Nmcli, None)
+        );
+        assert_eq!(
+            r"nmcli device wifi connect 'Bob'\''s' password 'p4ss $HOME'",
+            wifi("Bob's", "WPA2").connect_command_for(Tool::Nmcli, Some("p4ss $HOME"))
+        );
+        assert_eq!(
+            "nmcli device wifi connect '00:11:22:33:44:55' password 'secret'",
+            wifi("", "WPA2").connect_command_for(Tool::Nmcli, Some("secret"))
+        );
+    }
+
+    #[test]
+    fn should_build_networksetup_connect_command() {
+        assert_eq!(
+            "networksetup -setairportnetwork en0 'Free Wifi'",
+            wifi("Free Wifi", "").connect_command_for(Tool::Networksetup, None)
+        );
+        assert_eq!(
+            r"networksetup -setairportnetwork en0 'Bob'\''s' 'it'\''s `secret`'",
+            wifi("Bob's", "WPA2(PSK/AES/AES)")
+                .connect_command_for(Tool::Networksetup, Some("it's `secret`"))
+        );
+    }
+
+    #[test]
+    fn should_build_netsh_connect_command() {
+        assert_eq!(
+            "netsh wlan connect name=\"Free Wifi\"",
+            wifi("Free Wifi", "").connect_command_for(Tool::Netsh, None)
+        );
+        assert_eq!(
+            "netsh wlan connect name=\"Say \"\"hi\"\"\"",
+            wifi("Say \"hi\"", "WPA2-Personal").connect_command_for(Tool::Netsh, Some("secret"))
+        );
+    }
+
+    #[test]
+    fn should_build_connect_command_for_current_os() {
+        let wifi = wifi("home", "WPA2");
+        assert_eq!(
+            wifi.connect_command_for(CURRENT_TOOL, Some("secret")),
+            wifi.connect_command(Some("secret"))
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::Wifi;
+
+/// What makes two scan entries the same network for [`dedupe_strongest`]
+/// and [`scan_map`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdentityKey {
+    /// the same access point, matched by BSSID
+    Bssid,
+    /// the same SSID on the same channel, e.g. the radios of a mesh on one
+    /// channel
+    SsidChannel,
+    /// the same SSID on any channel and band
+    Ssid,
+}
+
+impl IdentityKey {
+    /// Returns the identity of `wifi`, `None` when it has none under this key
+    /// (no valid BSSID, or no channel number).
+    pub fn of(self, wifi: &Wifi) -> Option<String> {
+        match self {
+            IdentityKey::Bssid => Some(wifi.mac_addr()?.to_string()),
+            IdentityKey::SsidChannel => Some(format!("{}:{}", wifi.ssid, wifi.channel_number()?)),
+            IdentityKey::Ssid => Some(wifi.ssid.clone()),
+        }
+    }
+}
+
+/// Collapses entries with the same identity into the one with the strongest
+/// signal. On a tie, or when no entry has a parseable signal, the first one
+/// wins. Entries without an identity are all kept. The order of first
+/// appearance is kept.
+pub fn dedupe_strongest(wifis: Vec<Wifi>, key: IdentityKey) -> Vec<Wifi> {
+    dedupe_by(wifis, key, |wifi| wifi.signal_dbm().unwrap_or(i32::MIN))
+}
+
+/// Collapses entries with the same identity into the most recently seen
+/// one, e.g. when merging cached and live scans. An entry without a
+/// [`Wifi::last_seen_ms`] comes from a live scan and counts as just seen. On
+/// a tie the strongest signal wins, then the first one. Entries without an
+/// identity are all kept. The order of first appearance is kept.
+pub fn dedupe_freshest(wifis: Vec<Wifi>, key: IdentityKey) -> Vec<Wifi> {
+    dedupe_by(wifis, key, |wifi| {
+        (
+            std::cmp::Reverse(wifi.last_seen_ms.unwrap_or(0)),
+            wifi.signal_dbm().unwrap_or(i32::MIN),
+        )
+    })
+}
+
+/// Merges the scans of several machines, e.g. the sensors of a site survey,
+/// into one list holding each BSSID once, its strongest observation wins.
+/// Entries without a valid BSSID are all kept. The order of first appearance
+/// across the scans is kept.
+pub fn merge_scans(scans: Vec<Vec<Wifi>>) -> Vec<Wifi> {
+    dedupe_strongest(scans.into_iter().flatten().collect(), IdentityKey::Bssid)
+}
+
+/// Keeps the entry ranking highest of each identity, the first one on a tie
+fn dedupe_by<R: Ord>(wifis: Vec<Wifi>, key: IdentityKey, rank: impl Fn(&Wifi) -> R) -> Vec<Wifi> {
+    let mut result: Vec<Wifi> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for wifi in wifis {
+        let Some(identity) = key.of(&wifi) else {
+            result.push(wifi);
+            continue;
+        };
+        match index.get(&identity) {
+            Some(&i) => {
+                if rank(&wifi) > rank(&result[i]) {
+                    result[i] = wifi;
+                }
+            }
+            None => {
+                index.insert(identity, result.len());
+                result.push(wifi);
+            }
+        }
+    }
+    result
+}
+
+/// Scans and returns the strongest network of each identity, keyed by
+/// [`IdentityKey::of`]. Networks without an identity are left out.
+pub fn scan_map(key: IdentityKey) -> anyhow::Result<HashMap<String, Wifi>> {
+    Ok(to_map(crate::scan()?, key))
+}
+
+fn to_map(wifis: Vec<Wifi>, key: IdentityKey) -> HashMap<String, Wifi> {
+    dedupe_strongest(wifis, key)
+        .into_iter()
+        .filter_map(|wifi| Some((key.of(&wifi)?, wifi)))
+        .collect()
+}
+
+/// Scans and returns the names of the networks nearby, each once and sorted.
+/// The comparison is exact, `Office` and `office` are two names. Hidden
+/// networks are listed once as an empty string if `include_hidden` is set,
+/// left out otherwise.
+pub fn scan_ssids(include_hidden: bool) -> anyhow::Result<Vec<String>> {
+    Ok(ssids(&crate::scan()?, include_hidden))
+}
+
+pub(crate) fn ssids(wifis: &[Wifi], include_hidden: bool) -> Vec<String> {
+    wifis
+        .iter()
+        .filter(|wifi| include_hidden || !wifi.is_hidden())
+        .map(|wifi| {
+            if wifi.is_hidden() {
+                String::new()
+            } else {
+                wifi.ssid.clone()
+            }
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_nmcli;
+
+    fn fixture() -> Vec<Wifi> {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_duplicates.txt").unwrap();
+        parse_nmcli(&data).unwrap()
+    }
+
+    fn summary(wifis: &[Wifi]) -> Vec<(&str, &str, &str)> {
+        wifis
+            .iter()
+            .map(|w| (w.ssid.as_str(), w.channel.as_str(), w.mac.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn should_dedupe_by_bssid() {
+        let wifis = dedupe_strongest(fixture(), IdentityKey::Bssid);
+        assert_eq!(
+            vec![
+                ("office", "1", "AA:00:00:00:00:01"),
+                ("office", "1", "AA:00:00:00:00:02"),
+                ("office", "36", "AA:00:00:00:00:03"),
+                ("guest", "6", "AA:00:00:00:00:04"),
+                ("guest", "6", "AA:00:00:00:00:05"),
+                ("guest", "6", ""),
+            ],
+            summary(&wifis)
+        );
+        assert_eq!("-70", wifis[2].signal_level);
+    }
+
+    #[test]
+    fn should_dedupe_by_ssid_and_channel() {
+        let wifis = dedupe_strongest(fixture(), IdentityKey::SsidChannel);
+        assert_eq!(
+            vec![
+                ("office", "1", "AA:00:00:00:00:02"),
+                ("office", "36", "AA:00:00:00:00:03"),
+                ("guest", "6", "AA:00:00:00:00:04"),
+            ],
+            summary(&wifis)
+        );
+    }
+
+    #[test]
+    fn should_dedupe_by_ssid() {
+        let wifis = dedupe_strongest(fixture(), IdentityKey::Ssid);
+        assert_eq!(
+            vec![
+                ("office", "1", "AA:00:00:00:00:02"),
+                ("guest", "6", "AA:00:00:00:00:04"),
+            ],
+            summary(&wifis)
+        );
+
+        let map = to_map(fixture(), IdentityKey::Ssid);
+        assert_eq!(2, map.len());
+        assert_eq!("AA:00:00:00:00:02", map["office"].mac);
+    }
+
+    #[test]
+    fn should_dedupe_freshest() {
+        let seen = |mac: &str, signal_level: &str, last_seen_ms: Option<u32>| Wifi {
+            mac: mac.to_string(),
+            signal_level: signal_level.to_string(),
+            last_seen_ms,
+            ..Default::default()
+        };
+        let wifis = vec![
+            seen("AA:00:00:00:00:01", "-40", Some(9000)),
+            seen("AA:00:00:00:00:01", "-70", Some(120)),
+            seen("AA:00:00:00:00:02", "-60", Some(500)),
+            seen("AA:00:00:00:00:02", "-80", None),
+            seen("AA:00:00:00:00:03", "-75", Some(200)),
+            seen("AA:00:00:00:00:03", "-65", Some(200)),
+            seen("", "-50", None),
+        ];
+
+        let deduped = dedupe_freshest(wifis, IdentityKey::Bssid);
+        let signals: Vec<&str> = deduped.iter().map(|w| w.signal_level.as_str()).collect();
+        // a live entry beats a cached one, equal ages go to the strongest
+        assert_eq!(vec!["-70", "-80", "-65", "-50"], signals);
+    }
+
+    #[test]
+    fn should_merge_scans() {
+        let seen = |mac: &str, ssid: &str, signal_level: &str| Wifi {
+            mac: mac.to_string(),
+            ssid: ssid.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        };
+        let lobby = vec![
+            seen("00:00:00:00:00:01", "Office", "-70"),
+            seen("00:00:00:00:00:02", "Guest", "-50"),
+            seen("", "NoBssid", "-60"),
+        ];
+        let kitchen = vec![
+            seen("00:00:00:00:00:03", "Printer", "-80"),
+            seen("00:00:00:00:00:01", "Office", "-45"),
+            seen("00:00:00:00:00:02", "Guest", "-75"),
+            seen("", "NoBssid", "-65"),
+        ];
+        assert_eq!(
+            vec![
+                seen("00:00:00:00:00:01", "Office", "-45"),
+                seen("00:00:00:00:00:02", "Guest", "-50"),
+                seen("", "NoBssid", "-60"),
+                seen("00:00:00:00:00:03", "Printer", "-80"),
+                seen("", "NoBssid", "-65"),
+            ],
+            merge_scans(vec![lobby, kitchen])
+        );
+        assert!(merge_scans(vec![]).is_empty());
+    }
+
+    #[test]
+    fn should_list_each_ssid_once() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_ssids.txt").unwrap();
+        let wifis = parse_nmcli(&data).unwrap();
+
+        assert_eq!(vec!["Office", "cafe", "office"], ssids(&wifis, false));
+        assert_eq!(vec!["", "Office", "cafe", "office"], ssids(&wifis, true));
+    }
+}
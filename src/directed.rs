@@ -0,0 +1,83 @@
+use anyhow::Context;
+
+use crate::{channel_to_frequency, Band, Wifi};
+
+/// Returns the hotspots broadcasting `ssid`.
+///
+/// On Linux this asks `iw` to probe for the SSID, which is quicker than a
+/// full scan and also finds hidden networks with that SSID. When that fails
+/// (e.g. `iw` isn't installed or needs root) it falls back, like other
+/// platforms, to a regular [`crate::scan`] filtered to the SSID.
+pub fn scan_for_ssid(ssid: &str) -> anyhow::Result<Vec<Wifi>> {
+    #[cfg(target_os = "linux")]
+    let wifis = crate::sys::scan_for_ssid(ssid).or_else(|e| {
+        tracing::debug!("directed iw scan failed ({e}), scanning everything");
+        crate::scan()
+    })?;
+    #[cfg(not(target_os = "linux"))]
+    let wifis = crate::scan()?;
+    Ok(keep_ssid(wifis, ssid))
+}
+
+/// Returns the hotspots on `channel` of `band`, failing if there is no such
+/// channel.
+///
+/// On Linux this asks `iw` to scan only the channel's frequency, which takes
+/// the radio off its current channel for much less time than a full scan.
+/// When that fails it falls back, like other platforms, to a regular
+/// [`crate::scan`] filtered to the channel.
+pub fn scan_channel(channel: u16, band: Band) -> anyhow::Result<Vec<Wifi>> {
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    let frequency = channel_to_frequency(channel, band)
+        .with_context(|| format!("there is no channel {channel} on {band:?}"))?;
+    #[cfg(target_os = "linux")]
+    let wifis = crate::sys::scan_frequency(frequency).or_else(|e| {
+        tracing::debug!("iw scan of {frequency} MHz failed ({e}), scanning everything");
+        crate::scan()
+    })?;
+    #[cfg(not(target_os = "linux"))]
+    let wifis = crate::scan()?;
+    Ok(keep_channel(wifis, channel, band))
+}
+
+/// Keeps the hotspots on `channel` of `band`, `iw` also reports hotspots it
+/// cached from earlier scans
+fn keep_channel(wifis: Vec<Wifi>, channel: u16, band: Band) -> Vec<Wifi> {
+    wifis
+        .into_iter()
+        .filter(|wifi| wifi.channel_number() == Some(channel) && wifi.band() == Some(band))
+        .collect()
+}
+
+/// Keeps the hotspots named `ssid`, a directed scan reports others it heard
+/// too.
+fn keep_ssid(wifis: Vec<Wifi>, ssid: &str) -> Vec<Wifi> {
+    wifis.into_iter().filter(|wifi| wifi.ssid == ssid).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_nmcli;
+
+    #[test]
+    fn should_keep_only_the_ssid() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_duplicates.txt").unwrap();
+        let wifis = keep_ssid(parse_nmcli(&data).unwrap(), "guest");
+        assert_eq!(3, wifis.len());
+        assert!(wifis.iter().all(|wifi| wifi.ssid == "guest"));
+
+        assert!(keep_ssid(parse_nmcli(&data).unwrap(), "Guest").is_empty());
+    }
+
+    #[test]
+    fn should_keep_only_the_channel() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_duplicates.txt").unwrap();
+        let wifis = keep_channel(parse_nmcli(&data).unwrap(), 36, Band::Band5GHz);
+        assert_eq!(2, wifis.len());
+        assert!(wifis.iter().all(|wifi| wifi.channel == "36"));
+
+        assert!(keep_channel(parse_nmcli(&data).unwrap(), 36, Band::Band6GHz).is_empty());
+        assert!(scan_channel(14, Band::Band5GHz).is_err());
+    }
+}
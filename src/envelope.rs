@@ -0,0 +1,130 @@
+use std::time::SystemTime;
+
+use crate::json::{object, Value};
+use crate::ndjson::wifi_object;
+use crate::sort::canonicalize;
+use crate::wigle::utc_timestamp;
+use crate::Wifi;
+
+/// Version of the JSON written by [`to_json_envelope`]. It goes up whenever
+/// a key is removed or renamed or its value changes meaning; keys may be
+/// added without a new version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Scans and returns the result as [`to_json_envelope`] does, stamped with
+/// the current time
+pub fn scan_json_envelope() -> anyhow::Result<String> {
+    Ok(to_json_envelope(&crate::scan()?, SystemTime::now()))
+}
+
+/// Returns the hotspots of a scan made at `taken_at` as one JSON object,
+/// which parsers can check the version of before reading on:
+///
+/// - `schema_version`: [`JSON_SCHEMA_VERSION`]
+/// - `taken_at`: `taken_at` in UTC, e.g. `2024-02-29T23:59:30Z`
+/// - `networks`: one object per hotspot keyed by [`Wifi::field_names`],
+///   every value a string, brought into the canonical order of
+///   [`crate::canonicalize`] so equal scans give the same JSON
+pub fn to_json_envelope(wifis: &[Wifi], taken_at: SystemTime) -> String {
+    let mut wifis = wifis.to_vec();
+    canonicalize(&mut wifis);
+    object(vec![
+        (
+            "schema_version",
+            Value::Number(f64::from(JSON_SCHEMA_VERSION)),
+        ),
+        (
+            "taken_at",
+            Value::String(format!("{}Z", utc_timestamp(taken_at).replace(' ', "T"))),
+        ),
+        (
+            "networks",
+            Value::Array(wifis.iter().map(wifi_object).collect()),
+        ),
+    ])
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn should_wrap_networks_in_envelope() {
+        let wifi = |mac: &str, ssid: &str| Wifi {
+            mac: mac.to_string(),
+            ssid: ssid.to_string(),
+            ..Default::default()
+        };
+        let wifis = vec![
+            wifi("66:77:88:99:AA:BB", "Lobby"),
+            wifi("00:11:22:33:44:55", "Office"),
+        ];
+        // 2024-02-29 23:59:30 UTC
+        let taken_at = UNIX_EPOCH + Duration::from_secs(1_709_251_170);
+
+        let envelope = to_json_envelope(&wifis, taken_at);
+        let value = json::parse(&envelope).unwrap();
+        let Value::Object(entries) = &value else {
+            panic!("not an object: {envelope}");
+        };
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(vec!["schema_version", "taken_at", "networks"], keys);
+        assert_eq!(Some(&Value::Number(1.0)), value.get("schema_version"));
+        assert_eq!(
+            Some("2024-02-29T23:59:30Z"),
+            value.get("taken_at").and_then(Value::as_str)
+        );
+
+        let networks = value.get("networks").and_then(Value::as_array).unwrap();
+        let macs: Vec<&str> = networks
+            .iter()
+            .filter_map(|network| network.get("mac")?.as_str())
+            .collect();
+        assert_eq!(vec!["00:11:22:33:44:55", "66:77:88:99:aa:bb"], macs);
+        assert_eq!(
+            envelope,
+            to_json_envelope(&[wifis[1].clone(), wifis[0].clone()], taken_at)
+        );
+
+        let empty = json::parse(&to_json_envelope(&[], taken_at)).unwrap();
+        assert_eq!(
+            Some(&[][..]),
+            empty.get("networks").and_then(Value::as_array)
+        );
+    }
+
+    #[test]
+    fn should_keep_network_keys_of_schema_version() {
+        // removing or renaming one of these needs a new JSON_SCHEMA_VERSION
+        let keys_of_version: &[&str] = match JSON_SCHEMA_VERSION {
+            1 => &[
+                "mac",
+                "ssid",
+                "channel",
+                "signal_level",
+                "security",
+                "frequency_mhz",
+                "band",
+                "wifi_generation",
+                "noise_dbm",
+                "bss_color",
+                "max_rate_mbps",
+                "tx_rate_mbps",
+                "rx_rate_mbps",
+                "max_tx_power_dbm",
+            ],
+            version => panic!("no keys pinned for schema version {version}"),
+        };
+        let envelope = to_json_envelope(&[Wifi::default()], UNIX_EPOCH);
+        let value = json::parse(&envelope).unwrap();
+        let networks = value.get("networks").and_then(Value::as_array).unwrap();
+        let Value::Object(entries) = &networks[0] else {
+            panic!("not an object: {envelope}");
+        };
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys_of_version, keys);
+    }
+}
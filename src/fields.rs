@@ -0,0 +1,225 @@
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::Wifi;
+
+/// A field exporters write: its name, the title of its table column, how
+/// to read it from a hotspot, empty when unknown, and how to set it back
+/// from what `value` wrote. Fields derived from others don't set anything.
+pub(crate) struct Field {
+    pub(crate) name: &'static str,
+    #[cfg_attr(not(feature = "pretty-table"), allow(dead_code))]
+    pub(crate) title: &'static str,
+    pub(crate) value: fn(&Wifi) -> String,
+    #[cfg_attr(not(feature = "binary"), allow(dead_code))]
+    pub(crate) set: fn(&mut Wifi, &str),
+}
+
+/// Writes an optional value, empty when it is `None`
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Reads a value [`optional`] wrote, `None` when it is empty
+fn parse<T: FromStr>(value: &str) -> Option<T> {
+    value.parse().ok()
+}
+
+/// Every field exporters write, in the order [`Wifi::field_names`] lists them
+pub(crate) const FIELDS: &[Field] = &[
+    Field {
+        name: "mac",
+        title: "BSSID",
+        value: |wifi| wifi.mac.clone(),
+        set: |wifi, value| wifi.mac = value.to_string(),
+    },
+    Field {
+        name: "ssid",
+        title: "SSID",
+        value: |wifi| wifi.ssid.clone(),
+        set: |wifi, value| wifi.ssid = value.to_string(),
+    },
+    Field {
+        name: "channel",
+        title: "Channel",
+        value: |wifi| wifi.channel.clone(),
+        set: |wifi, value| wifi.channel = value.to_string(),
+    },
+    Field {
+        name: "signal_level",
+        title: "Signal",
+        value: |wifi| wifi.signal_level.clone(),
+        set: |wifi, value| wifi.signal_level = value.to_string(),
+    },
+    Field {
+        name: "security",
+        title: "Security",
+        value: |wifi| wifi.security.clone(),
+        set: |wifi, value| wifi.security = value.to_string(),
+    },
+    Field {
+        name: "frequency_mhz",
+        title: "Frequency",
+        value: |wifi| optional(wifi.frequency_mhz()),
+        // only kept when the channel doesn't already give it
+        set: |wifi, value| {
+            wifi.reported_frequency_mhz = None;
+            let reported = parse(value);
+            if wifi.frequency_mhz() != reported {
+                wifi.reported_frequency_mhz = reported;
+            }
+        },
+    },
+    Field {
+        name: "band",
+        title: "Band",
+        value: |wifi| optional(wifi.band()),
+        set: |_, _| {},
+    },
+    Field {
+        name: "wifi_generation",
+        title: "Generation",
+        value: |wifi| optional(wifi.wifi_generation),
+        set: |wifi, value| wifi.wifi_generation = parse(value),
+    },
+    Field {
+        name: "noise_dbm",
+        title: "Noise",
+        value: |wifi| optional(wifi.noise_dbm),
+        set: |wifi, value| wifi.noise_dbm = parse(value),
+    },
+    Field {
+        name: "bss_color",
+        title: "BSS color",
+        value: |wifi| optional(wifi.bss_color),
+        set: |wifi, value| wifi.bss_color = parse(value),
+    },
+    // kept until the field is removed, the positions of the later ones
+    // mustn't change
+    #[allow(deprecated)]
+    Field {
+        name: "max_rate_mbps",
+        title: "Rate",
+        value: |wifi| optional(wifi.max_rate_mbps),
+        set: |wifi, value| wifi.max_rate_mbps = parse(value),
+    },
+    Field {
+        name: "tx_rate_mbps",
+        title: "TX rate",
+        value: |wifi| optional(wifi.tx_rate_mbps),
+        set: |wifi, value| wifi.tx_rate_mbps = parse(value),
+    },
+    Field {
+        name: "rx_rate_mbps",
+        title: "RX rate",
+        value: |wifi| optional(wifi.rx_rate_mbps),
+        set: |wifi, value| wifi.rx_rate_mbps = parse(value),
+    },
+    Field {
+        name: "max_tx_power_dbm",
+        title: "Max TX power",
+        value: |wifi| optional(wifi.max_tx_power_dbm),
+        set: |wifi, value| wifi.max_tx_power_dbm = parse(value),
+    },
+];
+
+static FIELD_NAMES: LazyLock<Vec<&'static str>> =
+    LazyLock::new(|| FIELDS.iter().map(|field| field.name).collect());
+
+impl Wifi {
+    /// Names of the fields exporters write, in the order they list them:
+    /// `mac`, `ssid`, `channel`, `signal_level` and `security` as the
+    /// backend reported them, then `frequency_mhz`, `band`,
+    /// `wifi_generation`, `noise_dbm`, `bss_color`, `max_rate_mbps`
+    /// (deprecated), `tx_rate_mbps`, `rx_rate_mbps` and `max_tx_power_dbm`.
+    /// New fields are only ever added at the end.
+    pub fn field_names() -> &'static [&'static str] {
+        &FIELD_NAMES
+    }
+
+    /// Values of the fields [`Wifi::field_names`] names, in the same order,
+    /// e.g. to write a CSV row. Unknown values are empty, `band` is written
+    /// as `2.4 GHz`, `5 GHz` or `6 GHz`.
+    pub fn field_values(&self) -> Vec<String> {
+        FIELDS.iter().map(|field| (field.value)(self)).collect()
+    }
+
+    /// Raw bytes of the SSID, reading the `\xNN` escapes `iw` prints for
+    /// bytes that aren't printable ASCII, e.g. `Caf\xe9` for a Latin-1
+    /// `Café`. Other characters are taken as UTF-8. SSIDs the other backends
+    /// decoded lossily can't be recovered, their U+FFFD stays.
+    pub fn ssid_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.ssid.len());
+        let mut rest = self.ssid.as_str();
+        while let Some(c) = rest.chars().next() {
+            let escaped = rest
+                .strip_prefix("\\x")
+                .and_then(|hex| u8::from_str_radix(hex.get(..2)?, 16).ok());
+            match escaped {
+                Some(byte) => {
+                    bytes.push(byte);
+                    rest = &rest[4..];
+                }
+                None => {
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_list_fields_in_order() {
+        let wifi = Wifi {
+            mac: "11:22:33:44:55:66".to_string(),
+            ssid: "hello".to_string(),
+            channel: "6".to_string(),
+            signal_level: "-40".to_string(),
+            security: "WPA2".to_string(),
+            wifi_generation: Some(6),
+            tx_rate_mbps: Some(286.8),
+            ..Default::default()
+        };
+
+        let values = wifi.field_values();
+        assert_eq!(Wifi::field_names().len(), values.len());
+        assert_eq!(
+            ["mac", "ssid", "channel", "signal_level", "security"],
+            Wifi::field_names()[..5]
+        );
+        let value = |name: &str| {
+            let at = Wifi::field_names().iter().position(|n| *n == name);
+            values[at.unwrap()].as_str()
+        };
+        assert_eq!(
+            vec!["11:22:33:44:55:66", "hello", "6", "-40", "WPA2"],
+            values[..5]
+        );
+        assert_eq!("2437", value("frequency_mhz"));
+        assert_eq!("2.4 GHz", value("band"));
+        assert_eq!("6", value("wifi_generation"));
+        assert_eq!("286.8", value("tx_rate_mbps"));
+        assert_eq!("", value("noise_dbm"));
+    }
+
+    #[test]
+    fn should_unescape_ssid_bytes() {
+        let ssid = |ssid: &str| Wifi {
+            ssid: ssid.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(b"Caf\xe9".to_vec(), ssid("Caf\\xe9").ssid_bytes());
+        assert_eq!("Café".as_bytes(), ssid("Caf\\xc3\\xa9").ssid_bytes());
+        assert_eq!("Café".as_bytes(), ssid("Café").ssid_bytes());
+        assert_eq!(b"a\\b".to_vec(), ssid("a\\x5cb").ssid_bytes());
+        // not an escape
+        assert_eq!(b"\\xg1\\x".to_vec(), ssid("\\xg1\\x").ssid_bytes());
+        assert!(ssid("").ssid_bytes().is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Context;
+use regex::Regex;
+
+use crate::{Wifi, WifiError};
+
+/// Scans and keeps the networks whose SSID matches the regular expression
+/// `pattern`, e.g. `Store-\d+`. The pattern isn't anchored, add `^` and `$`
+/// to match the whole SSID.
+pub fn scan_ssid_matching(pattern: &str) -> anyhow::Result<Vec<Wifi>> {
+    let regex = ssid_regex(pattern)?;
+    Ok(filter_ssid(crate::scan()?, &regex))
+}
+
+/// Compiles `pattern` up front, so a typo is reported before scanning.
+fn ssid_regex(pattern: &str) -> anyhow::Result<Regex> {
+    Regex::new(pattern).with_context(|| WifiError::SyntaxRegexError)
+}
+
+fn filter_ssid(wifis: Vec<Wifi>, regex: &Regex) -> Vec<Wifi> {
+    wifis
+        .into_iter()
+        .filter(|wifi| regex.is_match(&wifi.ssid))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifis() -> Vec<Wifi> {
+        ["Store-12", "Store-7", "Store-HQ", "Guest"]
+            .into_iter()
+            .map(|ssid| Wifi {
+                ssid: ssid.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_filter_ssid_matching() {
+        let regex = ssid_regex(r"^Store-\d+$").unwrap();
+        let ssids: Vec<String> = filter_ssid(wifis(), &regex)
+            .into_iter()
+            .map(|wifi| wifi.ssid)
+            .collect();
+        assert_eq!(vec!["Store-12", "Store-7"], ssids);
+
+        let regex = ssid_regex("^Office").unwrap();
+        assert!(filter_ssid(wifis(), &regex).is_empty());
+    }
+
+    #[test]
+    fn should_reject_invalid_regex() {
+        let err = ssid_regex("Store-(").unwrap_err();
+        assert_eq!(
+            Some(&WifiError::SyntaxRegexError),
+            err.downcast_ref::<WifiError>()
+        );
+    }
+}
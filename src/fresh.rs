@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use crate::Wifi;
+
+/// Returns the hotspots heard from within `max_age`. On Linux this reads the
+/// cache of earlier scans (`iw ... scan dump`), which needs no privileges but
+/// can hold hotspots that have since gone away. Other backends always scan
+/// afresh, and hotspots without an age are kept.
+pub fn scan_fresh(max_age: Duration) -> anyhow::Result<Vec<Wifi>> {
+    Ok(drop_stale(crate::sys::scan_cached()?, max_age))
+}
+
+fn drop_stale(wifis: Vec<Wifi>, max_age: Duration) -> Vec<Wifi> {
+    wifis
+        .into_iter()
+        .filter(|wifi| {
+            wifi.last_seen_ms
+                .is_none_or(|age| u128::from(age) <= max_age.as_millis())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_iw;
+
+    #[test]
+    fn should_drop_stale_entries() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_dump_01.txt").unwrap();
+        let wifis = parse_iw(&data).unwrap();
+        let ages: Vec<Option<u32>> = wifis.iter().map(|wifi| wifi.last_seen_ms).collect();
+        assert_eq!(vec![Some(120), Some(11460), Some(240226)], ages);
+
+        let fresh = drop_stale(wifis.clone(), Duration::from_secs(30));
+        let ssids: Vec<&str> = fresh.iter().map(|wifi| wifi.ssid.as_str()).collect();
+        assert_eq!(vec!["home", "neighbour-5g"], ssids);
+
+        assert_eq!(1, drop_stale(wifis, Duration::from_secs(1)).len());
+
+        // backends without an age keep everything
+        assert_eq!(1, drop_stale(vec![Wifi::default()], Duration::ZERO).len());
+    }
+}
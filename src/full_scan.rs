@@ -0,0 +1,61 @@
+use crate::{InterfaceScan, Wifi};
+
+/// Everything a dashboard shows on its first load, returned by [`scan_full`]
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct FullScan {
+    /// the network this machine is connected to, see
+    /// [`crate::current_connection`]
+    pub connected: Option<Wifi>,
+    /// the hotspots in your area, see [`crate::scan`]
+    pub networks: Vec<Wifi>,
+    /// names of the wireless interfaces, e.g. `en0` or `wlp2s0`
+    pub interfaces: Vec<String>,
+}
+
+impl FullScan {
+    /// Combines the scans of every interface, the connected network being the
+    /// first one marked associated
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn from_interfaces(scans: Vec<InterfaceScan>) -> FullScan {
+        let mut full = FullScan::default();
+        for scan in scans {
+            full.interfaces.push(scan.interface);
+            full.networks.extend(scan.networks);
+        }
+        full.connected = full.networks.iter().find(|wifi| wifi.associated).cloned();
+        full
+    }
+}
+
+/// Returns the connected network, the hotspots in your area and the wireless
+/// interfaces in one call.
+///
+/// On macOS a single `system_profiler` run reports all three. Elsewhere this
+/// is [`crate::scan`] and [`crate::current_connection`], with the interfaces
+/// from `iw dev` (or `/proc/net/wireless`) on Linux and from
+/// `netsh wlan show interfaces` on Windows, which also gives the connected
+/// network.
+pub fn scan_full() -> anyhow::Result<FullScan> {
+    crate::sys::scan_full()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_systemprofiler_interfaces;
+
+    #[test]
+    fn should_combine_interface_scans() {
+        let data =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_01.json").unwrap();
+        let full = FullScan::from_interfaces(parse_systemprofiler_interfaces(&data).unwrap());
+
+        assert_eq!(vec!["en0", "awdl0"], full.interfaces);
+        assert_eq!(
+            Some("HomeNetwork"),
+            full.connected.as_ref().map(|w| w.ssid.as_str())
+        );
+        let ssids: Vec<&str> = full.networks.iter().map(|w| w.ssid.as_str()).collect();
+        assert_eq!(vec!["HomeNetwork", "Neighbour 2.4", "CoffeeShop"], ssids);
+    }
+}
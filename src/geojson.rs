@@ -0,0 +1,100 @@
+use crate::json::{object, Value};
+use crate::Wifi;
+
+/// Returns a GeoJSON `FeatureCollection` with a `Point` for every hotspot
+/// that has a [`Wifi::location`], hotspots without one are left out. The
+/// hotspot fields go in the `properties`. The crate never fills in locations
+/// itself, set them from your own GPS fixes first.
+pub fn to_geojson(wifis: &[Wifi]) -> String {
+    let features = wifis
+        .iter()
+        .filter_map(|wifi| {
+            let (lat, lon) = wifi.location?;
+            Some(object(vec![
+                ("type", Value::String("Feature".to_string())),
+                (
+                    "geometry",
+                    object(vec![
+                        ("type", Value::String("Point".to_string())),
+                        // GeoJSON puts the longitude first
+                        (
+                            "coordinates",
+                            Value::Array(vec![Value::Number(lon), Value::Number(lat)]),
+                        ),
+                    ]),
+                ),
+                ("properties", properties(wifi)),
+            ]))
+        })
+        .collect();
+    object(vec![
+        ("type", Value::String("FeatureCollection".to_string())),
+        ("features", Value::Array(features)),
+    ])
+    .to_string()
+}
+
+fn properties(wifi: &Wifi) -> Value {
+    let string = |s: &str| Value::String(s.to_string());
+    object(vec![
+        ("bssid", string(&wifi.mac)),
+        ("ssid", string(&wifi.ssid)),
+        ("channel", string(&wifi.channel)),
+        ("signal_level", string(&wifi.signal_level)),
+        ("security", string(&wifi.security)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn should_export_located_networks() {
+        let wifis = [
+            Wifi {
+                mac: "11:22:33:44:55:66".to_string(),
+                ssid: "located".to_string(),
+                location: Some((51.5072, -0.1276)),
+                ..Default::default()
+            },
+            Wifi {
+                ssid: "nowhere".to_string(),
+                ..Default::default()
+            },
+        ];
+        let geojson = json::parse(&to_geojson(&wifis)).unwrap();
+
+        assert_eq!(
+            Some("FeatureCollection"),
+            geojson.get("type").and_then(Value::as_str)
+        );
+        let features = geojson.get("features").and_then(Value::as_array).unwrap();
+        assert_eq!(1, features.len());
+
+        let geometry = features[0].get("geometry").unwrap();
+        assert_eq!(Some("Point"), geometry.get("type").and_then(Value::as_str));
+        assert_eq!(
+            Some(&[Value::Number(-0.1276), Value::Number(51.5072)][..]),
+            geometry.get("coordinates").and_then(Value::as_array)
+        );
+        let properties = features[0].get("properties").unwrap();
+        assert_eq!(
+            Some("located"),
+            properties.get("ssid").and_then(Value::as_str)
+        );
+        assert_eq!(
+            Some("11:22:33:44:55:66"),
+            properties.get("bssid").and_then(Value::as_str)
+        );
+    }
+
+    #[test]
+    fn should_export_empty_collection() {
+        assert_eq!(
+            r#"{"type":"FeatureCollection","features":[]}"#,
+            to_geojson(&[Wifi::default()])
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+use crate::Wifi;
+
+/// Renders the networks in the InfluxDB line protocol, one line per network
+/// with a parseable signal, e.g.
+/// `wifi,bssid=11:22:33:44:55:66,channel=6,security=WPA2,ssid=my\ home signal_dbm=-67i,frequency_mhz=2437i 1700000000000000000`.
+///
+/// The tags are `bssid`, `channel`, `security` and `ssid`, sorted by key as
+/// InfluxDB prefers them. Empty tags are left out since the protocol doesn't
+/// allow them. The fields are `signal_dbm` and, when known, `frequency_mhz`,
+/// `noise_dbm`, `tx_rate_mbps`, `rx_rate_mbps` and `max_tx_power_dbm`, so
+/// they stay numbers and don't add series. Control characters
+/// such as newlines, which would end the line, become spaces. Commas, equal
+/// signs and spaces in tag values, and commas and spaces in `measurement`,
+/// are escaped with a backslash.
+pub fn to_influx_line(wifis: &[Wifi], measurement: &str, timestamp_ns: u64) -> String {
+    fn escape(value: &str, special: &[char]) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            let c = if c.is_control() { ' ' } else { c };
+            if special.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    let measurement = escape(measurement, &[',', ' ']);
+    let mut out = String::new();
+    for wifi in wifis {
+        let Some(dbm) = wifi.signal_dbm() else {
+            continue;
+        };
+        out.push_str(&measurement);
+        let tags = [
+            ("bssid", &wifi.mac),
+            ("channel", &wifi.channel),
+            ("security", &wifi.security),
+            ("ssid", &wifi.ssid),
+        ];
+        for (key, value) in tags {
+            if !value.is_empty() {
+                let _ = write!(out, ",{key}={}", escape(value, &[',', '=', ' ']));
+            }
+        }
+        let _ = write!(out, " signal_dbm={dbm}i");
+        let integers = [
+            ("frequency_mhz", wifi.frequency_mhz().map(i64::from)),
+            ("noise_dbm", wifi.noise_dbm.map(i64::from)),
+        ];
+        for (key, value) in integers {
+            if let Some(value) = value {
+                let _ = write!(out, ",{key}={value}i");
+            }
+        }
+        let floats = [
+            ("tx_rate_mbps", wifi.tx_rate_mbps),
+            ("rx_rate_mbps", wifi.rx_rate_mbps),
+        ];
+        for (key, value) in floats {
+            if let Some(value) = value {
+                let _ = write!(out, ",{key}={value}");
+            }
+        }
+        if let Some(power) = wifi.max_tx_power_dbm {
+            let _ = write!(out, ",max_tx_power_dbm={power}i");
+        }
+        let _ = writeln!(out, " {timestamp_ns}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_export_influx_line() {
+        let wifis = vec![
+            Wifi {
+                mac: "11:22:33:44:55:66".to_string(),
+                ssid: "my home,2=b".to_string(),
+                channel: "6".to_string(),
+                signal_level: "-67.00".to_string(),
+                security: "WPA2 WPA3".to_string(),
+                noise_dbm: Some(-92),
+                tx_rate_mbps: Some(286.8),
+                max_tx_power_dbm: Some(20),
+                ..Default::default()
+            },
+            Wifi {
+                ssid: "no signal".to_string(),
+                ..Default::default()
+            },
+            Wifi {
+                ssid: "cafe".to_string(),
+                signal_level: "-80".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let expected = r"wifi\ scan,bssid=11:22:33:44:55:66,channel=6,security=WPA2\ WPA3,ssid=my\ home\,2\=b signal_dbm=-67i,frequency_mhz=2437i,noise_dbm=-92i,tx_rate_mbps=286.8,max_tx_power_dbm=20i 1700000000000000000
+wifi\ scan,ssid=cafe signal_dbm=-80i 1700000000000000000
+";
+        assert_eq!(
+            expected,
+            to_influx_line(&wifis, "wifi scan", 1_700_000_000_000_000_000)
+        );
+        assert_eq!("", to_influx_line(&[], "wifi", 0));
+    }
+
+    #[test]
+    fn should_replace_control_characters() {
+        let wifis = vec![Wifi {
+            ssid: "a\nb\r\tc".to_string(),
+            signal_level: "-70".to_string(),
+            ..Default::default()
+        }];
+
+        let line = to_influx_line(&wifis, "wifi\n", 0);
+        assert_eq!(1, line.lines().count());
+        assert_eq!("wifi\\ ,ssid=a\\ b\\ \\ c signal_dbm=-70i 0\n", line);
+    }
+}
@@ -0,0 +1,279 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+use crate::{InterfaceScan, MacAddr, Wifi, WifiError};
+
+/// One lock per interface scanned so far, see [`with_interface_lock`]
+static SCAN_LOCKS: Mutex<Vec<(String, Arc<Mutex<()>>)>> = Mutex::new(Vec::new());
+
+/// Interfaces that scanned, and the interfaces whose scan failed with why
+pub type AllInterfacesScan = (Vec<InterfaceScan>, Vec<(String, anyhow::Error)>);
+
+/// Like [`crate::scan_with_interfaces`] but scans every interface at the
+/// same time, each on a thread of its own, so a machine with many radios
+/// waits for the slowest scan rather than for all of them in turn. An
+/// interface failing to scan doesn't fail the call, it is returned along
+/// with its error instead. Only Linux scans per interface (with `iw`), on
+/// macOS and Windows this is the same as [`crate::scan_with_interfaces`].
+pub fn scan_all_interfaces() -> anyhow::Result<AllInterfacesScan> {
+    crate::sys::scan_all_interfaces(0)
+}
+
+/// Like [`scan_all_interfaces`] but with at most `max_threads` interfaces
+/// scanning at the same time, e.g. so many USB adapters on one hub don't all
+/// scan at once. The other interfaces wait for a scan to finish. Zero
+/// scans every interface at once, which is what [`scan_all_interfaces`]
+/// does.
+pub fn scan_all_interfaces_with_max_threads(
+    max_threads: usize,
+) -> anyhow::Result<AllInterfacesScan> {
+    crate::sys::scan_all_interfaces(max_threads)
+}
+
+/// Returns the names of the wireless interfaces, e.g. `en0` or `wlp2s0`.
+/// Uses `iw dev` (or `/proc/net/wireless`) on Linux, `system_profiler` on
+/// macOS and `netsh wlan show interfaces` on Windows.
+pub fn list_interfaces() -> anyhow::Result<Vec<String>> {
+    crate::sys::interface_names()
+}
+
+/// Returns the hotspots seen by `interface` alone, failing with
+/// [`WifiError::InterfaceNotFound`] when no wireless interface has that
+/// name. Uses `iw` on Linux, `system_profiler` on macOS and `netsh` on
+/// Windows.
+pub fn scan_on_interface(interface: &str) -> anyhow::Result<Vec<Wifi>> {
+    crate::sys::scan_interface(interface)
+}
+
+/// Fails with [`WifiError::InterfaceNotFound`] unless `interface` is one of
+/// `interfaces`
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub(crate) fn ensure_interface(interfaces: &[String], interface: &str) -> anyhow::Result<()> {
+    if interfaces.iter().any(|name| name == interface) {
+        Ok(())
+    } else {
+        Err(WifiError::InterfaceNotFound(interface.to_string()).into())
+    }
+}
+
+/// Runs `scan` for each interface on `max_threads` threads, or one per
+/// interface when zero, keeping the order of `interfaces`. A scan that
+/// panics fails its interface alone, its thread goes on with the next one.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn scan_concurrently(
+    interfaces: Vec<(String, Option<MacAddr>)>,
+    max_threads: usize,
+    scan: impl Fn(&str) -> anyhow::Result<Vec<Wifi>> + Sync,
+) -> AllInterfacesScan {
+    let threads = match max_threads {
+        0 => interfaces.len(),
+        max_threads => max_threads.min(interfaces.len()),
+    };
+    // each thread takes the next interface nobody scans yet
+    let next = AtomicUsize::new(0);
+    let scanned: Vec<_> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some((interface, _)) = interfaces.get(index) else {
+                            break results;
+                        };
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| scan(interface)))
+                            .unwrap_or_else(|_| Err(anyhow::anyhow!("scan panicked")));
+                        results.push((index, result));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+    let mut results: Vec<_> = interfaces.iter().map(|_| None).collect();
+    for (index, result) in scanned {
+        results[index] = Some(result);
+    }
+    let results = results
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|| Err(anyhow::anyhow!("scan panicked"))));
+
+    let mut scans = Vec::new();
+    let mut errors = Vec::new();
+    for ((interface, own_mac), result) in interfaces.into_iter().zip(results) {
+        match result {
+            Ok(networks) => scans.push(InterfaceScan {
+                interface,
+                own_mac,
+                networks,
+            }),
+            Err(e) => {
+                tracing::debug!("scanning {interface} failed: {e}");
+                errors.push((interface, e));
+            }
+        }
+    }
+    (scans, errors)
+}
+
+/// Runs `scan` once no other scan of `interface` in this process is running,
+/// as a second scan would fail with `Device or resource busy`. Scans of
+/// other interfaces run alongside.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn with_interface_lock<T>(interface: &str, scan: impl FnOnce() -> T) -> T {
+    let lock = {
+        let mut locks = SCAN_LOCKS.lock().unwrap_or_else(PoisonError::into_inner);
+        match locks.iter().find(|(name, _)| name == interface) {
+            Some((_, lock)) => Arc::clone(lock),
+            None => {
+                let lock = Arc::new(Mutex::new(()));
+                locks.push((interface.to_string(), Arc::clone(&lock)));
+                lock
+            }
+        }
+    };
+    let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+    scan()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Barrier};
+    use std::time::Duration;
+
+    #[test]
+    fn should_serialize_scans_of_an_interface() {
+        // fails like iw does when another scan of the interface is running
+        let running = AtomicUsize::new(0);
+        let scans = AtomicUsize::new(0);
+        let busy_scan = || {
+            with_interface_lock("wlan-locked", || {
+                if running.fetch_add(1, Ordering::SeqCst) > 0 {
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    anyhow::bail!("Device or resource busy");
+                }
+                thread::sleep(Duration::from_millis(50));
+                scans.fetch_add(1, Ordering::SeqCst);
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            })
+        };
+        let results: Vec<anyhow::Result<()>> = thread::scope(|scope| {
+            let threads: Vec<_> = (0..2).map(|_| scope.spawn(busy_scan)).collect();
+            threads.into_iter().map(|t| t.join().unwrap()).collect()
+        });
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+        assert_eq!(2, scans.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn should_scan_other_interfaces_alongside() {
+        // each scan waits for the other to start, which never happens if
+        // they are serialized
+        let (wlan0_started, wlan0_seen) = mpsc::channel();
+        let (wlan1_started, wlan1_seen) = mpsc::channel();
+        thread::scope(|scope| {
+            let wlan0 = scope.spawn(move || {
+                with_interface_lock("wlan-parallel0", || {
+                    wlan0_started.send(()).unwrap();
+                    wlan1_seen.recv_timeout(Duration::from_secs(5))
+                })
+            });
+            let wlan1 = scope.spawn(move || {
+                with_interface_lock("wlan-parallel1", || {
+                    wlan1_started.send(()).unwrap();
+                    wlan0_seen.recv_timeout(Duration::from_secs(5))
+                })
+            });
+            assert!(wlan0.join().unwrap().is_ok());
+            assert!(wlan1.join().unwrap().is_ok());
+        });
+    }
+
+    #[test]
+    fn should_scan_interfaces_concurrently() {
+        let interfaces = vec![
+            ("wlan0".to_string(), "02:00:00:00:00:01".parse().ok()),
+            ("wlan1".to_string(), None),
+            ("wlan2".to_string(), None),
+        ];
+        // only passes once every scan is running at the same time
+        let barrier = Barrier::new(interfaces.len());
+        let (scans, errors) = scan_concurrently(interfaces, 0, |interface| {
+            barrier.wait();
+            let fixture = match interface {
+                "wlan0" => "iw_dev_scan_01.txt",
+                "wlan1" => "iw_dev_scan_ht40.txt",
+                _ => anyhow::bail!("device busy"),
+            };
+            let data = std::fs::read_to_string(format!("tests/fixtures/iw/{fixture}"))?;
+            crate::parse_iw(&data)
+        });
+
+        let tagged: Vec<(&str, usize)> = scans
+            .iter()
+            .map(|scan| (scan.interface.as_str(), scan.networks.len()))
+            .collect();
+        let expected = |fixture: &str| {
+            let data = std::fs::read_to_string(format!("tests/fixtures/iw/{fixture}")).unwrap();
+            crate::parse_iw(&data).unwrap().len()
+        };
+        assert_eq!(
+            vec![
+                ("wlan0", expected("iw_dev_scan_01.txt")),
+                ("wlan1", expected("iw_dev_scan_ht40.txt")),
+            ],
+            tagged
+        );
+        assert_eq!("02:00:00:00:00:01".parse().ok(), scans[0].own_mac);
+
+        assert_eq!(1, errors.len());
+        assert_eq!("wlan2", errors[0].0);
+        assert_eq!("device busy", errors[0].1.to_string());
+    }
+
+    #[test]
+    fn should_limit_concurrent_scans() {
+        let interfaces: Vec<_> = (0..6).map(|i| (format!("wlan{i}"), None)).collect();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let (scans, errors) = scan_concurrently(interfaces, 2, |_| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+            Ok(vec![])
+        });
+
+        assert_eq!(2, peak.load(Ordering::SeqCst));
+        assert!(errors.is_empty());
+        let order: Vec<&str> = scans.iter().map(|scan| scan.interface.as_str()).collect();
+        assert_eq!(
+            vec!["wlan0", "wlan1", "wlan2", "wlan3", "wlan4", "wlan5"],
+            order
+        );
+    }
+
+    #[test]
+    fn should_fail_only_the_interface_whose_scan_panicked() {
+        let interfaces: Vec<_> = (0..5).map(|i| (format!("wlan{i}"), None)).collect();
+        let (scans, errors) = scan_concurrently(interfaces, 2, |interface| {
+            if interface == "wlan1" {
+                panic!("driver crashed");
+            }
+            Ok(vec![Wifi::default()])
+        });
+
+        let scanned: Vec<&str> = scans.iter().map(|scan| scan.interface.as_str()).collect();
+        assert_eq!(vec!["wlan0", "wlan2", "wlan3", "wlan4"], scanned);
+        assert_eq!(1, errors.len());
+        assert_eq!("wlan1", errors[0].0);
+        assert_eq!("scan panicked", errors[0].1.to_string());
+    }
+}
@@ -36,64 +36,503 @@
 //! Alternatively if you've cloned the the Git repo, you can run the above example
 //! using: `cargo run --example scan`.
 
-//TODO need to find a way to move these out of lib and into sys or better still windows module
-#[cfg(target_os = "windows")]
-#[macro_use]
-extern crate itertools;
-#[cfg(target_os = "windows")]
-extern crate regex;
-
+mod analysis;
+mod anonymize;
+mod average;
+mod backend;
+mod bench;
+mod best_effort;
+#[cfg(feature = "binary")]
+mod binary;
+mod cache;
+mod channel;
+mod connect;
+mod dedup;
+mod directed;
+#[cfg(feature = "serde")]
+mod envelope;
+mod fields;
+#[cfg(feature = "regex")]
+mod filter;
+mod fresh;
+mod full_scan;
+#[cfg(feature = "serde")]
+mod geojson;
+#[cfg(feature = "influx")]
+mod influx;
+mod interfaces;
+mod json;
+mod mac;
+mod mesh;
+#[cfg(feature = "serde")]
+mod ndjson;
+mod options;
+mod output;
+pub mod parse;
+#[cfg(feature = "pcap")]
+mod pcap;
+mod platform;
+#[cfg(feature = "plist")]
+mod plist;
+pub mod prelude;
+mod presence;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+mod qr;
+mod rate_limit;
+mod rssi;
+mod scan_log;
+mod security;
+mod signal;
+mod sort;
+mod steer;
+mod survey;
 mod sys;
+#[cfg(feature = "pretty-table")]
+mod table;
+#[cfg(any(test, feature = "test-util"))]
+mod test_util;
+mod validate;
+mod vendor;
+mod watch;
+mod wifi_filter;
+mod wigle;
+
+pub use analysis::{
+    band_congestion_index, band_summary, channel_histogram, diff, diff_with_delta,
+    group_by_base_bssid, interference_report, recommend_channel_5g, ssid_diff, BandSummary,
+    ChannelStats, InterferenceKind, InterferencePair, InterferenceSeverity, ScanDiff, ScanStats,
+    DEFAULT_SIGNAL_DELTA_DBM,
+};
+pub use anonymize::anonymize;
+pub use average::scan_averaged;
+pub use backend::{register_backend, scan_with_meta, ScanBackend, ScanMeta, BACKEND_ENV};
+pub use bench::{scan_n_times, ScanBench};
+pub use best_effort::try_scan;
+#[cfg(feature = "binary")]
+pub use binary::{from_bytes, to_bytes};
+pub use cache::scan_cached;
+pub use channel::{
+    channel_to_frequency, frequency_to_channel, is_valid_channel, Band, ChannelOffset,
+    CHANNELS_2_4, CHANNELS_5, CHANNELS_6,
+};
+pub use dedup::{
+    dedupe_freshest, dedupe_strongest, merge_scans, scan_map, scan_ssids, IdentityKey,
+};
+pub use directed::{scan_channel, scan_for_ssid};
+#[cfg(feature = "serde")]
+pub use envelope::{scan_json_envelope, to_json_envelope, JSON_SCHEMA_VERSION};
+#[cfg(feature = "regex")]
+pub use filter::scan_ssid_matching;
+pub use fresh::scan_fresh;
+pub use full_scan::{scan_full, FullScan};
+#[cfg(feature = "serde")]
+pub use geojson::to_geojson;
+#[cfg(feature = "influx")]
+pub use influx::to_influx_line;
+pub use interfaces::{
+    list_interfaces, scan_all_interfaces, scan_all_interfaces_with_max_threads, scan_on_interface,
+    AllInterfacesScan,
+};
+pub use mac::MacAddr;
+pub use mesh::MeshInfo;
+#[cfg(feature = "serde")]
+pub use ndjson::{to_ndjson, to_ndjson_with, SsidEncoding};
+pub use options::{scan_with_options, CustomCommand, ScanOptions, SignalParser};
+pub use output::{parse_any, scan_from_output, set_max_output_bytes, DEFAULT_MAX_OUTPUT_BYTES};
+pub use parse::{
+    parse_airport, parse_airport_info, parse_beacon_frames, parse_iw, parse_iwctl, parse_iwlist,
+    parse_netsh, parse_nmcli, parse_systemprofiler, parse_systemprofiler_interfaces, parse_wdutil,
+};
+#[cfg(feature = "plist")]
+pub use parse::{parse_systemprofiler_plist, parse_systemprofiler_plist_interfaces};
+#[cfg(feature = "pcap")]
+pub use pcap::from_pcap;
+pub use platform::{backend_capabilities, BackendCapabilities, Platform};
+pub use presence::PresenceTracker;
+#[cfg(feature = "prometheus")]
+pub use prometheus::to_prometheus;
+pub use rate_limit::{set_min_scan_interval, set_rate_limit_mode, RateLimitMode};
+pub use rssi::RssiTracker;
+pub use scan_log::ScanLog;
+pub use security::{PmfMode, SecurityKind, CAPTIVE_PORTAL_KEYWORDS};
+pub use signal::{dbm_to_mw, mw_to_dbm, signal_bars, SignalQuality};
+pub use sort::{canonicalize, sort_by_signal, sort_by_ssid, HiddenReason};
+pub use steer::{best_ap_for_ssid, best_ap_for_ssid_with_margin, DEFAULT_BAND_MARGIN_DB};
+#[cfg(target_os = "linux")]
+pub use survey::channel_survey;
+pub use survey::ChannelSurvey;
+#[cfg(feature = "pretty-table")]
+pub use table::format_pretty_table;
+#[cfg(any(test, feature = "test-util"))]
+pub use test_util::{assert_networks_eq_ignoring_signal, ReplayBackend};
+pub use validate::{validate, ValidationIssue};
+pub use vendor::{group_by_vendor, scan_by_vendor, set_oui_database, UNKNOWN_VENDOR};
+pub use watch::{scan_continuously, WatchEvent, Watcher};
+pub use wifi_filter::{find_rogues, scan_filtered, WifiFilter};
+pub use wigle::{to_wigle_csv, to_wigle_csv_at};
 
 use std::fmt;
 use std::process::ExitStatus;
+use std::time::Duration;
 
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq)]
-pub enum Error {
+pub enum WifiError {
     SyntaxRegexError,
     CommandNotFound,
-    CommandFailed(ExitStatus, String),
+    /// the command exited unsuccessfully, or printed nothing but an error
+    CommandFailed {
+        /// program and arguments that were run, empty when the output was
+        /// captured elsewhere
+        command: Vec<String>,
+        status: ExitStatus,
+        /// stderr, or stdout when stderr is empty, cut to 512 characters
+        reason: String,
+    },
     NoMatch,
     FailedToParse,
     NoValue,
     HeaderNotFound(&'static str),
+    /// the scan command didn't finish in time and was killed
+    Timeout(Duration),
+    /// this many entries of the command output couldn't be parsed and were
+    /// left out
+    PartialParse(usize),
+    /// the backend named in `WIFISCANNER_BACKEND` doesn't exist or can't run
+    /// here
+    BackendUnavailable(String),
+    /// the command printed more than this many bytes and was killed, see
+    /// [`set_max_output_bytes`]
+    OutputTooLarge(usize),
+    /// the command isn't allowed to scan, e.g. `iw` run without
+    /// `CAP_NET_ADMIN`
+    PermissionDenied {
+        /// how to give the command the permission
+        hint: String,
+    },
+    /// a string that should name a [`Band`] or [`SecurityKind`] doesn't, or
+    /// a value is of an unsupported version
+    InvalidValue {
+        /// what was expected, with the accepted values
+        expected: &'static str,
+        value: String,
+    },
+    /// no wireless interface has this name
+    InterfaceNotFound(String),
 }
 
+/// Former name of [`WifiError`], kept for backwards compatibility
+pub type Error = WifiError;
+
 /// Wifi struct used to return information about wifi hotspots
-#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone)]
 pub struct Wifi {
     /// mac address
     pub mac: String,
     /// hotspot name
     pub ssid: String,
+    /// hotspot name as the tool printed it, keeping the trailing spaces that
+    /// `ssid` drops along with padding. Only set by `airport`, whose SSID
+    /// column is right-aligned: trailing spaces survive but leading ones
+    /// can't be told apart from the column padding and are lost.
+    pub ssid_raw: Option<String>,
+    /// whether the SSID element named the network, was blank or was left
+    /// out, `ssid` is empty for the latter. Only set by `iw`.
+    pub hidden_reason: Option<HiddenReason>,
+    /// name the hotspot gives itself, apart from its SSID, e.g. the Device
+    /// Name of its WPS element or the AP name of a Cisco Aironet element.
+    /// Only set by `iw`.
+    pub device_name: Option<String>,
     pub channel: String,
+    /// centre frequency of the primary channel in MHz as the backend
+    /// reported it, which unlike the channel number tells the band. Only set
+    /// by `iw`, see [`Wifi::frequency_mhz()`] for the other backends.
+    pub reported_frequency_mhz: Option<u32>,
+    /// channel from the DS Parameter Set element when it isn't the one of
+    /// [`Wifi::reported_frequency_mhz`], a sign of a misconfigured hotspot.
+    /// [`Wifi::channel`] is the DS Parameter Set one then. Only set by `iw`.
+    pub advertised_channel: Option<u16>,
     /// wifi signal strength in dBm
     pub signal_level: String,
     /// this field is currently empty in the Linux version of the lib
     pub security: String,
+    /// Wi-Fi generation (4 for n, 5 for ac, 6 for ax, 7 for be), from the
+    /// highest capability the hotspot advertises. Only set by `iw`, `wdutil`
+    /// and `netsh`.
+    pub wifi_generation: Option<u8>,
+    /// noise level in dBm, only set by `system_profiler` on macOS
+    pub noise_dbm: Option<i32>,
+    /// whether this machine is connected to the hotspot, only known to
+    /// `system_profiler` on macOS
+    pub associated: bool,
+    /// how long the hotspot has been up, from its TSF timer. Only set by
+    /// `iw`, a value going down between scans means the hotspot rebooted.
+    pub uptime: Option<Duration>,
+    /// raw 802.11 capability flags followed by the RSN capability flags, e.g.
+    /// `ESS Privacy ShortSlotTime 1-PTKSA-RC`. Only set by `iw`.
+    pub capabilities: Option<Vec<String>>,
+    /// management frame protection the hotspot supports, from its RSN
+    /// capabilities. `None` without an RSN element. Only set by `iw`.
+    pub pmf: Option<PmfMode>,
+    /// whether the RSN element pairs GCMP-256 with the BIP-GMAC-256 group
+    /// management cipher, the ciphers of WPA3-Enterprise 192-bit mode. Only
+    /// set by `iw`, see [`Wifi::security_kind`].
+    pub suite_b_ciphers: bool,
+    /// channel widths in MHz the hotspot supports, from its HT, VHT and HE
+    /// capabilities, e.g. `[20, 40, 80, 160]`. It may operate on a narrower
+    /// one. Only set by `iw`.
+    pub supported_widths: Vec<u16>,
+    /// BSS Color from the HE Operation element, 1 to 63, which Wi-Fi 6
+    /// hotspots use to tell their frames from those of neighbours on the
+    /// same channel. Only set by `iw`.
+    pub bss_color: Option<u8>,
+    /// secondary channel of a 40 MHz bonded channel. Only set by `iw`.
+    pub secondary_channel_offset: Option<ChannelOffset>,
+    /// global operating class the hotspot says it operates in, from its
+    /// Supported Operating Classes element. Only set by `iw`, see
+    /// [`Wifi::operating_class`] for the other backends.
+    pub current_operating_class: Option<u8>,
+    /// how long ago the hotspot was last heard from, in milliseconds. Only
+    /// set by `iw`, which also reports hotspots it cached from earlier scans.
+    pub last_seen_ms: Option<u32>,
+    /// BSSID of the hotspot that beacons for this one, when this is a virtual
+    /// BSSID announced in a Multiple BSSID element. Only set by `iw`.
+    pub transmitted_bssid: Option<MacAddr>,
+    /// [`Wifi::tx_rate_mbps`] as a whole number, still set by `airport -I`
+    /// and `system_profiler` but not by the other backends
+    #[deprecated(note = "use `tx_rate_mbps`")]
+    pub max_rate_mbps: Option<u32>,
+    /// rate in Mbit/s of the last frame sent to the hotspot. Only set for the
+    /// connected network by `iw`, `airport -I` and `netsh`, and by
+    /// `system_profiler` when it tells the rate of a network.
+    pub tx_rate_mbps: Option<f64>,
+    /// rate in Mbit/s of the last frame received from the hotspot. Only set
+    /// for the connected network, by `iw` and `netsh`.
+    pub rx_rate_mbps: Option<f64>,
+    /// most the hotspot may transmit with on its channel in dBm, from its
+    /// Country element less its Power Constraint. Only set by `iw`.
+    pub max_tx_power_dbm: Option<i32>,
+    /// power the hotspot says it transmits the frame with in dBm, from its
+    /// TPC Report element. Only set by `iw`.
+    pub tpc_tx_power_dbm: Option<i32>,
+    /// link margin in dB from the TPC Report element. Beacons leave it
+    /// reserved and `iw` only prints it when it's there, so it is rarely
+    /// set. Only set by `iw`.
+    pub link_margin_db: Option<i32>,
+    /// the 802.11s mesh the hotspot belongs to. Only set by `iw`.
+    pub mesh: Option<MeshInfo>,
+    /// where the hotspot was seen as (latitude, longitude). Scanning never
+    /// fills this in, it is there for apps to attach their own GPS fix.
+    pub location: Option<(f64, f64)>,
 }
 
-impl fmt::Display for Error {
+/// The networks seen by one wireless interface
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct InterfaceScan {
+    /// interface name, e.g. `en0` or `wlp2s0`
+    pub interface: String,
+    /// the interface's own MAC address, when the tool reports it
+    pub own_mac: Option<MacAddr>,
+    pub networks: Vec<Wifi>,
+}
+
+impl fmt::Display for WifiError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::SyntaxRegexError => write!(f, "An error occured during syntax check"),
-            Error::CommandNotFound => write!(f, "Couldn't find command"),
-            Error::CommandFailed(status, reason) => {
-                write!(f, "Command failed with exit status {}: {}", status, reason)
+            WifiError::SyntaxRegexError => write!(f, "An error occured during syntax check"),
+            WifiError::CommandNotFound => write!(f, "Couldn't find command"),
+            WifiError::CommandFailed {
+                command,
+                status,
+                reason,
+            } => {
+                write!(f, "Command ")?;
+                if !command.is_empty() {
+                    let quoted: Vec<String> = command.iter().map(|arg| quote(arg)).collect();
+                    write!(f, "`{}` ", quoted.join(" "))?;
+                }
+                match status.code() {
+                    Some(code) => write!(f, "failed with exit code {}: {}", code, reason),
+                    None => write!(f, "failed with {}: {}", status, reason),
+                }
             }
-            Error::NoMatch => write!(f, "Couldn't match"),
-            Error::FailedToParse => write!(f, "Failed to parse command"),
-            Error::NoValue => write!(f, "Value expected but is not present"),
-            Error::HeaderNotFound(header) => {
+            WifiError::NoMatch => write!(f, "Couldn't match"),
+            WifiError::FailedToParse => write!(f, "Failed to parse command"),
+            WifiError::NoValue => write!(f, "Value expected but is not present"),
+            WifiError::HeaderNotFound(header) => {
                 write!(f, "Did not find header {} but expected it", header)
             }
+            WifiError::Timeout(timeout) => write!(f, "Command timed out after {:?}", timeout),
+            WifiError::PartialParse(skipped) => {
+                write!(f, "{} entries could not be parsed", skipped)
+            }
+            WifiError::BackendUnavailable(name) => {
+                write!(
+                    f,
+                    "Backend {:?} from WIFISCANNER_BACKEND is not available",
+                    name
+                )
+            }
+            WifiError::OutputTooLarge(limit) => {
+                write!(f, "Command printed more than {} bytes", limit)
+            }
+            WifiError::PermissionDenied { hint } => write!(f, "Permission denied: {}", hint),
+            WifiError::InvalidValue { expected, value } => {
+                write!(f, "{:?} is not a valid {}", value, expected)
+            }
+            WifiError::InterfaceNotFound(name) => {
+                write!(f, "No wireless interface named {:?}", name)
+            }
         }
     }
 }
 
+impl std::error::Error for WifiError {}
+
+/// Quotes `arg` for display when it holds whitespace or quotes
+fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+        format!("{:?}", arg)
+    } else {
+        arg.to_string()
+    }
+}
+
 /// Returns a list of WiFi hotspots in your area.
-/// Uses `airport` on macOS and `iw` on Linux.
+/// Uses `airport` or `system_profiler` on macOS, `nmcli`, `iw`, `iwctl` or
+/// `iwlist` on Linux and `netsh` on Windows, after any backend added with
+/// [`register_backend`]. As a last resort on macOS, `wdutil` (run as root)
+/// gives just the connected network.
+///
+/// Setting [`BACKEND_ENV`] to a backend name (`nmcli`, `iw`, `iwctl`,
+/// `iwlist`, `system_profiler`, `airport`, `wdutil` or `netsh`) skips the
+/// others.
+///
+/// Scans of one interface from several threads take turns: on Linux an `iw`
+/// scan waits for the one running on the same interface to finish, as it
+/// would otherwise fail with `Device or resource busy`. Other interfaces
+/// still scan at the same time.
+///
+/// Errors the crate raises itself are [`WifiError`]s wrapped in the
+/// `anyhow::Error`, get them back with `err.downcast_ref::<WifiError>()`.
 pub fn scan() -> anyhow::Result<Vec<Wifi>> {
-    crate::sys::scan()
+    let mut wifis = Vec::new();
+    scan_into(&mut wifis)?;
+    Ok(wifis)
+}
+
+/// Same as [`scan`] but clears `buf` and fills it with the hotspots, so a
+/// caller scanning often can reuse one buffer. Its capacity is kept between
+/// calls. `buf` is left empty when the scan fails.
+pub fn scan_into(buf: &mut Vec<Wifi>) -> anyhow::Result<()> {
+    scan_into_with(buf, &crate::backend::DefaultBackend)
+}
+
+fn scan_into_with(buf: &mut Vec<Wifi>, backend: &dyn ScanBackend) -> anyhow::Result<()> {
+    buf.clear();
+    buf.extend(backend.scan()?);
+    Ok(())
+}
+
+/// Same as [`scan`] but returns the hotspots as an iterator, to chain
+/// adapters like `scan_iter()?.filter(...).take(10)`. The backend has run and
+/// exited by the time this returns, its whole output already captured and
+/// parsed, so dropping the iterator early leaves no process behind.
+pub fn scan_iter() -> anyhow::Result<impl Iterator<Item = Wifi>> {
+    scan_iter_with(&crate::backend::DefaultBackend)
+}
+
+fn scan_iter_with(backend: &dyn ScanBackend) -> anyhow::Result<impl Iterator<Item = Wifi>> {
+    Ok(backend.scan()?.into_iter())
+}
+
+/// Same as [`scan`] but hands each hotspot to `callback` instead of
+/// collecting them, e.g. to add rows to a UI one at a time. None of the
+/// parsers stream, so the callbacks fire once the backend has exited and
+/// its output is parsed, in the order [`scan`] returns the hotspots. A
+/// failed scan calls `callback` for no hotspot.
+pub fn scan_each<F: FnMut(Wifi)>(callback: F) -> anyhow::Result<()> {
+    scan_each_with(callback, &crate::backend::DefaultBackend)
+}
+
+fn scan_each_with<F: FnMut(Wifi)>(callback: F, backend: &dyn ScanBackend) -> anyhow::Result<()> {
+    backend.scan()?.into_iter().for_each(callback);
+    Ok(())
+}
+
+/// Returns the hotspots in your area grouped by the interface that saw them.
+/// Uses `system_profiler` on macOS and `iw` on Linux. `netsh` doesn't say
+/// which interface saw a network, so on Windows everything is reported under
+/// a single unnamed interface.
+pub fn scan_with_interfaces() -> anyhow::Result<Vec<InterfaceScan>> {
+    crate::sys::scan_with_interfaces()
+}
+
+/// Returns the network this machine is currently connected to, `None` when
+/// it isn't connected.
+/// Uses `system_profiler` or `wdutil` on macOS, `iw` or `/proc/net/wireless`
+/// on Linux and `netsh` on Windows.
+pub fn current_connection() -> anyhow::Result<Option<Wifi>> {
+    crate::sys::current_connection()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_call_back_once_per_network() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_01.txt").unwrap();
+        let expected = parse_iw(&data).unwrap();
+        let replay = ReplayBackend::new(vec![expected.clone()]).fail_at(1);
+
+        let mut seen = Vec::new();
+        scan_each_with(|wifi| seen.push(wifi), &replay).unwrap();
+        assert_eq!(expected, seen);
+
+        let mut calls = 0;
+        assert!(scan_each_with(|_| calls += 1, &replay).is_err());
+        assert_eq!(0, calls);
+    }
+
+    #[test]
+    fn should_reuse_scan_buffer() {
+        let replay =
+            ReplayBackend::new(vec![vec![Wifi::default(); 8], vec![Wifi::default(); 3]]).fail_at(2);
+        let mut buf = Vec::new();
+
+        scan_into_with(&mut buf, &replay).unwrap();
+        assert_eq!(8, buf.len());
+        let capacity = buf.capacity();
+
+        scan_into_with(&mut buf, &replay).unwrap();
+        assert_eq!(3, buf.len());
+        assert_eq!(capacity, buf.capacity());
+
+        assert!(scan_into_with(&mut buf, &replay).is_err());
+        assert!(buf.is_empty());
+        assert_eq!(capacity, buf.capacity());
+    }
+
+    #[test]
+    fn should_iterate_scan() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli01.txt").unwrap();
+        let replay = ReplayBackend::new(vec![parse_nmcli(&data).unwrap()]);
+
+        let secured: Vec<String> = scan_iter_with(&replay)
+            .unwrap()
+            .filter(|wifi| !wifi.security.is_empty())
+            .take(1)
+            .map(|wifi| wifi.ssid)
+            .collect();
+        assert_eq!(vec!["hello"], secured);
+    }
+
+    #[test]
+    fn should_not_iterate_failed_scan() {
+        let replay = ReplayBackend::new(vec![vec![Wifi::default()]]).fail_at(0);
+        assert!(scan_iter_with(&replay).is_err());
+        assert_eq!(1, scan_iter_with(&replay).unwrap().count());
+    }
 }
@@ -0,0 +1,117 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Wifi, WifiError};
+
+/// A 48-bit MAC address (BSSID)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl FromStr for MacAddr {
+    type Err = WifiError;
+
+    /// Parses `11:22:33:44:55:66` or `11-22-33-44-55-66`, in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut octets = [0u8; 6];
+        let mut parts = s.trim().split([':', '-']);
+        for octet in octets.iter_mut() {
+            let part = parts.next().ok_or(WifiError::FailedToParse)?;
+            if part.len() != 2 {
+                return Err(WifiError::FailedToParse);
+            }
+            *octet = u8::from_str_radix(part, 16).map_err(|_| WifiError::FailedToParse)?;
+        }
+        if parts.next().is_some() {
+            return Err(WifiError::FailedToParse);
+        }
+        Ok(MacAddr(octets))
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+impl MacAddr {
+    /// Whether the address was made up rather than assigned by the
+    /// manufacturer (bit 1 of the first octet), as done for the extra SSIDs
+    /// of an access point or for randomized addresses
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+
+    /// Whether this is a group address (bit 0 of the first octet), which no
+    /// access point should have as its BSSID
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+}
+
+impl Wifi {
+    /// Returns the parsed mac address, `None` if the backend didn't report a
+    /// valid one.
+    pub fn mac_addr(&self) -> Option<MacAddr> {
+        self.mac.parse().ok()
+    }
+
+    /// Whether the BSSID is locally administered rather than the hardware
+    /// address of the radio, see [`MacAddr::is_locally_administered`].
+    /// `None` without a valid mac address.
+    pub fn has_randomized_bssid(&self) -> Option<bool> {
+        Some(self.mac_addr()?.is_locally_administered())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_mac_addr() {
+        let expected = MacAddr([0x11, 0x22, 0x33, 0xaa, 0xbb, 0xcc]);
+        assert_eq!(Ok(expected), "11:22:33:aa:bb:cc".parse());
+        assert_eq!(Ok(expected), "11-22-33-AA-BB-CC".parse());
+        assert_eq!("11:22:33:aa:bb:cc", expected.to_string());
+    }
+
+    #[test]
+    fn should_classify_mac_addr() {
+        let hardware: MacAddr = "24:a4:3c:01:02:03".parse().unwrap();
+        assert!(!hardware.is_locally_administered());
+        assert!(!hardware.is_multicast());
+
+        let randomized: MacAddr = "26:a4:3c:01:02:03".parse().unwrap();
+        assert!(randomized.is_locally_administered());
+        assert!(!randomized.is_multicast());
+
+        let multicast: MacAddr = "01:00:5e:00:00:01".parse().unwrap();
+        assert!(multicast.is_multicast());
+        assert!(!multicast.is_locally_administered());
+
+        let wifi = |mac: &str| Wifi {
+            mac: mac.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(Some(true), wifi("da:a1:19:00:00:01").has_randomized_bssid());
+        assert_eq!(
+            Some(false),
+            wifi("24:A4:3C:01:02:03").has_randomized_bssid()
+        );
+        assert_eq!(None, wifi("").has_randomized_bssid());
+    }
+
+    #[test]
+    fn should_not_parse_invalid_mac_addr() {
+        for mac in [
+            "",
+            "11:22:33:44:55",
+            "11:22:33:44:55:66:77",
+            "1:22:33:44:55:66",
+        ] {
+            assert_eq!(Err(WifiError::FailedToParse), mac.parse::<MacAddr>());
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::Wifi;
+
+/// The 802.11s mesh a hotspot is part of, from its Mesh ID and Mesh
+/// Configuration elements
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MeshInfo {
+    /// name of the mesh, the SSID of mesh backhaul links
+    pub mesh_id: String,
+    /// path selection protocol the mesh uses, 1 being HWMP, the 802.11s
+    /// default. `None` without a Mesh Configuration element.
+    pub active_path_protocol: Option<u8>,
+}
+
+impl Wifi {
+    /// Whether the hotspot is an 802.11s mesh point, e.g. the backhaul radio
+    /// of a mesh system. Only `iw` tells, so it is always false elsewhere.
+    pub fn is_mesh(&self) -> bool {
+        self.mesh.is_some()
+    }
+}
@@ -0,0 +1,200 @@
+use crate::json::{object, Value};
+use crate::Wifi;
+
+/// How [`to_ndjson_with`] writes SSIDs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SsidEncoding {
+    /// the SSID as the backend reported it
+    #[default]
+    Lossy,
+    /// the SSID decoded from [`Wifi::ssid_bytes`], as base64 of those bytes
+    /// when they aren't UTF-8, which an `ssid_is_base64` key after `ssid`
+    /// tells. Keeps the exact bytes, e.g. for wardriving datasets.
+    Base64,
+}
+
+/// Returns every hotspot as a compact JSON object on its own line (JSON
+/// Lines), which streaming consumers can handle one line at a time. The keys
+/// are [`Wifi::field_names`]. No hotspots give an empty string.
+pub fn to_ndjson(wifis: &[Wifi]) -> String {
+    to_ndjson_with(wifis, SsidEncoding::Lossy)
+}
+
+/// Same as [`to_ndjson`], writing the SSIDs as `encoding` says
+pub fn to_ndjson_with(wifis: &[Wifi], encoding: SsidEncoding) -> String {
+    wifis
+        .iter()
+        .map(|wifi| {
+            let mut object = wifi_object(wifi);
+            if encoding == SsidEncoding::Base64 {
+                encode_ssid(&mut object, wifi);
+            }
+            format!("{object}\n")
+        })
+        .collect()
+}
+
+/// Replaces the `ssid` of `object` by the SSID bytes of `wifi`, as base64
+/// when they aren't UTF-8, and adds `ssid_is_base64` after it
+fn encode_ssid(object: &mut Value, wifi: &Wifi) {
+    let Value::Object(entries) = object else {
+        return;
+    };
+    let Some(at) = entries.iter().position(|(key, _)| key == "ssid") else {
+        return;
+    };
+    let (ssid, is_base64) = match String::from_utf8(wifi.ssid_bytes()) {
+        Ok(ssid) => (ssid, false),
+        Err(err) => (base64(err.as_bytes()), true),
+    };
+    entries[at].1 = Value::String(ssid);
+    entries.insert(
+        at + 1,
+        ("ssid_is_base64".to_string(), Value::Bool(is_base64)),
+    );
+}
+
+/// Standard base64 with padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(group >> (18 - 6 * i)) as usize & 63]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn wifi_object(wifi: &Wifi) -> Value {
+    let fields = Wifi::field_names().iter().copied();
+    object(
+        fields
+            .zip(wifi.field_values().into_iter().map(Value::String))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn from_json(line: &str) -> Wifi {
+        let value = json::parse(line).unwrap();
+        let field = |key: &str| value.get(key).and_then(Value::as_str).unwrap().to_string();
+        Wifi {
+            mac: field("mac"),
+            ssid: field("ssid"),
+            channel: field("channel"),
+            signal_level: field("signal_level"),
+            security: field("security"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_write_one_network_per_line() {
+        let wifis = vec![
+            Wifi {
+                mac: "11:22:33:44:55:66".to_string(),
+                ssid: "say \"hi\"\nthere".to_string(),
+                channel: "36".to_string(),
+                signal_level: "-60".to_string(),
+                security: "WPA2".to_string(),
+                ..Default::default()
+            },
+            Wifi {
+                ssid: "open".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let ndjson = to_ndjson(&wifis);
+        assert!(ndjson.ends_with('\n'));
+        let parsed: Vec<Wifi> = ndjson.lines().map(from_json).collect();
+        assert_eq!(wifis, parsed);
+
+        assert_eq!("", to_ndjson(&[]));
+    }
+
+    /// Inverse of [`base64`]
+    fn from_base64(text: &str) -> Vec<u8> {
+        const ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let sextets: Vec<u32> = text
+            .trim_end_matches('=')
+            .chars()
+            .map(|c| ALPHABET.find(c).unwrap() as u32)
+            .collect();
+        let mut bytes = Vec::new();
+        for chunk in sextets.chunks(4) {
+            let group = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |group, (i, &sextet)| group | sextet << (18 - 6 * i));
+            for i in 0..chunk.len() - 1 {
+                bytes.push((group >> (16 - 8 * i)) as u8);
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn should_encode_base64() {
+        for (text, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encoded, base64(text.as_bytes()));
+            assert_eq!(text.as_bytes(), from_base64(encoded));
+        }
+    }
+
+    #[test]
+    fn should_keep_non_utf8_ssid_bytes() {
+        // Latin-1 and a lone continuation byte, as iw escapes them
+        let wifis = vec![
+            Wifi {
+                ssid: "Caf\\xe9 \\x80".to_string(),
+                ..Default::default()
+            },
+            Wifi {
+                ssid: "Caf\\xc3\\xa9".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let ndjson = to_ndjson_with(&wifis, SsidEncoding::Base64);
+        let lines: Vec<Value> = ndjson.lines().map(|l| json::parse(l).unwrap()).collect();
+        let ssid = |line: &Value| {
+            line.get("ssid")
+                .and_then(Value::as_str)
+                .unwrap()
+                .to_string()
+        };
+        let is_base64 = |line: &Value| line.get("ssid_is_base64") == Some(&Value::Bool(true));
+
+        assert!(is_base64(&lines[0]));
+        assert_eq!(b"Caf\xe9 \x80".to_vec(), from_base64(&ssid(&lines[0])));
+        assert_eq!(wifis[0].ssid_bytes(), from_base64(&ssid(&lines[0])));
+        // valid UTF-8 is written as text
+        assert!(!is_base64(&lines[1]));
+        assert_eq!("Café", ssid(&lines[1]));
+
+        let lossy = to_ndjson_with(&wifis, SsidEncoding::Lossy);
+        assert_eq!(to_ndjson(&wifis), lossy);
+        assert!(!lossy.contains("ssid_is_base64"));
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::output::{parse_output, run};
+use crate::{Platform, Wifi};
+
+/// Settings for [`scan_with_options`]. The default scans the same way as
+/// [`crate::scan`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Drop entries that can't identify a hotspot: those with neither an
+    /// SSID (an empty or all-NUL one counts as none, see
+    /// [`Wifi::is_hidden`]) nor a valid BSSID (see [`Wifi::mac_addr`]).
+    /// Hidden networks with a BSSID and networks without a BSSID but with
+    /// an SSID are kept. Off by default, when every parsed entry is kept.
+    pub strict: bool,
+    /// command to run instead of the backends, see
+    /// [`ScanOptions::custom_command`]
+    pub command: Option<CustomCommand>,
+    /// On Linux only scan the channels the current regulatory domain
+    /// allows, read from `iw reg get`, by passing their frequencies to
+    /// `iw ... scan freq`. Falls back to a regular scan when `iw` fails.
+    /// Other platforms already only scan the allowed channels.
+    pub regulatory_channels: bool,
+    /// reads the signal levels instead of the backends, see
+    /// [`ScanOptions::signal_parser`]
+    pub signal_parser: Option<SignalParser>,
+}
+
+/// A user function reading a signal level in dBm from the string a backend
+/// reported, see [`ScanOptions::signal_parser`]
+#[derive(Clone)]
+pub struct SignalParser(Arc<ParseSignal>);
+
+type ParseSignal = dyn Fn(&str) -> Option<i32> + Send + Sync;
+
+impl fmt::Debug for SignalParser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SignalParser(..)")
+    }
+}
+
+/// Parsers are only equal to themselves and their clones
+impl PartialEq for SignalParser {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SignalParser {}
+
+/// A command printing the output of one of the tools the crate knows, e.g.
+/// `ssh host nmcli ...`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// which tool's output it prints, picking the parser
+    pub format: Platform,
+}
+
+impl ScanOptions {
+    /// Scans by running `program` with `args` and parsing its stdout as the
+    /// output of `format`'s tool, e.g. to scan a remote machine over SSH or
+    /// through a wrapper script. The backends aren't tried.
+    ///
+    /// The program is run directly, not through a shell, with the
+    /// environment and privileges of this process. Don't build it or its
+    /// arguments from untrusted input, and mind that a command reaching
+    /// another machine hands this process whatever that machine prints.
+    pub fn custom_command<I, S>(
+        mut self,
+        program: impl Into<String>,
+        args: I,
+        format: Platform,
+    ) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.command = Some(CustomCommand {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            format,
+        });
+        self
+    }
+
+    /// Reads every signal level with `parser`, for drivers reporting it in a
+    /// format the crate doesn't know. `parser` gets the signal string as the
+    /// backend parsed it, e.g. `-67.00`, `85` or whatever the tool printed,
+    /// and returns the level in dBm. Its result replaces `signal_level`, so
+    /// [`Wifi::signal_dbm`] and everything built on it use it. When it
+    /// returns `None`, the signal is left empty.
+    pub fn signal_parser(
+        mut self,
+        parser: impl Fn(&str) -> Option<i32> + Send + Sync + 'static,
+    ) -> Self {
+        self.signal_parser = Some(SignalParser(Arc::new(parser)));
+        self
+    }
+
+    /// Applies the options to freshly parsed entries
+    pub(crate) fn apply(&self, mut wifis: Vec<Wifi>) -> Vec<Wifi> {
+        if let Some(SignalParser(parser)) = &self.signal_parser {
+            for wifi in &mut wifis {
+                wifi.signal_level = parser(&wifi.signal_level)
+                    .map(|dbm| dbm.to_string())
+                    .unwrap_or_default();
+            }
+        }
+        if self.strict {
+            wifis.retain(|wifi| !wifi.is_hidden() || wifi.mac_addr().is_some());
+        }
+        wifis
+    }
+}
+
+/// Same as [`crate::scan`] with the given options.
+pub fn scan_with_options(options: &ScanOptions) -> anyhow::Result<Vec<Wifi>> {
+    let wifis = match &options.command {
+        Some(command) => {
+            let output = run(Command::new(&command.program).args(&command.args))?;
+            parse_output(command.format, &String::from_utf8_lossy(&output.stdout))?
+        }
+        None if options.regulatory_channels => scan_regulatory_channels()?,
+        None => crate::scan()?,
+    };
+    Ok(options.apply(wifis))
+}
+
+fn scan_regulatory_channels() -> anyhow::Result<Vec<Wifi>> {
+    #[cfg(target_os = "linux")]
+    return crate::sys::scan_regulatory_channels().or_else(|e| {
+        tracing::debug!("iw scan of the allowed channels failed ({e}), scanning everything");
+        crate::scan()
+    });
+    #[cfg(not(target_os = "linux"))]
+    crate::scan()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_nmcli;
+
+    #[test]
+    fn should_drop_entries_without_identity_when_strict() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_junk.txt").unwrap();
+        let wifis = parse_nmcli(&data).unwrap();
+        assert_eq!(4, wifis.len());
+
+        let lenient = ScanOptions::default().apply(wifis.clone());
+        assert_eq!(wifis, lenient);
+
+        let strict = ScanOptions {
+            strict: true,
+            ..Default::default()
+        }
+        .apply(wifis);
+        let kept: Vec<(&str, &str)> = strict
+            .iter()
+            .map(|wifi| (wifi.ssid.as_str(), wifi.mac.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("hello", "11:22:33:44:55:66"),
+                ("", "44:55:66:77:88:99"),
+                ("Office", ""),
+            ],
+            kept
+        );
+    }
+
+    #[test]
+    fn should_use_signal_parser() {
+        let wifi = |signal_level: &str| Wifi {
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        };
+        // e.g. a driver printing `quality/70` instead of dBm
+        let options = ScanOptions::default().signal_parser(|raw| {
+            let (quality, max) = raw.split_once('/')?;
+            let quality: i32 = quality.parse().ok()?;
+            Some(quality * 70 / max.parse::<i32>().ok()? - 110)
+        });
+        let wifis = options.apply(vec![wifi("56/70"), wifi("-40"), wifi("")]);
+        let signals: Vec<Option<i32>> = wifis.iter().map(Wifi::signal_dbm).collect();
+        assert_eq!(vec![Some(-54), None, None], signals);
+
+        assert_eq!(options, options.clone());
+        assert_ne!(options, ScanOptions::default().signal_parser(|_| None));
+        assert_eq!(
+            vec![wifi("-40")],
+            ScanOptions::default().apply(vec![wifi("-40")])
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_scan_with_custom_command() {
+        let options = ScanOptions::default().custom_command(
+            "cat",
+            ["tests/fixtures/nmcli/nmcli01.txt"],
+            Platform::LinuxNmcli,
+        );
+        let wifis = scan_with_options(&options).unwrap();
+        let ssids: Vec<&str> = wifis.iter().map(|wifi| wifi.ssid.as_str()).collect();
+        assert_eq!(vec!["hello", "Cafe Guest", "Office"], ssids);
+
+        let options = ScanOptions::default().custom_command(
+            "cat",
+            ["tests/fixtures/nmcli/missing.txt"],
+            Platform::LinuxNmcli,
+        );
+        let err = scan_with_options(&options).unwrap_err();
+        assert!(err.to_string().starts_with("Command `cat "), "{err}");
+    }
+}
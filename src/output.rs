@@ -0,0 +1,514 @@
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+
+use anyhow::Context;
+
+use crate::backend::add_command_time;
+use crate::best_effort::to_wifi_error;
+use crate::parse::{
+    parse_airport, parse_iw, parse_iwctl, parse_iwlist, parse_netsh, parse_nmcli,
+    parse_systemprofiler, parse_wdutil,
+};
+use crate::{Platform, Wifi, WifiError};
+
+/// Turns captured command output into networks the same way [`crate::scan`]
+/// does after running `platform`'s command, without running anything. An
+/// unsuccessful `status` gives [`WifiError::CommandFailed`] with the output
+/// as the reason and an empty command line.
+pub fn scan_from_output(
+    platform: Platform,
+    stdout: &[u8],
+    status: ExitStatus,
+) -> Result<Vec<Wifi>, WifiError> {
+    check_status(&[], status, stdout, &[])?;
+    let stdout = match platform {
+        Platform::Windows => decode_text(stdout),
+        _ => String::from_utf8_lossy(stdout),
+    };
+    parse_output(platform, &stdout).map_err(to_wifi_error)
+}
+
+/// Longest failure reason [`check_status`] keeps, in characters
+const MAX_REASON_CHARS: usize = 512;
+
+/// Most a scan command may print before it is killed, 16 MiB unless changed
+/// with [`set_max_output_bytes`]
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 16 << 20;
+
+static MAX_OUTPUT_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_OUTPUT_BYTES);
+
+/// Sets the most a scan command may print, on stdout and on stderr each, for
+/// the whole process. A command printing more is killed and the scan fails
+/// with [`WifiError::OutputTooLarge`], so a runaway tool can't exhaust the
+/// memory. Defaults to [`DEFAULT_MAX_OUTPUT_BYTES`].
+pub fn set_max_output_bytes(bytes: usize) {
+    MAX_OUTPUT_BYTES.store(bytes, Ordering::Relaxed);
+}
+
+pub(crate) fn max_output_bytes() -> usize {
+    MAX_OUTPUT_BYTES.load(Ordering::Relaxed)
+}
+
+/// Runs `command` to completion and checks its output with [`check_status`].
+pub(crate) fn run(command: &mut Command) -> anyhow::Result<Output> {
+    run_limited(command, max_output_bytes())
+}
+
+fn run_limited(command: &mut Command, limit: usize) -> anyhow::Result<Output> {
+    let start = Instant::now();
+    let output = output_limited(command, limit);
+    add_command_time(start.elapsed());
+    let output = output?;
+    check_status(
+        &command_line(command),
+        output.status,
+        &output.stdout,
+        &output.stderr,
+    )?;
+    Ok(output)
+}
+
+/// Like [`Command::output`], killing the command once it prints more than
+/// `limit` bytes on stdout. More than that on stderr is dropped.
+fn output_limited(command: &mut Command, limit: usize) -> anyhow::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stderr = drain(child.stderr.take().context("No stderr")?, limit);
+
+    let mut stdout = Vec::new();
+    let pipe = child.stdout.take().context("No stdout")?;
+    pipe.take(limit as u64 + 1).read_to_end(&mut stdout)?;
+    if stdout.len() > limit {
+        // it may have exited already
+        let _ = child.kill();
+        child.wait()?;
+        return Err(WifiError::OutputTooLarge(limit).into());
+    }
+
+    let status = child.wait()?;
+    let mut stderr = stderr
+        .join()
+        .map_err(|_| anyhow::anyhow!("stderr reader thread panicked"))??;
+    stderr.truncate(limit);
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Reads `pipe` to the end on a new thread, so a chatty command can't fill it
+/// and block. Keeps the first `limit` + 1 bytes, which tells the caller when
+/// there were more than `limit`, and drops the rest.
+pub(crate) fn drain(
+    pipe: impl Read + Send + 'static,
+    limit: usize,
+) -> thread::JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let mut pipe = pipe;
+        (&mut pipe).take(limit as u64 + 1).read_to_end(&mut buf)?;
+        io::copy(&mut pipe, &mut io::sink())?;
+        Ok(buf)
+    })
+}
+
+/// Decodes command output, which is UTF-8 except for `netsh` on some
+/// Windows setups, where it is UTF-16. That is told by its byte order mark
+/// or, lacking one, by a NUL byte after most ASCII characters. Invalid
+/// sequences become U+FFFD either way.
+pub(crate) fn decode_text(bytes: &[u8]) -> Cow<'_, str> {
+    let (bytes, little_endian) = match bytes {
+        [0xff, 0xfe, rest @ ..] => (rest, true),
+        [0xfe, 0xff, rest @ ..] => (rest, false),
+        _ => {
+            let units = bytes.len() / 2;
+            let nul_high = bytes.chunks_exact(2).filter(|unit| unit[1] == 0).count();
+            if units == 0 || !bytes.len().is_multiple_of(2) || nul_high * 2 <= units {
+                return String::from_utf8_lossy(bytes);
+            }
+            (bytes, true)
+        }
+    };
+    let units = bytes.chunks_exact(2).map(|unit| {
+        let unit = [unit[0], unit[1]];
+        if little_endian {
+            u16::from_le_bytes(unit)
+        } else {
+            u16::from_be_bytes(unit)
+        }
+    });
+    Cow::Owned(
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect(),
+    )
+}
+
+/// Returns the program and arguments of `command`
+pub(crate) fn command_line(command: &Command) -> Vec<String> {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Fails with [`WifiError::CommandFailed`] unless `status` is a success. The
+/// reason is stderr, or stdout when stderr is empty as some tools (`netsh`)
+/// report errors there, cut to [`MAX_REASON_CHARS`].
+///
+/// A command that succeeds but prints nothing except on stderr fails too,
+/// tools lacking the permission to scan do that instead of listing no
+/// networks.
+pub(crate) fn check_status(
+    command: &[String],
+    status: ExitStatus,
+    stdout: &[u8],
+    stderr: &[u8],
+) -> Result<(), WifiError> {
+    let is_blank = |output: &[u8]| output.iter().all(u8::is_ascii_whitespace);
+    if status.success() && (!is_blank(stdout) || is_blank(stderr)) {
+        return Ok(());
+    }
+    let output = if is_blank(stderr) { stdout } else { stderr };
+    let output = decode_text(output);
+    let output = output.trim();
+    let reason = match output.char_indices().nth(MAX_REASON_CHARS) {
+        Some((end, _)) => format!("{}...", &output[..end]),
+        None => output.to_string(),
+    };
+    Err(WifiError::CommandFailed {
+        command: command.to_vec(),
+        status,
+        reason,
+    })
+}
+
+/// Parses `text` with the parser of `platform`, like [`scan_from_output`]
+/// without an exit status. Never panics, whatever `text` holds: output a
+/// parser can't make sense of gives [`WifiError::FailedToParse`] or one of
+/// the other parse errors.
+pub fn parse_any(platform: Platform, text: &str) -> Result<Vec<Wifi>, WifiError> {
+    parse_output(platform, text).map_err(to_wifi_error)
+}
+
+/// Runs the parser for `platform`'s command.
+pub(crate) fn parse_output(platform: Platform, output: &str) -> anyhow::Result<Vec<Wifi>> {
+    match platform {
+        Platform::MacOsSystemProfiler => parse_systemprofiler(output),
+        Platform::MacOsAirport => parse_airport(output),
+        Platform::MacOsWdutil => parse_wdutil(output),
+        Platform::LinuxIw => parse_iw(output),
+        Platform::LinuxIwctl => parse_iwctl(output),
+        Platform::LinuxIwlist => parse_iwlist(output),
+        Platform::LinuxNmcli => parse_nmcli(output),
+        Platform::Windows => parse_netsh(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::exit_status;
+
+    #[test]
+    fn should_scan_from_output() {
+        let fixtures = [
+            (
+                Platform::MacOsSystemProfiler,
+                "tests/fixtures/system_profiler/sp_airport_01.json",
+                3,
+            ),
+            (
+                Platform::MacOsAirport,
+                "tests/fixtures/airport/airport01.txt",
+                36,
+            ),
+            (
+                Platform::MacOsWdutil,
+                "tests/fixtures/wdutil/wdutil_info_01.txt",
+                1,
+            ),
+            (
+                Platform::LinuxIw,
+                "tests/fixtures/iw/iw_dev_scan_ht40.txt",
+                3,
+            ),
+            (
+                Platform::LinuxIwctl,
+                "tests/fixtures/iwctl/iwctl_get_networks.txt",
+                4,
+            ),
+            (
+                Platform::LinuxIwlist,
+                "tests/fixtures/iwlist/iwlist_scan_01.txt",
+                4,
+            ),
+            (Platform::LinuxNmcli, "tests/fixtures/nmcli/nmcli01.txt", 3),
+            (
+                Platform::Windows,
+                "tests/fixtures/netsh/netsh01_windows81.txt",
+                4,
+            ),
+        ];
+        for (platform, fixture, count) in fixtures {
+            let stdout = std::fs::read(fixture).unwrap();
+
+            let wifis = scan_from_output(platform, &stdout, exit_status(0)).unwrap();
+            assert_eq!(count, wifis.len(), "{platform:?}");
+
+            let err = scan_from_output(platform, &stdout, exit_status(1)).unwrap_err();
+            assert!(
+                matches!(err, WifiError::CommandFailed { status, .. } if !status.success()),
+                "{platform:?}"
+            );
+        }
+    }
+
+    /// xorshift generator, so a failing input can be found again
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+    }
+
+    /// Randomly cuts, drops, repeats and garbles the lines of `text`
+    fn mutate(text: &str, rng: &mut Rng) -> String {
+        const TOKENS: &[&str] = &[
+            "\t",
+            "\n",
+            "\r\n",
+            " ",
+            ":",
+            ",",
+            "(",
+            ")",
+            "{",
+            "}",
+            "[",
+            "]",
+            "\"",
+            "\\",
+            "*",
+            "-",
+            "0x",
+            "\x00",
+            "é",
+            "€",
+            "BSS ",
+            "\tSSID: ",
+            "SSID",
+            "BSSID",
+            "channel",
+            "dBm",
+            "Cell ",
+            "<dict>",
+            "<key>",
+            "\u{feff}",
+            "999999999999",
+            "-",
+            "|",
+        ];
+        let mut lines: Vec<String> = text.lines().map(String::from).collect();
+        for _ in 0..1 + rng.next(8) {
+            if lines.is_empty() {
+                lines.push(String::new());
+            }
+            let at = rng.next(lines.len());
+            match rng.next(5) {
+                0 => {
+                    lines.remove(at);
+                }
+                1 => lines.insert(at, lines[at].clone()),
+                2 => {
+                    let line = &mut lines[at];
+                    let cut = rng.next(line.chars().count() + 1);
+                    *line = line.chars().take(cut).collect();
+                }
+                3 => lines.truncate(at),
+                _ => {
+                    let line = &mut lines[at];
+                    let chars = line.chars().count();
+                    let offset = line
+                        .char_indices()
+                        .nth(rng.next(chars + 1))
+                        .map_or(line.len(), |(i, _)| i);
+                    line.insert_str(offset, TOKENS[rng.next(TOKENS.len())]);
+                }
+            }
+        }
+        lines.join(if rng.next(4) == 0 { "\r\n" } else { "\n" })
+    }
+
+    #[test]
+    fn should_never_panic_on_garbage() {
+        let fixtures = [
+            (
+                Platform::MacOsSystemProfiler,
+                "system_profiler/sp_airport_01.json",
+            ),
+            (
+                Platform::MacOsSystemProfiler,
+                "system_profiler/sp_airport_array.json",
+            ),
+            (Platform::MacOsAirport, "airport/airport01.txt"),
+            (Platform::MacOsAirport, "airport/airport_ssid_spaces.txt"),
+            (Platform::MacOsWdutil, "wdutil/wdutil_info_01.txt"),
+            (Platform::LinuxIw, "iw/iw_dev_scan_01.txt"),
+            (Platform::LinuxIw, "iw/iw_dev_scan_mbssid.txt"),
+            (Platform::LinuxIw, "iw/iw_dev_scan_vendor_ie.txt"),
+            (Platform::LinuxIwctl, "iwctl/iwctl_get_networks.txt"),
+            (Platform::LinuxIwlist, "iwlist/iwlist_scan_01.txt"),
+            (Platform::LinuxNmcli, "nmcli/nmcli01.txt"),
+            (Platform::LinuxNmcli, "nmcli/nmcli_ssids.txt"),
+            (Platform::Windows, "netsh/netsh01_windows81.txt"),
+            (Platform::Windows, "netsh/netsh02_radio_types.txt"),
+        ];
+        let mut rng = Rng(0x5eed_cafe_f00d_beef);
+        for (platform, fixture) in fixtures {
+            let text = std::fs::read_to_string(format!("tests/fixtures/{fixture}")).unwrap();
+            for round in 0..400 {
+                // mutations of the fixture, then random text far from it
+                let seed = rng.0;
+                let input = mutate(if round < 300 { &text } else { "" }, &mut rng);
+                let result = std::panic::catch_unwind(|| parse_any(platform, &input));
+                assert!(result.is_ok(), "{fixture} panicked, seed {seed:#x}");
+            }
+        }
+    }
+
+    #[test]
+    fn should_decode_utf16_netsh_output() {
+        let utf8 = std::fs::read("tests/fixtures/netsh/netsh01_windows81.txt").unwrap();
+        let expected = scan_from_output(Platform::Windows, &utf8, exit_status(0)).unwrap();
+        let text = String::from_utf8(utf8).unwrap();
+
+        let utf16le: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let with_bom = [&[0xff, 0xfe], utf16le.as_slice()].concat();
+        let utf16be: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        let with_be_bom = [&[0xfe, 0xff], utf16be.as_slice()].concat();
+        for stdout in [with_bom, utf16le, with_be_bom] {
+            let wifis = scan_from_output(Platform::Windows, &stdout, exit_status(0)).unwrap();
+            assert_eq!(expected, wifis);
+        }
+
+        assert_eq!("Café", decode_text("Café".as_bytes()));
+        assert_eq!("Cafés", decode_text(b"C\0a\0f\0\xe9\0s\0"));
+        assert_eq!("\u{fffd}", decode_text(&[0xff, 0xfe, 0x00, 0xd8]));
+        assert_eq!("", decode_text(b""));
+        let reason = "The Wireless AutoConfig Service (wlansvc) is not running.";
+        let stdout: Vec<u8> = reason.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let Err(WifiError::CommandFailed {
+            reason: decoded, ..
+        }) = scan_from_output(Platform::Windows, &stdout, exit_status(1))
+        else {
+            panic!("expected CommandFailed");
+        };
+        assert_eq!(reason, decoded);
+    }
+
+    #[test]
+    fn should_report_failure_reason() {
+        let err = scan_from_output(
+            Platform::Windows,
+            b"The Wireless AutoConfig Service (wlansvc) is not running.\r\n",
+            exit_status(1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            WifiError::CommandFailed {
+                command: vec![],
+                status: exit_status(1),
+                reason: "The Wireless AutoConfig Service (wlansvc) is not running.".to_string()
+            },
+            err
+        );
+    }
+
+    #[test]
+    fn should_truncate_failure_reason() {
+        let stderr = "é".repeat(MAX_REASON_CHARS + 10);
+        let Err(WifiError::CommandFailed { reason, .. }) =
+            check_status(&[], exit_status(1), b"", stderr.as_bytes())
+        else {
+            panic!("expected CommandFailed");
+        };
+        assert_eq!(format!("{}...", "é".repeat(MAX_REASON_CHARS)), reason);
+    }
+
+    #[test]
+    fn should_fail_on_stderr_only_output() {
+        let stderr = b"Error: Not authorized to control networking.\n";
+        assert_eq!(
+            Err(WifiError::CommandFailed {
+                command: vec!["nmcli".to_string()],
+                status: exit_status(0),
+                reason: "Error: Not authorized to control networking.".to_string()
+            }),
+            check_status(&["nmcli".to_string()], exit_status(0), b"\n", stderr)
+        );
+
+        assert_eq!(Ok(()), check_status(&[], exit_status(0), b"", b""));
+        assert_eq!(
+            Ok(()),
+            check_status(&[], exit_status(0), b"hello:6", stderr)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_fail_for_command_printing_only_stderr() {
+        let err = run(Command::new("sh").args(["-c", "echo 'permission denied' >&2"]))
+            .unwrap_err()
+            .downcast::<WifiError>()
+            .unwrap();
+        assert!(
+            matches!(&err, WifiError::CommandFailed { status, reason, .. } if status.success() && reason == "permission denied"),
+            "{err:?}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_kill_command_printing_too_much() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "while :; do echo 'BSS 11:22:33:44:55:66'; done"]);
+        let err = run_limited(&mut command, 1000)
+            .unwrap_err()
+            .downcast::<WifiError>()
+            .unwrap();
+        assert_eq!(WifiError::OutputTooLarge(1000), err);
+
+        // right at the limit is fine, stderr is cut instead
+        let mut command = Command::new("sh");
+        command.args(["-c", "printf '%010d' 0; printf '%020d' 0 >&2"]);
+        let output = run_limited(&mut command, 10).unwrap();
+        assert_eq!(b"0000000000", &output.stdout[..]);
+        assert_eq!(10, output.stderr.len());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_show_command_and_exit_code() {
+        let err = run(Command::new("sh").args(["-c", "echo 'wlan0 is down' >&2; exit 3"]))
+            .unwrap_err()
+            .downcast::<WifiError>()
+            .unwrap();
+        assert_eq!(
+            "Command `sh -c \"echo 'wlan0 is down' >&2; exit 3\"` failed with exit code 3: wlan0 is down",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Parsers for the output of the tools each backend runs, and for raw
+//! beacon frames. They don't run anything themselves, so captured output can
+//! be parsed on any platform.
+
+use std::borrow::Cow;
+
+mod airport;
+mod beacon;
+mod iw;
+mod iwctl;
+mod iwlist;
+mod netsh;
+mod nmcli;
+mod system_profiler;
+mod wdutil;
+
+pub use airport::{parse_airport, parse_airport_info};
+pub use beacon::parse_beacon_frames;
+#[cfg(target_os = "linux")]
+pub(crate) use iw::extract_value;
+pub use iw::parse_iw;
+pub use iwctl::parse_iwctl;
+pub use iwlist::parse_iwlist;
+pub use netsh::parse_netsh;
+pub use nmcli::parse_nmcli;
+#[cfg(target_os = "linux")]
+pub(crate) use nmcli::parse_nmcli_partial;
+pub use system_profiler::{parse_systemprofiler, parse_systemprofiler_interfaces};
+#[cfg(feature = "plist")]
+pub use system_profiler::{parse_systemprofiler_plist, parse_systemprofiler_plist_interfaces};
+pub use wdutil::parse_wdutil;
+
+/// Trims the NUL bytes and whitespace some hotspots pad their SSID with,
+/// raw or escaped as `\x00` the way `iw` prints them, so a padded SSID
+/// equals its clean form. An SSID made only of padding is a hidden network and
+/// is kept as is, see [`crate::Wifi::is_hidden`].
+pub(crate) fn clean_ssid(ssid: &str) -> String {
+    let mut clean = ssid;
+    loop {
+        let trimmed = clean.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+        let trimmed = trimmed.strip_suffix("\\x00").unwrap_or(trimmed);
+        if trimmed.len() == clean.len() {
+            break;
+        }
+        clean = trimmed;
+    }
+    if clean.is_empty() {
+        ssid.to_string()
+    } else {
+        clean.to_string()
+    }
+}
+
+/// Error about the line at `index` (from 0) of a tool's output, naming it by
+/// its 1-based number and quoting it
+pub(crate) fn line_error(index: usize, line: &str, problem: &str) -> anyhow::Error {
+    anyhow::anyhow!("line {}: {problem}: {line:?}", index + 1)
+}
+
+/// Strips a leading UTF-8 BOM and turns CRLF line endings into LF, so output
+/// captured on Windows parses the same as live output.
+pub(crate) fn normalize(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.contains("\r\n") {
+        Cow::Owned(text.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_trim_ssid_padding() {
+        assert_eq!("home", clean_ssid("home\0\0\0"));
+        assert_eq!("home", clean_ssid("home \\x00\\x00"));
+        assert_eq!("a\0b c", clean_ssid("a\0b c\0 "));
+        // hidden networks keep their padding
+        assert_eq!("\\x00\\x00", clean_ssid("\\x00\\x00"));
+
+        let padded = parse_nmcli("home\0\0:6:85:WPA2:11\\:22\\:33\\:44\\:55\\:66\n").unwrap();
+        let clean = parse_nmcli("home:6:85:WPA2:11\\:22\\:33\\:44\\:55\\:66\n").unwrap();
+        assert_eq!(clean, padded);
+
+        let json = r#"{"SPAirPortDataType": [{"spairport_airport_interfaces": [{
+            "spairport_airport_other_local_wireless_networks": [{"_name": "Lobby\u0000\u0000"}]
+        }]}]}"#;
+        assert_eq!("Lobby", parse_systemprofiler(json).unwrap()[0].ssid);
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Context;
+
+use super::{clean_ssid, line_error, normalize};
+use crate::Wifi;
+
+/// Parses the output of `airport -s`.
+pub fn parse_airport(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
+    let network_list = normalize(network_list);
+    let mut wifis: Vec<Wifi> = Vec::new();
+    let mut lines = network_list.lines();
+    let headers = match lines.next() {
+        Some(v) => v,
+        // return an empty list of WiFi if the network_list is empty
+        None => return Ok(vec![]),
+    };
+
+    let headers_string = String::from(headers);
+    let col_headers = ["BSSID", "RSSI", "CHANNEL", "HT", "SECURITY"]
+        .iter()
+        .map(|header| {
+            headers_string
+                .find(header)
+                .context("HeaderNotFound in {header:?}")
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let col_mac = col_headers[0];
+    let col_rrsi = col_headers[1];
+    let col_channel = col_headers[2];
+    let col_ht = col_headers[3];
+    let col_security = col_headers[4];
+
+    // the header is line 0
+    for (index, line) in lines.enumerate().map(|(i, line)| (i + 1, line)) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let column = |start: usize, end: Option<usize>| {
+            match end {
+                Some(end) => line.get(start..end),
+                None => line.get(start..),
+            }
+            .map(str::trim)
+            .ok_or_else(|| line_error(index, line, "doesn't line up with the header"))
+        };
+        // the SSID is right-aligned and ends one space before the BSSID, so
+        // only the padding on its left is to be trimmed
+        let ssid_raw = line
+            .get(..col_mac.saturating_sub(1))
+            .ok_or_else(|| line_error(index, line, "doesn't line up with the header"))?
+            .trim_start();
+        let mac = column(col_mac, Some(col_rrsi))?;
+        let signal_level = column(col_rrsi, Some(col_channel))?;
+        let channel = column(col_channel, Some(col_ht))?;
+        let security = column(col_security, None)?;
+
+        wifis.push(Wifi {
+            mac: mac.to_string(),
+            ssid: clean_ssid(ssid_raw),
+            ssid_raw: Some(ssid_raw.to_string()),
+            channel: channel.to_string(),
+            signal_level: signal_level.to_string(),
+            security: security.to_string(),
+            ..Default::default()
+        });
+    }
+
+    Ok(wifis)
+}
+
+/// Parses the output of `airport -I`, which describes the network the
+/// machine is connected to: one `key: value` line per field, e.g.
+/// `agrCtlRSSI: -55`. Returns `None` when the Wi-Fi is off (`AirPort: Off`)
+/// or not connected.
+pub fn parse_airport_info(info: &str) -> anyhow::Result<Option<Wifi>> {
+    let info = normalize(info);
+    let mut wifi = Wifi {
+        associated: true,
+        ..Default::default()
+    };
+    for line in info.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "AirPort" if value == "Off" => return Ok(None),
+            "agrCtlRSSI" => wifi.signal_level = value.to_string(),
+            "agrCtlNoise" => wifi.noise_dbm = value.parse().ok(),
+            "lastTxRate" => {
+                wifi.tx_rate_mbps = value.parse().ok();
+                #[allow(deprecated)]
+                {
+                    wifi.max_rate_mbps = value.parse().ok();
+                }
+            }
+            "link auth" => wifi.security = value.to_string(),
+            "BSSID" => wifi.mac = pad_octets(value),
+            "SSID" => wifi.ssid = clean_ssid(value),
+            // the primary channel then the width or secondary offset, e.g.
+            // `149,80` or `6,-1`
+            "channel" => wifi.channel = value.split(',').next().unwrap_or_default().to_string(),
+            _ => {}
+        }
+    }
+    let connected = !wifi.mac.is_empty() || !wifi.ssid.is_empty();
+    Ok(connected.then_some(wifi))
+}
+
+/// `airport -I` drops the leading zero of the octets, e.g. `0:11:2:33:44:5`
+fn pad_octets(mac: &str) -> String {
+    mac.split(':')
+        .map(|octet| format!("{octet:0>2}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Read;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_parse_airport() {
+        let expected = [
+            Wifi {
+                mac: "00:35:1a:90:56:03".to_string(),
+                ssid: "OurTest".to_string(),
+                ssid_raw: Some("OurTest".to_string()),
+                channel: "112".to_string(),
+                signal_level: "-70".to_string(),
+                security: "WPA2(PSK/AES/AES)".to_string(),
+                ..Default::default()
+            },
+            Wifi {
+                mac: "00:35:1a:90:56:00".to_string(),
+                ssid: "TEST-Wifi".to_string(),
+                channel: "1".to_string(),
+                signal_level: "-67".to_string(),
+                security: "WPA2(PSK/AES/AES)".to_string(),
+                ssid_raw: Some("TEST-Wifi".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let path = PathBuf::from("tests/fixtures/airport/airport01.txt");
+
+        let file_path = path.as_os_str();
+
+        let mut file = File::open(file_path).unwrap();
+
+        let mut filestr = String::new();
+        let _ = file.read_to_string(&mut filestr).unwrap();
+
+        let result = parse_airport(&filestr).unwrap();
+        let last = result.len() - 1;
+        assert_eq!(expected[0], result[0]);
+        assert_eq!(expected[1], result[last]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_parse_other() {
+        let path = PathBuf::from("tests/fixtures/iw/iw_dev_01.txt");
+        let file_path = path.as_os_str();
+        let mut file = File::open(file_path).unwrap();
+        let mut filestr = String::new();
+        file.read_to_string(&mut filestr).unwrap();
+        parse_airport(&filestr).unwrap(); // must panic
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn should_parse_airport_info() {
+        let data = std::fs::read_to_string("tests/fixtures/airport/airport_info.txt").unwrap();
+        assert_eq!(
+            Some(Wifi {
+                mac: "00:11:02:33:44:0a".to_string(),
+                ssid: "Home Network".to_string(),
+                channel: "149".to_string(),
+                signal_level: "-55".to_string(),
+                security: "wpa2-psk".to_string(),
+                noise_dbm: Some(-89),
+                associated: true,
+                max_rate_mbps: Some(867),
+                tx_rate_mbps: Some(867.0),
+                ..Default::default()
+            }),
+            parse_airport_info(&data).unwrap()
+        );
+
+        assert_eq!(None, parse_airport_info("AirPort: Off\n").unwrap());
+        assert_eq!(None, parse_airport_info("").unwrap());
+        let disconnected = "     agrCtlRSSI: 0\n          state: init\n     lastTxRate: 0\n";
+        assert_eq!(None, parse_airport_info(disconnected).unwrap());
+    }
+
+    #[test]
+    fn should_keep_ssid_spaces() {
+        let data =
+            std::fs::read_to_string("tests/fixtures/airport/airport_ssid_spaces.txt").unwrap();
+        let result = parse_airport(&data).unwrap();
+        let raw: Vec<Option<&str>> = result.iter().map(|w| w.ssid_raw.as_deref()).collect();
+        assert_eq!(vec![Some("guest  "), Some("lab net "), Some("plain")], raw);
+        assert_eq!("guest", result[0].ssid);
+        assert_eq!("00:11:22:33:44:01", result[0].mac);
+    }
+
+    #[test]
+    fn should_parse_airport_crlf_and_bom() {
+        let lf = std::fs::read_to_string("tests/fixtures/airport/airport01.txt").unwrap();
+        let crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        assert_eq!(parse_airport(&lf).unwrap(), parse_airport(&crlf).unwrap());
+    }
+
+    #[test]
+    fn should_report_broken_line() {
+        let data = std::fs::read_to_string("tests/fixtures/airport/airport01.txt").unwrap();
+        let mut lines: Vec<&str> = data.lines().collect();
+        lines[2] = "  OurDev 00:35:1a";
+
+        let err = parse_airport(&lines.join("\n")).unwrap_err();
+        assert_eq!(
+            "line 3: doesn't line up with the header: \"  OurDev 00:35:1a\"",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::parse::clean_ssid;
+use crate::{MacAddr, Wifi};
+
+/// Frame control byte of a beacon
+const BEACON: u8 = 0x80;
+/// Frame control byte of a probe response
+const PROBE_RESPONSE: u8 = 0x50;
+/// MAC header and the fixed timestamp, beacon interval and capability fields
+const FIXED_LEN: usize = 24 + 12;
+/// Privacy bit of the capability field, set by WEP and WPA hotspots
+const PRIVACY: u16 = 0x0010;
+/// Radiotap flag telling the frame ends with its 4 bytes FCS
+const RADIOTAP_FCS: u8 = 0x10;
+
+/// Decodes raw 802.11 beacon and probe response frames, e.g. captured in
+/// monitor mode by a wardriving tool. A frame may start with a radiotap
+/// header, whose antenna signal becomes the signal level. Other frames and
+/// truncated ones are skipped.
+///
+/// The SSID and channel come from the SSID and DS Parameter Set elements,
+/// the BSSID from the third address. The security reads like `iwlist`
+/// reports it: `WPA`, `WPA2` and `WPA3` from the WPA and RSN elements
+/// separated by spaces, `WEP` for a private hotspot without them.
+pub fn parse_beacon_frames(frames: &[&[u8]]) -> Vec<Wifi> {
+    frames
+        .iter()
+        .filter_map(|frame| parse_frame(frame))
+        .collect()
+}
+
+fn parse_frame(frame: &[u8]) -> Option<Wifi> {
+    let (frame, signal_dbm) = if frame.first() == Some(&0) {
+        strip_radiotap(frame)?
+    } else {
+        (frame, None)
+    };
+    if frame.len() < FIXED_LEN || ![BEACON, PROBE_RESPONSE].contains(&frame[0]) {
+        return None;
+    }
+    let bssid: [u8; 6] = frame[16..22].try_into().ok()?;
+    let capability = u16::from_le_bytes([frame[34], frame[35]]);
+
+    let mut wifi = Wifi {
+        mac: MacAddr(bssid).to_string(),
+        signal_level: signal_dbm.map(|dbm| dbm.to_string()).unwrap_or_default(),
+        ..Default::default()
+    };
+    let mut wpa = false;
+    let mut rsn: Option<&[u8]> = None;
+    let mut elements = &frame[FIXED_LEN..];
+    while let [id, len, rest @ ..] = elements {
+        let Some(body) = rest.get(..usize::from(*len)) else {
+            break;
+        };
+        match id {
+            0 => wifi.ssid = clean_ssid(&String::from_utf8_lossy(body)),
+            3 => wifi.channel = body.first()?.to_string(),
+            48 => rsn = Some(body),
+            221 => wpa |= body.starts_with(&[0x00, 0x50, 0xf2, 0x01]),
+            _ => {}
+        }
+        elements = &rest[body.len()..];
+    }
+    wifi.security = security(capability, wpa, rsn);
+    Some(wifi)
+}
+
+/// Splits off a radiotap header, returning the 802.11 frame without its FCS
+/// and the antenna signal in dBm if the header has one
+fn strip_radiotap(frame: &[u8]) -> Option<(&[u8], Option<i8>)> {
+    let header_len = usize::from(u16::from_le_bytes([*frame.get(2)?, *frame.get(3)?]));
+    let header = frame.get(..header_len)?;
+    let word = |at: usize| Some(u32::from_le_bytes(header.get(at..at + 4)?.try_into().ok()?));
+
+    let present = word(4)?;
+    // extended presence bitmaps only flag fields past the ones read here
+    let mut offset = 8;
+    while word(offset - 4)? & (1 << 31) != 0 {
+        offset += 4;
+    }
+
+    // (alignment, size) of TSFT, flags, rate, channel, FHSS and antenna signal
+    const FIELDS: [(usize, usize); 6] = [(8, 8), (1, 1), (1, 1), (2, 4), (1, 2), (1, 1)];
+    let mut flags = 0;
+    let mut signal = None;
+    for (bit, (align, size)) in FIELDS.into_iter().enumerate() {
+        if present & (1 << bit) == 0 {
+            continue;
+        }
+        offset = offset.next_multiple_of(align);
+        let field = header.get(offset..offset + size)?;
+        match bit {
+            1 => flags = field[0],
+            5 => signal = Some(field[0] as i8),
+            _ => {}
+        }
+        offset += size;
+    }
+
+    let mut frame = &frame[header_len..];
+    if flags & RADIOTAP_FCS != 0 {
+        frame = &frame[..frame.len().checked_sub(4)?];
+    }
+    Some((frame, signal))
+}
+
+/// Security schemes the WPA and RSN elements announce
+fn security(capability: u16, wpa: bool, rsn: Option<&[u8]>) -> String {
+    let mut schemes = Vec::new();
+    if wpa {
+        schemes.push("WPA");
+    }
+    if let Some(rsn) = rsn {
+        let akms = rsn_akm_suites(rsn);
+        // PSK and 802.1X, with and without fast transition
+        if akms.is_empty() || akms.iter().any(|akm| [1, 2, 3, 4, 5, 6].contains(akm)) {
+            schemes.push("WPA2");
+        }
+        // SAE, with and without fast transition, and Suite B 802.1X
+        if akms.iter().any(|akm| [8, 9, 12, 24, 25].contains(akm)) {
+            schemes.push("WPA3");
+        }
+    }
+    if schemes.is_empty() && capability & PRIVACY != 0 {
+        schemes.push("WEP");
+    }
+    schemes.join(" ")
+}
+
+/// Types of the 802.11 authentication suites an RSN element lists
+fn rsn_akm_suites(rsn: &[u8]) -> Vec<u8> {
+    // version and group cipher, then the counted pairwise ciphers
+    let Some([low, high]) = rsn.get(6..8) else {
+        return Vec::new();
+    };
+    let akm_at = 8 + 4 * usize::from(u16::from_le_bytes([*low, *high]));
+    let Some([low, high]) = rsn.get(akm_at..akm_at + 2) else {
+        return Vec::new();
+    };
+    let count = usize::from(u16::from_le_bytes([*low, *high]));
+    rsn.get(akm_at + 2..)
+        .unwrap_or_default()
+        .chunks_exact(4)
+        .take(count)
+        .filter(|suite| suite[..3] == [0x00, 0x0f, 0xac])
+        .map(|suite| suite[3])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Beacon of `bssid` with its capability field followed by `elements`
+    fn beacon(bssid: [u8; 6], capability: u16, elements: &[&[u8]]) -> Vec<u8> {
+        let mut frame = vec![BEACON, 0, 0, 0];
+        frame.extend([0xff; 6]);
+        frame.extend(bssid);
+        frame.extend(bssid);
+        frame.extend([0x10, 0x00]);
+        frame.extend([0; 8]);
+        frame.extend(100u16.to_le_bytes());
+        frame.extend(capability.to_le_bytes());
+        for element in elements {
+            frame.extend(*element);
+        }
+        frame
+    }
+
+    #[test]
+    fn should_parse_beacon_frames() {
+        // WPA2 and WPA3 transition mode, PSK and SAE
+        let rsn: &[u8] = &[
+            48, 24, 1, 0, 0x00, 0x0f, 0xac, 4, 1, 0, 0x00, 0x0f, 0xac, 4, 2, 0, 0x00, 0x0f, 0xac,
+            2, 0x00, 0x0f, 0xac, 8, 0x80, 0,
+        ];
+        let home = beacon(
+            [0x00, 0x11, 0x22, 0x33, 0x44, 0x55],
+            0x0411,
+            &[b"\x00\x04Home", &[1, 2, 0x82, 0x84], &[3, 1, 6], rsn],
+        );
+        // radiotap header with the flags (FCS at the end), rate, channel and
+        // antenna signal
+        let mut captured: Vec<u8> = vec![
+            0, 0, 18, 0, 0x2e, 0, 0, 0, 0x10, 0x02, 0x6c, 0x09, 0xa0, 0x00, 0xc4, 0, 0, 0,
+        ];
+        captured.extend(beacon(
+            [0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff],
+            0x0011,
+            &[b"\x00\x03Old", &[3, 1, 11]],
+        ));
+        captured[18] = PROBE_RESPONSE;
+        captured.extend([0xde, 0xad, 0xbe, 0xef]);
+        let hidden = beacon(
+            [0x02, 0, 0, 0, 0, 1],
+            0x0001,
+            &[&[0, 0], &[3, 1, 1], &[221, 6, 0x00, 0x50, 0xf2, 0x01, 1, 0]],
+        );
+        let data_frame: &[u8] = &[0x08, 0x02, 0, 0];
+
+        let result = parse_beacon_frames(&[&home, &captured, &hidden, data_frame, &home[..30]]);
+        assert_eq!(
+            vec![
+                Wifi {
+                    mac: "00:11:22:33:44:55".to_string(),
+                    ssid: "Home".to_string(),
+                    channel: "6".to_string(),
+                    security: "WPA2 WPA3".to_string(),
+                    ..Default::default()
+                },
+                Wifi {
+                    mac: "aa:bb:cc:dd:ee:ff".to_string(),
+                    ssid: "Old".to_string(),
+                    channel: "11".to_string(),
+                    signal_level: "-60".to_string(),
+                    security: "WEP".to_string(),
+                    ..Default::default()
+                },
+                Wifi {
+                    mac: "02:00:00:00:00:01".to_string(),
+                    channel: "1".to_string(),
+                    security: "WPA".to_string(),
+                    ..Default::default()
+                },
+            ],
+            result
+        );
+    }
+}
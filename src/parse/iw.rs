@@ -0,0 +1,924 @@
+use anyhow::Context;
+use std::time::Duration;
+
+use super::{clean_ssid, line_error, normalize};
+use crate::sort::is_hidden_ssid;
+use crate::{ChannelOffset, HiddenReason, MacAddr, MeshInfo, PmfMode, Wifi};
+
+/// Parses the output of `iw dev <interface> scan`.
+pub fn parse_iw(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
+    let network_list = normalize(network_list);
+    let mut wifis: Vec<Wifi> = Vec::new();
+    let mut wifi = Wifi::default();
+    let mut power = TxPower::default();
+    let mut ciphers = RsnCiphers::default();
+    let mut mbssid = MultipleBssid::default();
+    let mut ds_channel = None;
+    // iw lists the IEs of the probe response first when it has those of a
+    // beacon too
+    let mut in_beacon_ies = false;
+    let mut in_rsn = false;
+    for (index, line) in network_list.lines().enumerate() {
+        // the ciphers of a WPA element listed after the RSN one don't count
+        if line.starts_with('\t') && !line.starts_with("\t\t") {
+            in_rsn = line.starts_with("\tRSN:");
+        }
+        if let Ok(mac) = extract_value(line, "BSS ", Some("(")) {
+            wifi.max_tx_power_dbm = power.max_dbm(&wifi);
+            power = TxPower::default();
+            wifi.suite_b_ciphers = ciphers.is_suite_b();
+            ciphers = RsnCiphers::default();
+            apply_ds_channel(&mut wifi, ds_channel.take());
+            if !wifi.mac.is_empty()
+                && !wifi.signal_level.is_empty()
+                && (!wifi.channel.is_empty() || wifi.reported_frequency_mhz.is_some())
+            {
+                wifi.hidden_reason.get_or_insert(HiddenReason::Absent);
+                let nontransmitted = mbssid.expand(&wifi);
+                wifis.push(wifi);
+                wifis.extend(nontransmitted);
+                wifi = Wifi::default();
+            }
+            mbssid = MultipleBssid::default();
+            in_beacon_ies = false;
+            wifi.mac = mac;
+        } else if line.starts_with("\tInformation elements from Beacon frame:") {
+            in_beacon_ies = true;
+        } else if let Ok(freq) = extract_value(line, "\tfreq: ", None) {
+            // newer versions of iw print a fraction, e.g. `5955.0`
+            wifi.reported_frequency_mhz = freq
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .map(|mhz| mhz.round() as u32);
+        } else if let Ok(signal) = extract_value(line, "\tsignal: ", Some(" dBm")) {
+            if signal.trim().parse::<f64>().is_err() {
+                return Err(line_error(index, line, "invalid signal"));
+            }
+            wifi.signal_level = signal;
+        } else if let Ok(flags) = extract_value(line, "\tcapability: ", None) {
+            push_capabilities(&mut wifi, &flags);
+        } else if let Ok(flags) = extract_value(line, "\t\t * Capabilities: ", None) {
+            push_capabilities(&mut wifi, &flags);
+            // the WPA element may list capabilities too, without MFP flags
+            wifi.pmf = wifi.pmf.max(Some(rsn_pmf(&flags)));
+        } else if let Ok(age) = extract_value(line, "\tlast seen: ", Some(" ms ago")) {
+            wifi.last_seen_ms = age.parse().ok();
+        } else if let Ok(tsf) = extract_value(line, "\tTSF: ", Some(" usec")) {
+            wifi.uptime = tsf.parse().ok().map(Duration::from_micros);
+        } else if let Ok(channel) = extract_value(line, "\tDS Parameter set: channel ", None) {
+            ds_channel = channel.trim().parse::<u16>().ok();
+        } else if let Ok(channel) = extract_value(line, "\t\t * primary channel: ", None) {
+            wifi.channel = channel;
+        } else if let Ok(offset) = extract_value(line, "\t\t * secondary channel offset: ", None) {
+            wifi.secondary_channel_offset = match offset.as_str() {
+                "above" => Some(ChannelOffset::Above),
+                "below" => Some(ChannelOffset::Below),
+                "no secondary" => Some(ChannelOffset::None),
+                _ => None,
+            };
+        } else if let Ok(class) = extract_value(line, "\t\t * current operating class: ", None) {
+            wifi.current_operating_class = class.trim().parse().ok();
+        } else if let Ok(indicator) = extract_value(line, "\t\t * Max BSSID indicator: ", None) {
+            mbssid.max_bssid_indicator = indicator.trim().parse().ok();
+        } else if line.starts_with("\t\t * Nontransmitted BSSID profile:") {
+            mbssid.profiles.push(Profile::default());
+        } else if let Ok(ssid) = extract_value(line, "\t\t\t * SSID: ", None) {
+            if let Some(profile) = mbssid.profiles.last_mut() {
+                profile.ssid = clean_ssid(&ssid);
+            }
+        } else if let Ok(bssid_index) = extract_value(line, "\t\t\t * BSSID index: ", None) {
+            if let Some(profile) = mbssid.profiles.last_mut() {
+                profile.bssid_index = bssid_index.trim().parse().ok();
+            }
+        } else if let Some(ssid) = line.strip_prefix("\tSSID:") {
+            // a blank SSID is printed as `SSID: `, or without the space
+            let ssid = ssid.strip_prefix(' ').unwrap_or(ssid);
+            // a hidden network only tells its SSID in probe responses, the
+            // beacon's blank one mustn't replace it
+            if !(in_beacon_ies && is_hidden_ssid(ssid) && !is_hidden_ssid(&wifi.ssid)) {
+                wifi.ssid = clean_ssid(ssid);
+                wifi.hidden_reason = Some(if is_hidden_ssid(ssid) {
+                    HiddenReason::Empty
+                } else {
+                    HiddenReason::Visible
+                });
+            }
+        } else if let Ok(mesh_id) = extract_value(line, "\tMESH ID: ", None) {
+            wifi.mesh.get_or_insert_with(MeshInfo::default).mesh_id = clean_ssid(&mesh_id);
+        } else if let Ok(protocol) =
+            extract_value(line, "\t\t * Active Path Selection Protocol ID: ", None)
+        {
+            wifi.mesh
+                .get_or_insert_with(MeshInfo::default)
+                .active_path_protocol = protocol.trim().parse().ok();
+        } else if let Ok(name) = extract_value(line, "\t\t * Device name: ", None) {
+            // the WPS name wins over the one of a Cisco element
+            if !name.trim().is_empty() {
+                wifi.device_name = Some(name.trim().to_string());
+            }
+        } else if let Ok(hex) = extract_value(line, "\tUnknown IE (133): ", None) {
+            if wifi.device_name.is_none() {
+                wifi.device_name = cisco_ap_name(&hex);
+            }
+        } else if let Ok(pairwise) = extract_value(line, "\t\t * Pairwise ciphers: ", None) {
+            if in_rsn {
+                ciphers.gcmp_256 = pairwise.split_whitespace().any(|c| c == "GCMP-256");
+            }
+        } else if let Ok(group) = extract_value(line, "\t\t * Group mgmt cipher suite: ", None) {
+            if in_rsn {
+                ciphers.bip_gmac_256 = group.trim() == "BIP-GMAC-256";
+            }
+        } else if let Ok(security) = extract_value(line, "\t\t * Authentication suites: ", None) {
+            // those of a WPA element only count without an RSN one
+            if in_rsn || wifi.security.is_empty() {
+                wifi.security = security;
+            }
+        } else if let Ok(limits) = extract_value(line, "\t\tChannels [", None) {
+            power.channels.extend(parse_channel_limit(&limits));
+        } else if let Ok(report) = extract_value(line, "\tTPC report: ", None) {
+            wifi.tpc_tx_power_dbm = tpc_value(&report, "power: ", " dBm");
+            wifi.link_margin_db = tpc_value(&report, "margin: ", " dB");
+        } else if let Ok(constraint) = extract_value(line, "\tPower constraint: ", Some(" dB")) {
+            power.constraint_db = constraint.trim().parse().ok();
+        } else if let Ok(color) = extract_value(line, "\t\tBSS Color: ", None) {
+            wifi.bss_color = color
+                .trim()
+                .parse()
+                .ok()
+                .filter(|color| (1..=63).contains(color));
+        } else if let Some(widths) = iw_widths(line) {
+            add_widths(&mut wifi, widths);
+        } else if let Some(generation) = iw_generation(line) {
+            wifi.wifi_generation = wifi.wifi_generation.max(Some(generation));
+            // every VHT hotspot supports 80 MHz
+            if line.starts_with("\tVHT capabilities:") {
+                add_widths(&mut wifi, &[80]);
+            }
+        }
+    }
+    wifi.max_tx_power_dbm = power.max_dbm(&wifi);
+    wifi.suite_b_ciphers = ciphers.is_suite_b();
+    apply_ds_channel(&mut wifi, ds_channel);
+    // push the last wifi
+    if !wifi.mac.is_empty()
+        && !wifi.signal_level.is_empty()
+        && (!wifi.channel.is_empty() || wifi.reported_frequency_mhz.is_some())
+    {
+        wifi.hidden_reason.get_or_insert(HiddenReason::Absent);
+        let nontransmitted = mbssid.expand(&wifi);
+        wifis.push(wifi);
+        wifis.extend(nontransmitted);
+    }
+
+    // newer kernels also list the nontransmitted BSSIDs as BSSes of their own,
+    // those are kept over the ones derived here
+    let listed: Vec<String> = wifis
+        .iter()
+        .filter(|wifi| wifi.transmitted_bssid.is_none())
+        .map(|wifi| wifi.mac.to_lowercase())
+        .collect();
+    wifis.retain(|wifi| {
+        wifi.transmitted_bssid.is_none() || !listed.contains(&wifi.mac.to_lowercase())
+    });
+
+    Ok(wifis)
+}
+
+/// Makes the channel of the DS Parameter Set element the channel of `wifi`,
+/// noting it in [`Wifi::advertised_channel`] when its frequency is on another
+/// channel
+fn apply_ds_channel(wifi: &mut Wifi, ds_channel: Option<u16>) {
+    let Some(ds_channel) = ds_channel else {
+        return;
+    };
+    let tuned = wifi
+        .reported_frequency_mhz
+        .and_then(crate::frequency_to_channel);
+    if tuned.is_some_and(|tuned| tuned != ds_channel) {
+        wifi.advertised_channel = Some(ds_channel);
+    }
+    wifi.channel = ds_channel.to_string();
+}
+
+/// The `Multiple BSSID:` element of one BSS, which announces the virtual
+/// BSSIDs it beacons for
+#[derive(Default)]
+struct MultipleBssid {
+    /// n, the set holds up to 2^n BSSIDs differing in their n lowest bits
+    max_bssid_indicator: Option<u8>,
+    profiles: Vec<Profile>,
+}
+
+/// One `Nontransmitted BSSID profile:`
+#[derive(Default)]
+struct Profile {
+    ssid: String,
+    bssid_index: Option<u8>,
+}
+
+impl MultipleBssid {
+    /// Returns a copy of the transmitting BSS `wifi` for every profile, with
+    /// the profile's SSID and derived BSSID. Profiles lacking either are left
+    /// out. A profile often has a security of its own, e.g. an open guest
+    /// network next to a WPA3 one, and `iw` doesn't print it, so the security,
+    /// PMF, capabilities and device name are left unknown.
+    fn expand(&self, wifi: &Wifi) -> Vec<Wifi> {
+        let (Some(n), Some(transmitted)) = (self.max_bssid_indicator, wifi.mac_addr()) else {
+            return Vec::new();
+        };
+        self.profiles
+            .iter()
+            .filter_map(|profile| {
+                let bssid = nontransmitted_bssid(transmitted, n, profile.bssid_index?)?;
+                if profile.ssid.is_empty() {
+                    return None;
+                }
+                Some(Wifi {
+                    mac: bssid.to_string(),
+                    ssid: profile.ssid.clone(),
+                    hidden_reason: Some(HiddenReason::Visible),
+                    transmitted_bssid: Some(transmitted),
+                    security: String::new(),
+                    pmf: None,
+                    suite_b_ciphers: false,
+                    capabilities: None,
+                    device_name: None,
+                    ..wifi.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+/// BSSID `index` of the set of 2^`n` that `transmitted` beacons for: its `n`
+/// lowest bits are those of `transmitted` plus `index`, modulo 2^`n`
+fn nontransmitted_bssid(transmitted: MacAddr, n: u8, index: u8) -> Option<MacAddr> {
+    if !(1..=8).contains(&n) {
+        return None;
+    }
+    let mask = u8::MAX >> (8 - n);
+    let mut octets = transmitted.0;
+    let low = octets[5] & mask;
+    octets[5] = (octets[5] & !mask) | (low.wrapping_add(index) & mask);
+    Some(MacAddr(octets))
+}
+
+/// Ciphers of the RSN element that WPA3-Enterprise 192-bit mode requires
+#[derive(Default)]
+struct RsnCiphers {
+    gcmp_256: bool,
+    bip_gmac_256: bool,
+}
+
+impl RsnCiphers {
+    /// Whether these are the ciphers of the 192-bit mode, for
+    /// [`Wifi::suite_b_ciphers`]
+    fn is_suite_b(&self) -> bool {
+        self.gcmp_256 && self.bip_gmac_256
+    }
+}
+
+/// Reads the number between `key` and `unit` of a TPC report, e.g. the 20 of
+/// `TX power: 20 dBm`, in any case
+fn tpc_value(report: &str, key: &str, unit: &str) -> Option<i32> {
+    let report = report.to_lowercase();
+    let start = report.find(key)? + key.len();
+    let rest = &report[start..];
+    rest[..rest.find(&unit.to_lowercase())?].trim().parse().ok()
+}
+
+/// Transmit power limits of one BSS, from the channel lines of its
+/// `Country:` element and its `Power constraint:`
+#[derive(Default)]
+struct TxPower {
+    /// (first channel, last channel, max dBm)
+    channels: Vec<(u16, u16, i32)>,
+    constraint_db: Option<i32>,
+}
+
+impl TxPower {
+    /// The limit for the channel of `wifi`, `None` if the country element
+    /// doesn't cover it
+    fn max_dbm(&self, wifi: &Wifi) -> Option<i32> {
+        let channel = wifi.channel_number()?;
+        let (_, _, max) = self
+            .channels
+            .iter()
+            .find(|(first, last, _)| (*first..=*last).contains(&channel))?;
+        Some(max - self.constraint_db.unwrap_or(0))
+    }
+}
+
+/// Parses the rest of a country element line, e.g. `36 - 48] @ 17 dBm`
+fn parse_channel_limit(limits: &str) -> Option<(u16, u16, i32)> {
+    let (channels, power) = limits.split_once("] @ ")?;
+    let (first, last) = channels.split_once(" - ")?;
+    let power = power.trim().strip_suffix(" dBm")?;
+    Some((
+        first.trim().parse().ok()?,
+        last.trim().parse().ok()?,
+        power.trim().parse().ok()?,
+    ))
+}
+
+/// Reads the AP name of a Cisco Aironet element from its hex dump, e.g.
+/// `00 00 8f 00 0f 00 ff 03 59 00 4c 6f 62 62 79 00 ...`: 16 bytes padded with
+/// NULs after 10 bytes of flags
+fn cisco_ap_name(hex: &str) -> Option<String> {
+    let bytes: Vec<u8> = hex
+        .split_whitespace()
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect::<Option<_>>()?;
+    let name = bytes.get(10..26)?;
+    let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+    let name = String::from_utf8_lossy(&name[..end]).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Appends the flag names of a `capability:` line, dropping the trailing hex
+/// value, e.g. `ESS Privacy ShortSlotTime (0x0411)`.
+fn push_capabilities(wifi: &mut Wifi, flags: &str) {
+    let capabilities = wifi.capabilities.get_or_insert_with(Vec::new);
+    for flag in flags.split_whitespace() {
+        if !flag.starts_with("(0x") && !capabilities.iter().any(|c| c == flag) {
+            capabilities.push(flag.to_string());
+        }
+    }
+}
+
+/// PMF mode the flags of an RSN `Capabilities:` line tell, e.g.
+/// `16-PTKSA-RC 1-GTKSA-RC MFP-required MFP-capable (0x00cc)`
+fn rsn_pmf(flags: &str) -> PmfMode {
+    let has = |flag| flags.split_whitespace().any(|f| f == flag);
+    if has("MFP-required") {
+        PmfMode::Required
+    } else if has("MFP-capable") {
+        PmfMode::Capable
+    } else {
+        PmfMode::Disabled
+    }
+}
+
+/// Channel widths a line of the HT, VHT or HE capabilities tells, e.g.
+/// `HT20/HT40` or `Supported Channel Width: 160 MHz`
+fn iw_widths(line: &str) -> Option<&'static [u16]> {
+    let flags = line.strip_prefix("\t\t\t")?;
+    match flags.trim_end() {
+        "HT20" => Some(&[20]),
+        "HT20/HT40" => Some(&[20, 40]),
+        "HE40/2.4GHz" => Some(&[40]),
+        "HE40/HE80/5GHz" => Some(&[40, 80]),
+        "HE160/5GHz" | "HE160/HE80+80/5GHz" => Some(&[160]),
+        flags => {
+            let width = flags.strip_prefix("Supported Channel Width: ")?;
+            width.starts_with("160 MHz").then_some(&[160])
+        }
+    }
+}
+
+/// Adds `widths` to the supported ones of `wifi`, keeping them sorted
+fn add_widths(wifi: &mut Wifi, widths: &[u16]) {
+    for width in widths {
+        if let Err(at) = wifi.supported_widths.binary_search(width) {
+            wifi.supported_widths.insert(at, *width);
+        }
+    }
+}
+
+/// Maps a capability/operation IE header (e.g. `\tHE capabilities:`) to the
+/// Wi-Fi generation it implies.
+fn iw_generation(line: &str) -> Option<u8> {
+    let ie = line.strip_prefix('\t')?.split(':').next()?.to_lowercase();
+    match ie.as_str() {
+        "ht capabilities" | "ht operation" => Some(4),
+        "vht capabilities" | "vht operation" => Some(5),
+        "he capabilities" | "he operation" => Some(6),
+        "eht capabilities" | "eht operation" => Some(7),
+        _ => None,
+    }
+}
+
+pub(crate) fn extract_value(
+    line: &str,
+    pattern_start: &str,
+    pattern_end: Option<&str>,
+) -> anyhow::Result<String> {
+    match line.strip_prefix(pattern_start) {
+        Some(rest) if !rest.is_empty() => {
+            let end = match pattern_end {
+                Some(end) => rest.find(end).context("No value")?,
+                None => rest.len(),
+            };
+            Ok(rest[..end].to_string())
+        }
+        _ => anyhow::bail!("No value"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Band, SecurityKind};
+    use std::fs::File;
+    use std::io::Read;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_parse_iw() {
+        let expected: Vec<Wifi> = vec![
+            Wifi {
+                mac: "11:22:33:44:55:66".to_string(),
+                ssid: "hello".to_string(),
+                hidden_reason: Some(HiddenReason::Visible),
+                channel: "10".to_string(),
+                reported_frequency_mhz: Some(2457),
+                signal_level: "-67.00".to_string(),
+                security: "PSK".to_string(),
+                device_name: Some("XiaoMiRouter".to_string()),
+                wifi_generation: Some(4),
+                uptime: Some(Duration::from_micros(7031320135454)),
+                secondary_channel_offset: Some(ChannelOffset::None),
+                last_seen_ms: Some(5939),
+                pmf: Some(PmfMode::Disabled),
+                max_tx_power_dbm: Some(16),
+                capabilities: Some(
+                    [
+                        "ESS",
+                        "Privacy",
+                        "ShortSlotTime",
+                        "APSD",
+                        "1-PTKSA-RC",
+                        "1-GTKSA-RC",
+                    ]
+                    .map(String::from)
+                    .to_vec(),
+                ),
+                supported_widths: vec![20],
+                ..Default::default()
+            },
+            Wifi {
+                mac: "66:77:88:99:aa:bb".to_string(),
+                ssid: "hello-world-foo-bar".to_string(),
+                hidden_reason: Some(HiddenReason::Visible),
+                channel: "8".to_string(),
+                reported_frequency_mhz: Some(2447),
+                signal_level: "-89.00".to_string(),
+                security: "PSK".to_string(),
+                wifi_generation: Some(4),
+                uptime: Some(Duration::from_micros(2181790617990)),
+                secondary_channel_offset: Some(ChannelOffset::Below),
+                last_seen_ms: Some(6248),
+                pmf: Some(PmfMode::Disabled),
+                capabilities: Some(
+                    [
+                        "ESS",
+                        "Privacy",
+                        "ShortSlotTime",
+                        "16-PTKSA-RC",
+                        "1-GTKSA-RC",
+                    ]
+                    .map(String::from)
+                    .to_vec(),
+                ),
+                supported_widths: vec![20, 40],
+                ..Default::default()
+            },
+        ];
+
+        // FIXME: should be a better way to create test fixtures
+        let path = PathBuf::from("tests/fixtures/iw/iw_dev_scan_01.txt");
+        let mut file = File::open(path).unwrap();
+        let mut filestr = String::new();
+        let _ = file.read_to_string(&mut filestr).unwrap();
+
+        let result = parse_iw(&filestr).unwrap();
+        assert_eq!(expected[0], result[0]);
+        assert_eq!(expected[1], result[5]);
+    }
+
+    #[test]
+    fn should_parse_device_name() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_vendor_ie.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let names: Vec<(&str, Option<&str>)> = result
+            .iter()
+            .map(|w| (w.ssid.as_str(), w.device_name.as_deref()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("corp", Some("AP-Lobby")),
+                ("guest", Some("Archer C6")),
+                ("plain", None),
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn should_parse_supported_widths() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_vht.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let widths: Vec<(&str, &[u16])> = result
+            .iter()
+            .map(|w| (w.ssid.as_str(), w.supported_widths.as_slice()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("vht160", [20, 40, 80, 160].as_slice()),
+                ("vht80", &[20, 40, 80]),
+                ("he-2g", &[20, 40]),
+                ("legacy", &[20]),
+            ],
+            widths
+        );
+
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_country.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        assert!(result.iter().all(|w| w.supported_widths.is_empty()));
+    }
+
+    #[test]
+    fn should_expand_multiple_bssid() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_mbssid.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let entries: Vec<(&str, &str, Option<String>)> = result
+            .iter()
+            .map(|w| {
+                let transmitted = w.transmitted_bssid.map(|mac| mac.to_string());
+                (w.mac.as_str(), w.ssid.as_str(), transmitted)
+            })
+            .collect();
+        let transmitted = Some("3c:37:86:10:20:30".to_string());
+        assert_eq!(
+            vec![
+                ("3c:37:86:10:20:30", "corp", None),
+                ("3c:37:86:10:20:31", "corp-guest", transmitted.clone()),
+                ("3c:37:86:10:20:37", "corp-lab", transmitted),
+                // listed by the kernel itself, so not derived a second time
+                ("3c:37:86:10:20:32", "corp-iot", None),
+            ],
+            entries
+        );
+
+        // the virtual BSSIDs share the radio of the transmitting one
+        assert_eq!("36", result[1].channel);
+        assert_eq!("-58.00", result[1].signal_level);
+        assert_eq!(Some(6), result[1].wifi_generation);
+
+        // the profiles don't share the security of the transmitting one
+        assert_eq!("SAE", result[0].security);
+        assert_eq!("", result[1].security);
+        assert_eq!(None, result[1].pmf);
+        assert_eq!(None, result[1].capabilities);
+        assert_eq!("PSK", result[3].security);
+        assert_eq!(Some(PmfMode::Disabled), result[3].pmf);
+    }
+
+    #[test]
+    fn should_prefer_probe_response_ssid_of_hidden_network() {
+        let data =
+            std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_probe_response.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let ssids: Vec<&str> = result.iter().map(|w| w.ssid.as_str()).collect();
+        // a beacon that does name the network is newer than the probe response
+        assert_eq!(
+            vec!["backroom", "home-renamed", "\\x00\\x00\\x00\\x00"],
+            ssids
+        );
+        assert!(!result[0].is_hidden());
+        assert!(result[2].is_hidden());
+    }
+
+    #[test]
+    fn should_tell_empty_from_absent_ssid() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_hidden.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let reasons: Vec<(&str, &str, Option<HiddenReason>)> = result
+            .iter()
+            .map(|w| (w.mac.as_str(), w.ssid.as_str(), w.hidden_reason))
+            .collect();
+        assert_eq!(
+            vec![
+                ("02:aa:bb:cc:ff:01", "lobby", Some(HiddenReason::Visible)),
+                ("02:aa:bb:cc:ff:02", "", Some(HiddenReason::Empty)),
+                (
+                    "02:aa:bb:cc:ff:03",
+                    "\\x00\\x00\\x00",
+                    Some(HiddenReason::Empty)
+                ),
+                ("02:aa:bb:cc:ff:04", "", Some(HiddenReason::Absent)),
+            ],
+            reasons
+        );
+        assert!(result[1..].iter().all(Wifi::is_hidden));
+
+        // the probe response names a network its beacon cloaks
+        let data =
+            std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_probe_response.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        assert_eq!(Some(HiddenReason::Visible), result[0].hidden_reason);
+        assert_eq!(Some(HiddenReason::Empty), result[2].hidden_reason);
+    }
+
+    #[test]
+    fn should_derive_nontransmitted_bssid() {
+        let transmitted: MacAddr = "02:00:00:00:00:fe".parse().unwrap();
+        let bssid = |n, index| nontransmitted_bssid(transmitted, n, index).map(|m| m.to_string());
+        assert_eq!(Some("02:00:00:00:00:ff".to_string()), bssid(2, 1));
+        // the low bits wrap around without touching the others
+        assert_eq!(Some("02:00:00:00:00:fc".to_string()), bssid(2, 2));
+        assert_eq!(None, bssid(0, 1));
+    }
+
+    #[test]
+    fn should_parse_iw_wifi_generation() {
+        let path = PathBuf::from("tests/fixtures/iw/iw_dev_scan_eht.txt");
+        let mut file = File::open(path).unwrap();
+        let mut filestr = String::new();
+        let _ = file.read_to_string(&mut filestr).unwrap();
+
+        let result = parse_iw(&filestr).unwrap();
+        assert_eq!(3, result.len());
+        assert_eq!("wifi7-lab", result[0].ssid);
+        assert_eq!(Some(7), result[0].wifi_generation);
+        assert_eq!(Some(6), result[1].wifi_generation);
+        assert_eq!(Some(5), result[2].wifi_generation);
+    }
+
+    #[test]
+    fn should_parse_iw_frequency() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_6ghz.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let summary: Vec<_> = result
+            .iter()
+            .map(|w| {
+                (
+                    w.ssid.as_str(),
+                    w.reported_frequency_mhz,
+                    w.channel.as_str(),
+                    w.channel_number(),
+                    w.band(),
+                )
+            })
+            .collect();
+        // 6 GHz hotspots have no HT operation, channel 1 is 6 GHz by its
+        // frequency
+        assert_eq!(
+            vec![
+                ("six-low", Some(5955), "", Some(1), Some(Band::Band6GHz)),
+                ("six-mid", Some(6135), "", Some(37), Some(Band::Band6GHz)),
+                ("two-four", Some(2412), "1", Some(1), Some(Band::Band2_4GHz)),
+            ],
+            summary
+        );
+        assert_eq!(Some(5955), result[0].frequency_mhz());
+    }
+
+    #[test]
+    fn should_parse_bss_color() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_he.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let colors: Vec<(&str, Option<u8>)> = result
+            .iter()
+            .map(|w| (w.ssid.as_str(), w.bss_color))
+            .collect();
+        assert_eq!(
+            vec![
+                ("dense-floor-a", Some(21)),
+                ("dense-floor-b", Some(63)),
+                ("no-color", None),
+                ("wifi5", None),
+            ],
+            colors
+        );
+
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_eht.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let colors: Vec<Option<u8>> = result.iter().map(|w| w.bss_color).collect();
+        assert_eq!(vec![Some(42), Some(7), None], colors);
+    }
+
+    #[test]
+    fn should_parse_iw_uptime() {
+        let path = PathBuf::from("tests/fixtures/iw/iw_dev_scan_eht.txt");
+        let mut file = File::open(path).unwrap();
+        let mut filestr = String::new();
+        let _ = file.read_to_string(&mut filestr).unwrap();
+
+        let result = parse_iw(&filestr).unwrap();
+        // TSF: 2310987066 usec (0d, 00:38:30)
+        assert_eq!(Some(Duration::from_micros(2310987066)), result[0].uptime);
+        assert_eq!(
+            Some(Duration::from_secs(38 * 60 + 30)),
+            result[0].uptime.map(|d| Duration::from_secs(d.as_secs()))
+        );
+    }
+
+    #[test]
+    fn should_parse_iw_capabilities() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_eht.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        assert_eq!(
+            Some(
+                [
+                    "ESS",
+                    "Privacy",
+                    "SpectrumMgmt",
+                    "ShortSlotTime",
+                    "RadioMeasure",
+                    "16-PTKSA-RC",
+                    "1-GTKSA-RC",
+                    "MFP-required",
+                    "MFP-capable",
+                ]
+                .map(String::from)
+                .to_vec()
+            ),
+            result[0].capabilities
+        );
+    }
+
+    #[test]
+    fn should_parse_iw_pmf() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_pmf.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let modes: Vec<(&str, Option<PmfMode>)> = result
+            .iter()
+            .map(|wifi| (wifi.ssid.as_str(), wifi.pmf))
+            .collect();
+        assert_eq!(
+            vec![
+                ("sae-only", Some(PmfMode::Required)),
+                ("transition", Some(PmfMode::Capable)),
+                ("legacy", Some(PmfMode::Disabled)),
+                ("open", None),
+            ],
+            modes
+        );
+    }
+
+    #[test]
+    fn should_parse_iw_secondary_channel_offset() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_ht40.txt").unwrap();
+        let result = parse_iw(&data).unwrap();
+        let offsets: Vec<(&str, Option<ChannelOffset>)> = result
+            .iter()
+            .map(|wifi| (wifi.ssid.as_str(), wifi.secondary_channel_offset))
+            .collect();
+        assert_eq!(
+            vec![
+                ("bonded-below", Some(ChannelOffset::Below)),
+                ("bonded-above", Some(ChannelOffset::Above)),
+                ("narrow", Some(ChannelOffset::None)),
+            ],
+            offsets
+        );
+
+        assert_eq!(Some(84), result[0].current_operating_class);
+        assert_eq!(None, result[1].current_operating_class);
+        let classes: Vec<Option<u8>> = result.iter().map(Wifi::operating_class).collect();
+        assert_eq!(vec![Some(84), Some(83), Some(81)], classes);
+    }
+
+    #[test]
+    fn should_parse_iw_crlf_and_bom() {
+        let lf = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_01.txt").unwrap();
+        let crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        assert_eq!(parse_iw(&lf).unwrap(), parse_iw(&crlf).unwrap());
+    }
+
+    #[test]
+    fn should_report_broken_line() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_01.txt").unwrap();
+        let data = data.replacen("\tsignal: -42.00 dBm", "\tsignal: -4z.00 dBm", 1);
+
+        let err = parse_iw(&data).unwrap_err();
+        assert_eq!(
+            "line 80: invalid signal: \"\\tsignal: -4z.00 dBm\"",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_parse_iw_max_tx_power() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_country.txt").unwrap();
+        let wifis = parse_iw(&data).unwrap();
+        let powers: Vec<(&str, Option<i32>)> = wifis
+            .iter()
+            .map(|wifi| (wifi.ssid.as_str(), wifi.max_tx_power_dbm))
+            .collect();
+        assert_eq!(
+            vec![
+                ("office-5g", Some(14)),
+                ("home", Some(20)),
+                ("no-country", None)
+            ],
+            powers
+        );
+    }
+
+    #[test]
+    fn should_parse_iw_tpc_report() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_tpc.txt").unwrap();
+        let wifis = parse_iw(&data).unwrap();
+        let reports: Vec<(&str, Option<i32>, Option<i32>)> = wifis
+            .iter()
+            .map(|wifi| {
+                (
+                    wifi.ssid.as_str(),
+                    wifi.tpc_tx_power_dbm,
+                    wifi.link_margin_db,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("lab-5g", Some(18), Some(12)),
+                ("lab-2g", Some(-2), None),
+                ("no-tpc", None, None),
+            ],
+            reports
+        );
+    }
+
+    #[test]
+    fn should_prefer_ds_parameter_set_channel() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_ds_channel.txt").unwrap();
+        let wifis = parse_iw(&data).unwrap();
+        let channels: Vec<_> = wifis
+            .iter()
+            .map(|wifi| {
+                (
+                    wifi.ssid.as_str(),
+                    wifi.channel.as_str(),
+                    wifi.reported_frequency_mhz,
+                    wifi.advertised_channel,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("misconfigured", "1", Some(2437), Some(1)),
+                ("consistent", "11", Some(2462), None),
+                ("no-ds", "", Some(2412), None),
+            ],
+            channels
+        );
+        assert_eq!(Some(1), wifis[0].channel_number());
+        assert_eq!(Some(1), wifis[2].channel_number());
+    }
+
+    #[test]
+    fn should_parse_iw_mesh() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_mesh.txt").unwrap();
+        let wifis = parse_iw(&data).unwrap();
+
+        assert_eq!(
+            Some(MeshInfo {
+                mesh_id: "backhaul-mesh".to_string(),
+                active_path_protocol: Some(1),
+            }),
+            wifis[0].mesh
+        );
+        assert!(wifis[0].is_mesh());
+        assert_eq!("36", wifis[0].channel);
+        assert_eq!(None, wifis[1].mesh);
+        assert!(!wifis[1].is_mesh());
+    }
+
+    #[test]
+    fn should_detect_suite_b() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_suite_b.txt").unwrap();
+        let wifis = parse_iw(&data).unwrap();
+        let kinds: Vec<(&str, &str, Option<SecurityKind>)> = wifis
+            .iter()
+            .map(|w| (w.ssid.as_str(), w.security.as_str(), w.security_kind()))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "cnsa",
+                    "IEEE 802.1X/SUITE-B-192",
+                    Some(SecurityKind::Wpa3Enterprise192)
+                ),
+                (
+                    "cnsa-ft",
+                    "FT/IEEE 802.1X/SHA-384",
+                    Some(SecurityKind::Wpa3Enterprise192)
+                ),
+                // 802.1X with CCMP isn't the 192-bit mode
+                ("corp", "IEEE 802.1X/SHA-256", Some(SecurityKind::Wpa2)),
+                // the TKIP of its WPA element doesn't replace the RSN ciphers
+                (
+                    "cnsa-legacy",
+                    "IEEE 802.1X/SHA-384",
+                    Some(SecurityKind::Wpa3Enterprise192)
+                ),
+            ],
+            kinds
+        );
+        assert!(wifis[1].suite_b_ciphers);
+        assert!(!wifis[2].suite_b_ciphers);
+    }
+}
@@ -0,0 +1,120 @@
+use regex::Regex;
+
+use super::{clean_ssid, line_error, normalize};
+use crate::{Wifi, WifiError};
+
+/// Parses the output of `iwctl station <interface> get-networks rssi-dbms`,
+/// the network list of `iwd`. The colours `iwctl` prints are ignored. It
+/// tells no BSSID or channel, only the SSID, the security (`open`, `psk`,
+/// `8021x`, ...) and the signal, which `iwd` reports in hundredths of a dBm.
+/// The network marked with `>` is the connected one.
+pub fn parse_iwctl(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
+    let network_list = normalize(network_list);
+    let colours = Regex::new(r"\x1b\[[0-9;]*m")?;
+    let network_list = colours.replace_all(&network_list, "");
+    if network_list.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut lines = network_list.lines().enumerate();
+    let header = lines
+        .by_ref()
+        .map(|(_, line)| line)
+        .find(|line| line.contains("Network name"))
+        .ok_or(WifiError::HeaderNotFound("Network name"))?;
+    // columns are counted in characters, SSIDs aren't always ASCII
+    let column_of = |name: &'static str| -> anyhow::Result<usize> {
+        let start = header.find(name).ok_or(WifiError::HeaderNotFound(name))?;
+        Ok(header[..start].chars().count())
+    };
+    let col_ssid = column_of("Network name")?;
+    let col_security = column_of("Security")?;
+    let col_signal = column_of("Signal")?;
+
+    let mut wifis = Vec::new();
+    for (index, line) in lines {
+        if line.trim().is_empty() || line.starts_with("---") {
+            continue;
+        }
+        let column = |start: usize, end: usize| -> String {
+            line.chars().skip(start).take(end - start).collect()
+        };
+        let ssid = column(col_ssid, col_security);
+        let signal = column(col_signal, usize::MAX);
+        let signal = signal.trim();
+        let signal_level = match signal.parse::<i32>() {
+            Ok(dbm) if dbm.abs() > 200 => (dbm / 100).to_string(),
+            // without rssi-dbms the signal is drawn as stars
+            _ if signal.is_empty() || signal.starts_with('*') => String::new(),
+            Ok(dbm) => dbm.to_string(),
+            Err(_) => return Err(line_error(index, line, "invalid signal")),
+        };
+
+        wifis.push(Wifi {
+            ssid: clean_ssid(ssid.trim()),
+            security: column(col_security, col_signal).trim().to_string(),
+            signal_level,
+            associated: column(0, col_ssid).contains('>'),
+            ..Default::default()
+        });
+    }
+    Ok(wifis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_iwctl() {
+        let data = std::fs::read_to_string("tests/fixtures/iwctl/iwctl_get_networks.txt").unwrap();
+        let result = parse_iwctl(&data).unwrap();
+
+        assert_eq!(
+            Wifi {
+                ssid: "HomeNetwork".to_string(),
+                signal_level: "-48".to_string(),
+                security: "psk".to_string(),
+                associated: true,
+                ..Default::default()
+            },
+            result[0]
+        );
+        let summary: Vec<(&str, &str, &str, bool)> = result
+            .iter()
+            .map(|w| {
+                (
+                    w.ssid.as_str(),
+                    w.security.as_str(),
+                    w.signal_level.as_str(),
+                    w.associated,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("HomeNetwork", "psk", "-48", true),
+                ("Coffee Shop Guest", "open", "-67", false),
+                ("Office", "8021x", "-71", false),
+                ("Attic", "wep", "-85", false),
+            ],
+            summary
+        );
+
+        assert!(parse_iwctl("").unwrap().is_empty());
+        let err = parse_iwctl("No station on device: 'wlan9'\n").unwrap_err();
+        assert_eq!(
+            Some(&WifiError::HeaderNotFound("Network name")),
+            err.downcast_ref::<WifiError>()
+        );
+    }
+
+    #[test]
+    fn should_parse_iwctl_stars() {
+        let data = "      Network name   Security  Signal\n      Café           psk       ***\n";
+        let result = parse_iwctl(data).unwrap();
+        assert_eq!("Café", result[0].ssid);
+        assert_eq!("psk", result[0].security);
+        assert_eq!("", result[0].signal_level);
+    }
+}
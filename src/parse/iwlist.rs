@@ -0,0 +1,138 @@
+use super::{clean_ssid, normalize};
+use crate::Wifi;
+
+/// Parses the output of the deprecated `iwlist scan`. The signal level is
+/// taken from `Signal level=` when it is in dBm, otherwise the `Quality=`
+/// fraction is turned into dBm the same way as `netsh` percentages.
+pub fn parse_iwlist(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
+    let network_list = normalize(network_list);
+    let mut wifis = Vec::new();
+    let mut cell: Option<Cell> = None;
+    for line in network_list.lines().map(str::trim) {
+        if let Some((_, mac)) = line
+            .strip_prefix("Cell ")
+            .and_then(|line| line.split_once("Address: "))
+        {
+            wifis.extend(cell.take().map(Cell::into_wifi));
+            cell = Some(Cell {
+                wifi: Wifi {
+                    mac: mac.trim().to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(cell) = cell.as_mut() else {
+            continue;
+        };
+        if let Some(channel) = line.strip_prefix("Channel:") {
+            cell.wifi.channel = channel.to_string();
+        } else if let Some(ssid) = line.strip_prefix("ESSID:") {
+            cell.wifi.ssid = clean_ssid(ssid.trim_matches('"'));
+        } else if line.starts_with("Quality") {
+            cell.wifi.signal_level = iwlist_signal(line).unwrap_or_default();
+        } else if let Some(key) = line.strip_prefix("Encryption key:") {
+            cell.encrypted = key == "on";
+        } else if let Some(ie) = line.strip_prefix("IE: ") {
+            if ie.starts_with("IEEE 802.11i/WPA2") {
+                cell.wpa.push("WPA2");
+            } else if ie.starts_with("WPA Version") {
+                cell.wpa.push("WPA");
+            }
+        }
+    }
+    wifis.extend(cell.map(Cell::into_wifi));
+    Ok(wifis)
+}
+
+#[derive(Default)]
+struct Cell {
+    wifi: Wifi,
+    encrypted: bool,
+    wpa: Vec<&'static str>,
+}
+
+impl Cell {
+    /// Encryption without a WPA information element means WEP
+    fn into_wifi(mut self) -> Wifi {
+        self.wpa.sort_unstable();
+        self.wifi.security = match (self.encrypted, self.wpa.is_empty()) {
+            (false, _) => String::new(),
+            (true, true) => "WEP".to_string(),
+            (true, false) => self.wpa.join(" "),
+        };
+        self.wifi
+    }
+}
+
+/// Reads `Quality=62/70  Signal level=-48 dBm`, or `Quality:62/100 ...` on
+/// drivers that only report a relative level.
+fn iwlist_signal(line: &str) -> Option<String> {
+    let level = line
+        .split_once("Signal level")
+        .map(|(_, level)| level.trim_start_matches(['=', ':']));
+    if let Some(dbm) = level.and_then(|level| level.trim().strip_suffix("dBm")) {
+        return Some(dbm.trim().to_string());
+    }
+
+    let quality = line.split_whitespace().next()?;
+    let (value, max) = quality
+        .trim_start_matches("Quality")
+        .trim_start_matches(['=', ':'])
+        .split_once('/')?;
+    let (value, max): (f64, f64) = (value.parse().ok()?, max.parse().ok()?);
+    if max == 0.0 {
+        return None;
+    }
+    let percent = (value / max * 100.0).round() as i32;
+    Some((percent / 2 - 100).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_iwlist() {
+        let data = std::fs::read_to_string("tests/fixtures/iwlist/iwlist_scan_01.txt").unwrap();
+        let result = parse_iwlist(&data).unwrap();
+
+        assert_eq!(4, result.len());
+        assert_eq!(
+            Wifi {
+                mac: "00:1A:2B:3C:4D:01".to_string(),
+                ssid: "HomeNet".to_string(),
+                channel: "6".to_string(),
+                signal_level: "-48".to_string(),
+                security: "WPA2".to_string(),
+                ..Default::default()
+            },
+            result[0]
+        );
+        assert_eq!("Mixed Mode AP", result[1].ssid);
+        assert_eq!("WPA WPA2", result[1].security);
+        assert_eq!("WEP", result[2].security);
+        assert_eq!("", result[3].security);
+        assert_eq!("36", result[3].channel);
+    }
+
+    #[test]
+    fn should_convert_relative_quality() {
+        assert_eq!(
+            Some("-65".to_string()),
+            iwlist_signal("Quality:70/100  Signal level:70/100")
+        );
+        assert_eq!(
+            Some("-48".to_string()),
+            iwlist_signal("Quality=62/70  Signal level=-48 dBm")
+        );
+    }
+
+    #[test]
+    fn should_parse_iwlist_crlf_and_bom() {
+        let lf = std::fs::read_to_string("tests/fixtures/iwlist/iwlist_scan_01.txt").unwrap();
+        let crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        assert_eq!(parse_iwlist(&lf).unwrap(), parse_iwlist(&crlf).unwrap());
+    }
+}
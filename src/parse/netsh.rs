@@ -0,0 +1,186 @@
+use anyhow::Context;
+use itertools::izip;
+use regex::Regex;
+
+use super::{clean_ssid, line_error, normalize};
+use crate::Wifi;
+
+/// Parses the output of `netsh wlan show networks mode=Bssid`.
+pub fn parse_netsh(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
+    let network_list = normalize(network_list);
+    let mut wifis = Vec::new();
+
+    // Regex for matching SSID and MAC, since these aren't pulled directly
+    let ssid_regex = Regex::new("^ [0-9]* : ")?;
+    let mac_regex = Regex::new("[a-fA-F0-9:]{17}")?;
+
+    // each network starts with a line beginning with `SSID`, kept as lines
+    // with their index so errors can point at them
+    let mut blocks: Vec<Vec<(usize, &str)>> = vec![vec![]];
+    for (index, line) in network_list.lines().enumerate() {
+        match line.strip_prefix("SSID") {
+            Some(rest) if index > 0 => blocks.push(vec![(index, rest)]),
+            _ => blocks.last_mut().context("No block")?.push((index, line)),
+        }
+    }
+
+    for block in blocks {
+        let mut wifi_macs = Vec::new();
+        let mut wifi_ssid = String::new();
+        let mut wifi_channels = Vec::new();
+        let mut wifi_rssi = Vec::new();
+        let mut wifi_security = String::new();
+        // set by the `Radio type` line following each BSSID, if any
+        let mut wifi_generations: Vec<Option<u8>> = Vec::new();
+
+        for (index, line) in block {
+            if ssid_regex.is_match(line) {
+                wifi_ssid = line.split(':').nth(1).unwrap_or("").trim().to_string();
+            } else if line.contains("Authentication") {
+                wifi_security = line.split(':').nth(1).unwrap_or("").trim().to_string();
+            } else if line.contains("BSSID") {
+                let mac = mac_regex
+                    .find(line)
+                    .ok_or_else(|| line_error(index, line, "no BSSID"))?;
+                wifi_macs.push(mac);
+                wifi_generations.push(None);
+            } else if line.contains("Radio type") {
+                let radio_type = line.split(':').nth(1).unwrap_or("").trim();
+                if let Some(generation) = wifi_generations.last_mut() {
+                    *generation = netsh_generation(radio_type);
+                }
+            } else if line.contains("Signal") {
+                let percent = line.split(':').nth(1).unwrap_or("").trim().replace('%', "");
+                let percent: i32 = percent
+                    .parse()
+                    .map_err(|_| line_error(index, line, "invalid signal"))?;
+                wifi_rssi.push(percent / 2 - 100);
+            } else if line.contains("Channel") {
+                wifi_channels.push(line.split(':').nth(1).unwrap_or("").trim().to_string());
+            }
+        }
+
+        for (mac, channel, rssi, wifi_generation) in
+            izip!(wifi_macs, wifi_channels, wifi_rssi, wifi_generations)
+        {
+            wifis.push(Wifi {
+                mac: mac.as_str().to_string(),
+                ssid: clean_ssid(&wifi_ssid),
+                channel: channel.to_string(),
+                signal_level: rssi.to_string(),
+                security: wifi_security.to_string(),
+                wifi_generation,
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(wifis)
+}
+
+/// Wi-Fi generation from the radio type, e.g. 5 for `802.11ac`. The
+/// pre-802.11n types have none, as with the other backends.
+fn netsh_generation(radio_type: &str) -> Option<u8> {
+    match radio_type {
+        "802.11n" => Some(4),
+        "802.11ac" => Some(5),
+        "802.11ax" => Some(6),
+        "802.11be" => Some(7),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_netsh() {
+        use std::fs;
+
+        // Note: formula for % to dBm is (% / 100) - 100
+        let expected = [
+            Wifi {
+                mac: "ab:cd:ef:01:23:45".to_string(),
+                ssid: "Vodafone Hotspot".to_string(),
+                channel: "6".to_string(),
+                signal_level: "-92".to_string(),
+                security: "Open".to_string(),
+                wifi_generation: Some(4),
+                ..Default::default()
+            },
+            Wifi {
+                mac: "ab:cd:ef:01:23:45".to_string(),
+                ssid: "Vodafone Hotspot".to_string(),
+                channel: "6".to_string(),
+                signal_level: "-73".to_string(),
+                security: "Open".to_string(),
+                wifi_generation: Some(4),
+                ..Default::default()
+            },
+            Wifi {
+                mac: "ab:cd:ef:01:23:45".to_string(),
+                ssid: "EdaBox".to_string(),
+                channel: "11".to_string(),
+                signal_level: "-82".to_string(),
+                security: "WPA2-Personal".to_string(),
+                wifi_generation: Some(4),
+                ..Default::default()
+            },
+            Wifi {
+                mac: "ab:cd:ef:01:23:45".to_string(),
+                ssid: "FRITZ!Box 2345 Cable".to_string(),
+                channel: "1".to_string(),
+                signal_level: "-50".to_string(),
+                security: "WPA2-Personal".to_string(),
+                wifi_generation: Some(4),
+                ..Default::default()
+            },
+        ];
+
+        // Load test fixtures
+        let fixture = fs::read_to_string("tests/fixtures/netsh/netsh01_windows81.txt").unwrap();
+
+        let result = parse_netsh(&fixture).unwrap();
+        assert_eq!(expected[0], result[0]);
+        assert_eq!(expected[1], result[1]);
+        assert_eq!(expected[2], result[2]);
+        assert_eq!(expected[3], result[3]);
+    }
+
+    #[test]
+    fn should_parse_netsh_radio_type() {
+        let data = std::fs::read_to_string("tests/fixtures/netsh/netsh02_radio_types.txt").unwrap();
+        let result = parse_netsh(&data).unwrap();
+        assert_eq!(
+            vec![Some(6), Some(5), None, None, Some(4)],
+            result.iter().map(|w| w.wifi_generation).collect::<Vec<_>>()
+        );
+        assert_eq!("Office", result[1].ssid);
+        assert_eq!("36", result[1].channel);
+    }
+
+    #[test]
+    fn should_parse_netsh_crlf_and_bom() {
+        let lf = std::fs::read_to_string("tests/fixtures/netsh/netsh01_windows81.txt").unwrap();
+        let crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        assert_eq!(parse_netsh(&lf).unwrap(), parse_netsh(&crlf).unwrap());
+    }
+
+    #[test]
+    fn should_report_broken_line() {
+        let data = std::fs::read_to_string("tests/fixtures/netsh/netsh01_windows81.txt").unwrap();
+        let mut lines: Vec<&str> = data.lines().collect();
+        lines[9] = "         Signal             : strong";
+
+        let err = parse_netsh(&lines.join("\n")).unwrap_err();
+        assert!(
+            err.to_string().starts_with("line 10: invalid signal"),
+            "{err}"
+        );
+
+        lines[9] = "    BSSID 1                 : ab:cd";
+        let err = parse_netsh(&lines.join("\n")).unwrap_err();
+        assert!(err.to_string().starts_with("line 10: no BSSID"), "{err}");
+    }
+}
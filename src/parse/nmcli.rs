@@ -0,0 +1,84 @@
+use super::{clean_ssid, normalize};
+use crate::Wifi;
+
+/// Parses the output of
+/// `nmcli --color no --terse -f ssid,chan,signal,security,bssid dev wifi list`.
+/// The signal, a percentage in nmcli, is converted to dBm.
+pub fn parse_nmcli(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
+    Ok(parse_nmcli_partial(network_list).0)
+}
+
+/// Like [`parse_nmcli`], also returning how many non-empty lines didn't have
+/// all the fields and were skipped.
+pub(crate) fn parse_nmcli_partial(network_list: &str) -> (Vec<Wifi>, usize) {
+    let data = normalize(network_list);
+    let mut result = vec![];
+    let mut skipped = 0;
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        let fields: Vec<&str> = line.splitn(5, ':').collect();
+        let [ssid, channel, signal_level, security, mac] = fields[..] else {
+            skipped += 1;
+            continue;
+        };
+        result.push(Wifi {
+            ssid: clean_ssid(ssid),
+            channel: channel.to_string(),
+            signal_level: percent_to_dbm(signal_level),
+            security: security.to_string(),
+            mac: mac.replace(r"\:", ":").to_string(),
+            ..Default::default()
+        });
+    }
+    (result, skipped)
+}
+
+/// nmcli reports the signal as a percentage, converted to dBm the way the
+/// iwlist and netsh parsers do. Anything else is kept as is.
+fn percent_to_dbm(signal_level: &str) -> String {
+    match signal_level.trim().parse::<i32>() {
+        Ok(percent) => (percent / 2 - 100).to_string(),
+        Err(_) => signal_level.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_nmcli() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli01.txt").unwrap();
+        let result = parse_nmcli(&data).unwrap();
+        assert_eq!(3, result.len());
+        assert_eq!(
+            Wifi {
+                mac: "11:22:33:44:55:66".to_string(),
+                ssid: "hello".to_string(),
+                channel: "6".to_string(),
+                signal_level: "-58".to_string(),
+                security: "WPA2".to_string(),
+                ..Default::default()
+            },
+            result[0]
+        );
+        assert!(result
+            .iter()
+            .all(|w| w.signal_dbm().is_some_and(|dbm| dbm < 0)));
+    }
+
+    #[test]
+    fn should_count_skipped_nmcli_lines() {
+        let data = "hello:6:85:WPA2:11\\:22\\:33\\:44\\:55\\:66\ntruncated:11\n\n";
+        let (wifis, skipped) = parse_nmcli_partial(data);
+        assert_eq!(1, wifis.len());
+        assert_eq!("hello", wifis[0].ssid);
+        assert_eq!(1, skipped);
+    }
+
+    #[test]
+    fn should_parse_nmcli_crlf_and_bom() {
+        let lf = std::fs::read_to_string("tests/fixtures/nmcli/nmcli01.txt").unwrap();
+        let crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        assert_eq!(parse_nmcli(&lf).unwrap(), parse_nmcli(&crlf).unwrap());
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::Context;
+
+use super::{clean_ssid, normalize};
+use crate::json::{self, Value};
+use crate::{InterfaceScan, Wifi};
+
+/// Parses `system_profiler SPAirPortDataType -json`. The network this machine
+/// is connected to is listed under its own key and comes first, marked as
+/// `associated`.
+pub fn parse_systemprofiler(json: &str) -> anyhow::Result<Vec<Wifi>> {
+    Ok(parse_systemprofiler_interfaces(json)?
+        .into_iter()
+        .flat_map(|interface| interface.networks)
+        .collect())
+}
+
+/// Like [`parse_systemprofiler`], keeping the networks grouped by interface
+/// along with the interface's own MAC address.
+pub fn parse_systemprofiler_interfaces(json: &str) -> anyhow::Result<Vec<InterfaceScan>> {
+    interfaces(&json::parse(&normalize(json))?)
+}
+
+/// Parses `system_profiler SPAirPortDataType -xml`, the property list form
+/// of the output [`parse_systemprofiler`] reads.
+#[cfg(feature = "plist")]
+pub fn parse_systemprofiler_plist(text: &str) -> anyhow::Result<Vec<Wifi>> {
+    Ok(parse_systemprofiler_plist_interfaces(text)?
+        .into_iter()
+        .flat_map(|interface| interface.networks)
+        .collect())
+}
+
+/// Like [`parse_systemprofiler_plist`], keeping the networks grouped by
+/// interface along with the interface's own MAC address.
+#[cfg(feature = "plist")]
+pub fn parse_systemprofiler_plist_interfaces(text: &str) -> anyhow::Result<Vec<InterfaceScan>> {
+    interfaces(&crate::plist::parse(&normalize(text))?)
+}
+
+fn interfaces(root: &Value) -> anyhow::Result<Vec<InterfaceScan>> {
+    const NETWORK_KEYS: [(&str, bool); 2] = [
+        ("spairport_airport_local_wireless_networks", true),
+        ("spairport_airport_other_local_wireless_networks", false),
+    ];
+
+    let interfaces = SCHEMAS
+        .iter()
+        .find_map(|(_, data_types)| data_types(root))
+        .with_context(|| {
+            let tried: Vec<&str> = SCHEMAS.iter().map(|(shape, _)| *shape).collect();
+            format!("SPAirPortDataType not found, tried {}", tried.join(", "))
+        })?
+        .into_iter()
+        .filter_map(|data| data.get("spairport_airport_interfaces")?.as_array())
+        .flatten();
+
+    let mut scans = Vec::new();
+    for interface in interfaces {
+        let field = |key: &str| interface.get(key).and_then(Value::as_str);
+        let mut scan = InterfaceScan {
+            interface: field("_name").unwrap_or_default().to_string(),
+            own_mac: field("spairport_wireless_mac_address").and_then(|mac| mac.parse().ok()),
+            networks: Vec::new(),
+        };
+        for (key, associated) in NETWORK_KEYS {
+            let networks = interface.get(key).and_then(Value::as_array);
+            for network in networks.unwrap_or_default() {
+                let mut wifi = parse_systemprofiler_network(network);
+                wifi.associated = associated;
+                scan.networks.push(wifi);
+            }
+        }
+        scans.push(scan);
+    }
+    Ok(scans)
+}
+
+/// The shapes `system_profiler` output has been seen in, each returning the
+/// `SPAirPortDataType` entries when the document has that shape
+type Schema = (&'static str, fn(&Value) -> Option<Vec<&Value>>);
+
+const SCHEMAS: [Schema; 3] = [
+    // `system_profiler SPAirPortDataType -json`
+    (r#"{"SPAirPortDataType": [...]}"#, |root| {
+        Some(root.get("SPAirPortDataType")?.as_array()?.iter().collect())
+    }),
+    // one object per data type, e.g. when several data types are asked for
+    (r#"[{"SPAirPortDataType": [...]}, ...]"#, |root| {
+        let data_types: Vec<&Value> = root
+            .as_array()?
+            .iter()
+            .filter_map(|object| object.get("SPAirPortDataType")?.as_array())
+            .flatten()
+            .collect();
+        (!data_types.is_empty()).then_some(data_types)
+    }),
+    // `system_profiler SPAirPortDataType -xml`
+    (
+        r#"[{"_dataType": "SPAirPortDataType", "_items": [...]}, ...]"#,
+        |root| {
+            let data_types: Vec<&Value> = root
+                .as_array()?
+                .iter()
+                .filter(|object| {
+                    object.get("_dataType").and_then(Value::as_str) == Some("SPAirPortDataType")
+                })
+                .filter_map(|object| object.get("_items")?.as_array())
+                .flatten()
+                .collect();
+            (!data_types.is_empty()).then_some(data_types)
+        },
+    ),
+];
+
+fn parse_systemprofiler_network(network: &Value) -> Wifi {
+    // some macOS builds print the channel as a bare number, e.g. 108
+    let field = |key: &str| match network.get(key) {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Number(value)) => value.to_string(),
+        _ => String::new(),
+    };
+
+    // e.g. "-62 dBm / -93 dBm"
+    let signal_noise = field("spairport_signal_noise");
+    let mut levels = signal_noise
+        .split('/')
+        .map(|level| level.trim().trim_end_matches("dBm").trim());
+    let signal_level = levels.next().unwrap_or_default().to_string();
+    let noise_dbm = levels.next().and_then(|noise| noise.parse().ok());
+
+    // a number, or a string like "864" depending on the macOS version
+    let rate: String = field("spairport_network_rate")
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+
+    let security = field("spairport_security_mode");
+    #[allow(deprecated)]
+    Wifi {
+        ssid: clean_ssid(&field("_name")),
+        channel: field("spairport_network_channel"),
+        signal_level,
+        noise_dbm,
+        security: security
+            .strip_prefix("spairport_security_mode_")
+            .unwrap_or(&security)
+            .to_string(),
+        max_rate_mbps: rate.parse().ok(),
+        tx_rate_mbps: rate.parse().ok(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MacAddr;
+
+    #[test]
+    fn should_parse_systemprofiler() {
+        let json =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_01.json").unwrap();
+        let result = parse_systemprofiler(&json).unwrap();
+
+        assert_eq!(3, result.len());
+        assert_eq!(
+            Wifi {
+                ssid: "HomeNetwork".to_string(),
+                channel: "149 (5GHz, 80MHz)".to_string(),
+                signal_level: "-48".to_string(),
+                noise_dbm: Some(-92),
+                security: "wpa2_personal".to_string(),
+                associated: true,
+                ..Default::default()
+            },
+            result[0]
+        );
+        assert_eq!("Neighbour 2.4", result[1].ssid);
+        assert!(!result[1].associated);
+        assert_eq!("open", result[2].security);
+        assert_eq!(Some(-94), result[2].noise_dbm);
+    }
+
+    #[test]
+    fn should_parse_systemprofiler_interfaces() {
+        let json =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_01.json").unwrap();
+        let result = parse_systemprofiler_interfaces(&json).unwrap();
+
+        assert_eq!(2, result.len());
+        assert_eq!("en0", result[0].interface);
+        assert_eq!(
+            Some(MacAddr([0xa4, 0x83, 0xe7, 0x12, 0x34, 0x56])),
+            result[0].own_mac
+        );
+        assert_eq!(3, result[0].networks.len());
+        assert_eq!("awdl0", result[1].interface);
+        assert!(result[1].networks.is_empty());
+    }
+
+    #[test]
+    fn should_parse_systemprofiler_schema_variants() {
+        let object =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_01.json").unwrap();
+        let array = std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_array.json")
+            .unwrap();
+
+        let result = parse_systemprofiler(&array).unwrap();
+        assert_eq!(2, result.len());
+        assert_eq!("Studio", result[0].ssid);
+        assert!(result[0].associated);
+        assert_eq!("Lobby", result[1].ssid);
+        assert_eq!(3, parse_systemprofiler(&object).unwrap().len());
+
+        let err = parse_systemprofiler(r#"{"SPUSBDataType": []}"#).unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains(r#"{"SPAirPortDataType": [...]}"#),
+            "{message}"
+        );
+        assert!(
+            message.contains(r#"[{"SPAirPortDataType": [...]}, ...]"#),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn should_parse_numeric_channel() {
+        let json = std::fs::read_to_string(
+            "tests/fixtures/system_profiler/sp_airport_numeric_channel.json",
+        )
+        .unwrap();
+        let result = parse_systemprofiler(&json).unwrap();
+
+        assert_eq!(2, result.len());
+        assert_eq!("108", result[0].channel);
+        assert_eq!(Some(5540), result[0].frequency_mhz());
+        assert_eq!("6 (2GHz, 20MHz)", result[1].channel);
+    }
+
+    #[test]
+    fn should_parse_network_rate() {
+        let json =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_rate.json").unwrap();
+        let result = parse_systemprofiler(&json).unwrap();
+
+        let rates: Vec<(&str, Option<f64>)> = result
+            .iter()
+            .map(|wifi| (wifi.ssid.as_str(), wifi.tx_rate_mbps))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Studio", Some(864.0)),
+                ("Upstairs", Some(1200.0)),
+                ("Lobby", None),
+            ],
+            rates
+        );
+        assert!(result[0].associated);
+    }
+
+    #[cfg(feature = "plist")]
+    #[test]
+    fn should_parse_systemprofiler_plist() {
+        let json =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_01.json").unwrap();
+        let plist =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_01.plist").unwrap();
+
+        assert_eq!(
+            parse_systemprofiler(&json).unwrap(),
+            parse_systemprofiler_plist(&plist).unwrap()
+        );
+        let interfaces = parse_systemprofiler_plist_interfaces(&plist).unwrap();
+        assert_eq!(
+            vec!["en0", "awdl0"],
+            interfaces
+                .iter()
+                .map(|i| i.interface.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        let err = parse_systemprofiler_plist("<plist><array/></plist>").unwrap_err();
+        assert!(err.to_string().contains("_dataType"), "{err}");
+    }
+
+    #[test]
+    fn should_parse_systemprofiler_crlf_and_bom() {
+        let lf =
+            std::fs::read_to_string("tests/fixtures/system_profiler/sp_airport_01.json").unwrap();
+        let crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        assert_eq!(
+            parse_systemprofiler(&lf).unwrap(),
+            parse_systemprofiler(&crlf).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_report_json_error_position() {
+        let err = parse_systemprofiler("{\n  \"SPAirPortDataType\": [,]\n}").unwrap_err();
+        assert!(err.to_string().contains("at line 2 column 25"), "{err}");
+    }
+}
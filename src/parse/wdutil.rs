@@ -0,0 +1,113 @@
+use super::{clean_ssid, normalize};
+use crate::Wifi;
+
+/// Parses the output of `sudo wdutil info`, which replaces `airport` on
+/// recent macOS. It only describes the network the machine is connected to,
+/// so this returns that one network, or nothing when it isn't connected.
+///
+/// Without the location permission macOS shows the SSID and BSSID as
+/// `<redacted>`, they are left empty then.
+pub fn parse_wdutil(info: &str) -> anyhow::Result<Vec<Wifi>> {
+    let info = normalize(info);
+    let mut section = "";
+    let mut wifi = Wifi {
+        associated: true,
+        ..Default::default()
+    };
+    for line in info.lines() {
+        if !line.starts_with(' ') {
+            let header = line.trim().trim_matches('—');
+            if !header.is_empty() {
+                section = header;
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if section != "WIFI" {
+            continue;
+        }
+        let value = value.trim();
+        let known = |value: &str| match value {
+            "<redacted>" | "None" => String::new(),
+            value => value.to_string(),
+        };
+        let dbm = |value: &str| value.trim_end_matches("dBm").trim().to_string();
+        match key.trim() {
+            "SSID" => wifi.ssid = clean_ssid(&known(value)),
+            "BSSID" => wifi.mac = known(value),
+            "RSSI" => wifi.signal_level = dbm(value),
+            "Noise" => wifi.noise_dbm = dbm(value).parse().ok(),
+            "Security" => wifi.security = known(value),
+            "PHY Mode" => wifi.wifi_generation = wdutil_generation(value),
+            "Channel" => wifi.channel = wdutil_channel(value).unwrap_or_default(),
+            _ => {}
+        }
+    }
+
+    // disconnected interfaces report no signal, or 0 dBm
+    let connected = wifi.signal_dbm().is_some_and(|dbm| dbm < 0);
+    Ok(if connected { vec![wifi] } else { vec![] })
+}
+
+/// Turns `5g149/80` into `149 (5GHz, 80MHz)`, the way `system_profiler`
+/// writes channels, so the band is known for channels numbered the same on
+/// several bands.
+fn wdutil_channel(channel: &str) -> Option<String> {
+    let (band, rest) = channel.split_once('g')?;
+    let (number, width) = rest.split_once('/').unwrap_or((rest, ""));
+    let number: u16 = number.parse().ok()?;
+    Some(match width {
+        "" => format!("{number} ({band}GHz)"),
+        width => format!("{number} ({band}GHz, {width}MHz)"),
+    })
+}
+
+/// Wi-Fi generation from the PHY mode, e.g. 5 for `11ac`
+fn wdutil_generation(phy_mode: &str) -> Option<u8> {
+    match phy_mode {
+        "11n" => Some(4),
+        "11ac" => Some(5),
+        "11ax" => Some(6),
+        "11be" => Some(7),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Band;
+
+    #[test]
+    fn should_parse_wdutil() {
+        let data = std::fs::read_to_string("tests/fixtures/wdutil/wdutil_info_01.txt").unwrap();
+        let expected = Wifi {
+            mac: "8c:3b:ad:a1:b2:c3".to_string(),
+            ssid: "home".to_string(),
+            channel: "149 (5GHz, 80MHz)".to_string(),
+            signal_level: "-58".to_string(),
+            security: "WPA2 Personal".to_string(),
+            wifi_generation: Some(5),
+            noise_dbm: Some(-92),
+            associated: true,
+            ..Default::default()
+        };
+        let result = parse_wdutil(&data).unwrap();
+        assert_eq!(vec![expected], result);
+        assert_eq!(Some(Band::Band5GHz), result[0].band());
+    }
+
+    #[test]
+    fn should_parse_wdutil_redacted_and_disconnected() {
+        let redacted = "WIFI\n    SSID : <redacted>\n    BSSID : <redacted>\n    RSSI : -70 dBm\n    Channel : 6g37/160\n";
+        let result = parse_wdutil(redacted).unwrap();
+        assert_eq!("", result[0].ssid);
+        assert_eq!("", result[0].mac);
+        assert_eq!(Some(Band::Band6GHz), result[0].band());
+
+        let disconnected = "WIFI\n    SSID : None\n    RSSI : 0 dBm\n";
+        assert_eq!(Vec::<Wifi>::new(), parse_wdutil(disconnected).unwrap());
+    }
+}
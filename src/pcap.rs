@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::average::average_scans;
+use crate::{parse_beacon_frames, Wifi};
+
+/// Link type of frames starting with a radiotap header
+const LINKTYPE_RADIOTAP: u32 = 127;
+/// Link type of bare 802.11 frames
+const LINKTYPE_IEEE802_11: u32 = 105;
+
+/// Reads the beacons and probe responses of a pcap capture, e.g. from
+/// `tcpdump -i wlan0mon -w beacons.pcap`, and returns each hotspot once. The
+/// signal level is averaged over its frames the way [`crate::scan_averaged`]
+/// does, the other fields come from its last frame. The capture must hold
+/// radiotap or bare 802.11 frames, pcapng files aren't supported.
+pub fn from_pcap(path: &Path) -> anyhow::Result<Vec<Wifi>> {
+    let data = std::fs::read(path).with_context(|| format!("Can't read {}", path.display()))?;
+    let frames = pcap_frames(&data)?;
+    Ok(average_scans(vec![parse_beacon_frames(&frames)]))
+}
+
+/// Splits a pcap file into its frames
+fn pcap_frames(data: &[u8]) -> anyhow::Result<Vec<&[u8]>> {
+    let magic = data.get(..4).context("Not a pcap file: too short")?;
+    let read_u32: fn([u8; 4]) -> u32 = match magic {
+        // microsecond and nanosecond timestamps
+        [0xd4, 0xc3, 0xb2, 0xa1] | [0x4d, 0x3c, 0xb2, 0xa1] => u32::from_le_bytes,
+        [0xa1, 0xb2, 0xc3, 0xd4] | [0xa1, 0xb2, 0x3c, 0x4d] => u32::from_be_bytes,
+        [0x0a, 0x0d, 0x0d, 0x0a] => anyhow::bail!("pcapng files aren't supported"),
+        _ => anyhow::bail!("Not a pcap file: unknown magic {magic:02x?}"),
+    };
+    let field = |at: usize| -> anyhow::Result<u32> {
+        let bytes = data.get(at..at + 4).context("Truncated pcap file")?;
+        Ok(read_u32(bytes.try_into()?))
+    };
+
+    let link_type = field(20)?;
+    anyhow::ensure!(
+        [LINKTYPE_RADIOTAP, LINKTYPE_IEEE802_11].contains(&link_type),
+        "pcap link type {link_type} doesn't hold 802.11 frames"
+    );
+
+    let mut frames = Vec::new();
+    let mut offset = 24;
+    while offset < data.len() {
+        // timestamp, then the captured and original lengths
+        let captured = usize::try_from(field(offset + 8)?)?;
+        let start = offset + 16;
+        let frame = data
+            .get(start..start + captured)
+            .context("Truncated pcap file")?;
+        frames.push(frame);
+        offset = start + captured;
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_read_pcap() {
+        let wifis = from_pcap(Path::new("tests/fixtures/pcap/beacons.pcap")).unwrap();
+        let summary: Vec<(&str, &str, &str, &str)> = wifis
+            .iter()
+            .map(|w| {
+                (
+                    w.mac.as_str(),
+                    w.ssid.as_str(),
+                    w.channel.as_str(),
+                    w.signal_level.as_str(),
+                )
+            })
+            .collect();
+        // -40 and -50 dBm average to -43 dBm in milliwatts
+        assert_eq!(
+            vec![
+                ("00:11:22:33:44:55", "Home", "6", "-43"),
+                ("aa:bb:cc:dd:ee:ff", "Cafe", "36", "-70"),
+            ],
+            summary
+        );
+    }
+
+    #[test]
+    fn should_reject_other_files() {
+        let err = pcap_frames(b"\x0a\x0d\x0d\x0a").unwrap_err();
+        assert_eq!("pcapng files aren't supported", err.to_string());
+        assert!(pcap_frames(b"hello").is_err());
+        assert!(from_pcap(Path::new("tests/fixtures/pcap/missing.pcap")).is_err());
+    }
+}
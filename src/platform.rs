@@ -0,0 +1,154 @@
+/// The tools the crate knows how to scan with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// macOS `system_profiler SPAirPortDataType -json`
+    MacOsSystemProfiler,
+    /// macOS `airport -s`
+    MacOsAirport,
+    /// macOS `wdutil info`, which only shows the connected network
+    MacOsWdutil,
+    /// Linux `iw dev <interface> scan`
+    LinuxIw,
+    /// Linux `iwctl station <interface> get-networks rssi-dbms`, for systems
+    /// running `iwd` without NetworkManager
+    LinuxIwctl,
+    /// Linux `iwlist scan`, deprecated and only used when neither `nmcli` nor
+    /// `iw` is installed
+    LinuxIwlist,
+    /// Linux `nmcli dev wifi list`
+    LinuxNmcli,
+    /// Windows `netsh wlan show networks mode=Bssid`
+    Windows,
+}
+
+/// Which [`crate::Wifi`] fields a backend fills in, so a UI can hide the
+/// columns that would always be empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BackendCapabilities {
+    pub mac: bool,
+    pub ssid: bool,
+    pub channel: bool,
+    /// reported by the backend rather than derived from the channel
+    pub frequency: bool,
+    /// derived from the channel, see [`crate::Wifi::band`]
+    pub band: bool,
+    pub signal_level: bool,
+    pub security: bool,
+    pub wifi_generation: bool,
+    pub noise: bool,
+    pub associated: bool,
+    pub uptime: bool,
+    pub capabilities: bool,
+    pub pmf: bool,
+    pub secondary_channel_offset: bool,
+    pub last_seen: bool,
+    pub max_tx_power: bool,
+    /// the TPC Report transmit power and link margin
+    pub tpc_report: bool,
+    /// the rates of the last frames sent to and received from the hotspot
+    pub rates: bool,
+    pub bss_color: bool,
+    pub supported_widths: bool,
+    pub current_operating_class: bool,
+    pub mesh: bool,
+}
+
+impl Platform {
+    /// Returns the fields this backend fills in.
+    pub fn capabilities(self) -> BackendCapabilities {
+        let common = BackendCapabilities {
+            mac: true,
+            ssid: true,
+            channel: true,
+            frequency: false,
+            band: true,
+            signal_level: true,
+            security: true,
+            wifi_generation: false,
+            noise: false,
+            associated: false,
+            uptime: false,
+            capabilities: false,
+            pmf: false,
+            secondary_channel_offset: false,
+            last_seen: false,
+            max_tx_power: false,
+            tpc_report: false,
+            rates: false,
+            bss_color: false,
+            supported_widths: false,
+            current_operating_class: false,
+            mesh: false,
+        };
+        match self {
+            // system_profiler doesn't tell the BSSID
+            Platform::MacOsSystemProfiler => BackendCapabilities {
+                mac: false,
+                noise: true,
+                associated: true,
+                rates: true,
+                ..common
+            },
+            Platform::MacOsWdutil => BackendCapabilities {
+                wifi_generation: true,
+                noise: true,
+                associated: true,
+                ..common
+            },
+            Platform::MacOsAirport | Platform::LinuxIwlist | Platform::LinuxNmcli => common,
+            // iwctl lists networks, not the access points behind them
+            Platform::LinuxIwctl => BackendCapabilities {
+                mac: false,
+                channel: false,
+                band: false,
+                associated: true,
+                ..common
+            },
+            Platform::Windows => BackendCapabilities {
+                wifi_generation: true,
+                ..common
+            },
+            Platform::LinuxIw => BackendCapabilities {
+                frequency: true,
+                wifi_generation: true,
+                uptime: true,
+                capabilities: true,
+                pmf: true,
+                secondary_channel_offset: true,
+                last_seen: true,
+                max_tx_power: true,
+                tpc_report: true,
+                bss_color: true,
+                supported_widths: true,
+                current_operating_class: true,
+                mesh: true,
+                ..common
+            },
+        }
+    }
+}
+
+/// Returns the fields filled in by the backend [`crate::scan`] would scan
+/// with: the one [`crate::BACKEND_ENV`] names, else the first available
+/// one, registered backends included. See [`crate::ScanBackend::capabilities`].
+pub fn backend_capabilities() -> BackendCapabilities {
+    crate::backend::selected_capabilities()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_report_capabilities() {
+        assert!(Platform::LinuxIw.capabilities().wifi_generation);
+        assert!(!Platform::LinuxNmcli.capabilities().wifi_generation);
+        assert!(Platform::MacOsAirport.capabilities().mac);
+
+        let system_profiler = Platform::MacOsSystemProfiler.capabilities();
+        assert!(system_profiler.noise);
+        assert!(!system_profiler.mac);
+        assert!(system_profiler.rates);
+        assert!(Platform::LinuxIw.capabilities().mesh);
+    }
+}
@@ -0,0 +1,228 @@
+//! Minimal XML property list reader, enough for the
+//! `system_profiler -xml` output. Values are read into the JSON [`Value`]
+//! so the same code walks both documents: dicts become objects, integers
+//! and reals numbers, dates and base64 data stay strings.
+
+use crate::json::Value;
+
+/// Parses a complete XML property list.
+pub(crate) fn parse(text: &str) -> anyhow::Result<Value> {
+    let mut reader = Reader { text, pos: 0 };
+    match reader.tag()? {
+        Tag::Open("plist") => {}
+        _ => return Err(reader.error("expected <plist>")),
+    }
+    let value = reader.value()?;
+    reader.close("plist")?;
+    Ok(value)
+}
+
+enum Tag<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    /// a self-closing tag, e.g. `<true/>`
+    Empty(&'a str),
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, message: &str) -> anyhow::Error {
+        anyhow::anyhow!("{message} at byte {} of the property list", self.pos)
+    }
+
+    /// Reads the next tag, skipping whitespace, the XML declaration, the
+    /// doctype and comments
+    fn tag(&mut self) -> anyhow::Result<Tag<'a>> {
+        loop {
+            let rest = self.text[self.pos..].trim_start();
+            self.pos = self.text.len() - rest.len();
+            let skip = if rest.starts_with("<!--") {
+                rest.find("-->").map(|end| end + 3)
+            } else if rest.starts_with("<?") || rest.starts_with("<!") {
+                rest.find('>').map(|end| end + 1)
+            } else {
+                break;
+            };
+            self.pos += skip.ok_or_else(|| self.error("unterminated declaration"))?;
+        }
+
+        let rest = &self.text[self.pos..];
+        if !rest.starts_with('<') {
+            return Err(self.error("expected a tag"));
+        }
+        let end = rest
+            .find('>')
+            .ok_or_else(|| self.error("unterminated tag"))?;
+        let inner = &rest[1..end];
+        self.pos += end + 1;
+        // attributes, e.g. the plist version, don't matter here
+        let name = |s: &'a str| s.split_whitespace().next().unwrap_or_default();
+        Ok(if let Some(inner) = inner.strip_prefix('/') {
+            Tag::Close(name(inner))
+        } else if let Some(inner) = inner.strip_suffix('/') {
+            Tag::Empty(name(inner))
+        } else {
+            Tag::Open(name(inner))
+        })
+    }
+
+    fn close(&mut self, expected: &str) -> anyhow::Result<()> {
+        match self.tag()? {
+            Tag::Close(name) if name == expected => Ok(()),
+            _ => Err(self.error(&format!("expected </{expected}>"))),
+        }
+    }
+
+    /// Reads the text up to the closing `name` tag
+    fn text(&mut self, name: &str) -> anyhow::Result<String> {
+        let rest = &self.text[self.pos..];
+        let end = rest
+            .find('<')
+            .ok_or_else(|| self.error("unterminated text"))?;
+        let text = unescape(&rest[..end]);
+        self.pos += end;
+        self.close(name)?;
+        Ok(text)
+    }
+
+    fn value(&mut self) -> anyhow::Result<Value> {
+        match self.tag()? {
+            Tag::Empty("true") => Ok(Value::Bool(true)),
+            Tag::Empty("false") => Ok(Value::Bool(false)),
+            Tag::Empty("array") => Ok(Value::Array(Vec::new())),
+            Tag::Empty("dict") => Ok(Value::Object(Vec::new())),
+            Tag::Empty("string" | "date" | "data") => Ok(Value::String(String::new())),
+            Tag::Open(name @ ("string" | "date")) => Ok(Value::String(self.text(name)?)),
+            Tag::Open("data") => Ok(Value::String(self.text("data")?.trim().to_string())),
+            Tag::Open(name @ ("integer" | "real")) => {
+                let number = self.text(name)?;
+                let number = number
+                    .trim()
+                    .parse()
+                    .map_err(|_| self.error(&format!("invalid {name} {number:?}")))?;
+                Ok(Value::Number(number))
+            }
+            Tag::Open("array") => {
+                let mut items = Vec::new();
+                while !self.at_close("array")? {
+                    items.push(self.value()?);
+                }
+                Ok(Value::Array(items))
+            }
+            Tag::Open("dict") => {
+                let mut entries = Vec::new();
+                while !self.at_close("dict")? {
+                    match self.tag()? {
+                        Tag::Open("key") => {}
+                        _ => return Err(self.error("expected <key>")),
+                    }
+                    let key = self.text("key")?;
+                    entries.push((key, self.value()?));
+                }
+                Ok(Value::Object(entries))
+            }
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Consumes the closing `name` tag if it comes next
+    fn at_close(&mut self, name: &str) -> anyhow::Result<bool> {
+        let start = self.pos;
+        if matches!(self.tag()?, Tag::Close(close) if close == name) {
+            return Ok(true);
+        }
+        self.pos = start;
+        Ok(false)
+    }
+}
+
+/// Replaces the XML entities, e.g. `&amp;` and `&#233;`
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let decoded = entity.and_then(|(name, end)| {
+            let c = match name {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => {
+                    let code = match name.strip_prefix("#x") {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => name.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_plist() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<!-- a comment -->
+<dict>
+	<key>name</key>
+	<string>Caf&#233; &amp; Bar</string>
+	<key>channel</key>
+	<integer>108</integer>
+	<key>flags</key>
+	<array><true/><false/><string/></array>
+	<key>empty</key>
+	<dict/>
+</dict>
+</plist>"#;
+        assert_eq!(
+            Value::Object(vec![
+                ("name".to_string(), Value::String("Café & Bar".to_string())),
+                ("channel".to_string(), Value::Number(108.0)),
+                (
+                    "flags".to_string(),
+                    Value::Array(vec![
+                        Value::Bool(true),
+                        Value::Bool(false),
+                        Value::String(String::new()),
+                    ])
+                ),
+                ("empty".to_string(), Value::Object(vec![])),
+            ]),
+            parse(text).unwrap()
+        );
+
+        let err = parse("<plist><dict><string>x</string></dict></plist>").unwrap_err();
+        assert_eq!(
+            "expected <key> at byte 21 of the property list",
+            err.to_string()
+        );
+        assert!(parse("{}").is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! The commonly used types and functions, for glob importing.
+//!
+//! ```
+//! use wifiscanner::prelude::*;
+//!
+//! let wifis: Vec<Wifi> = scan().unwrap_or_default();
+//! let open = wifis.iter().filter(|wifi| wifi.security_kind() == Some(SecurityKind::Open));
+//! println!("{} open networks", open.count());
+//! ```
+
+pub use crate::{
+    current_connection, scan, scan_from_output, scan_iter, scan_with_interfaces, scan_with_options,
+    sort_by_signal, sort_by_ssid, try_scan, Band, InterfaceScan, MacAddr, Platform, ScanOptions,
+    SecurityKind, SignalQuality, Wifi, WifiError,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::exit_status;
+
+    #[test]
+    fn should_scan_with_prelude() {
+        let stdout = std::fs::read("tests/fixtures/nmcli/nmcli01.txt").unwrap();
+        let mut wifis = scan_from_output(Platform::LinuxNmcli, &stdout, exit_status(0)).unwrap();
+        sort_by_signal(&mut wifis);
+
+        let kinds: Vec<(&str, Option<SecurityKind>)> = wifis
+            .iter()
+            .map(|wifi| (wifi.ssid.as_str(), wifi.security_kind()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("hello", Some(SecurityKind::Wpa2)),
+                ("Cafe Guest", Some(SecurityKind::Open)),
+                ("Office", Some(SecurityKind::Wpa3)),
+            ],
+            kinds
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{MacAddr, Wifi};
+
+/// Remembers when each BSSID was last seen across repeated scans, e.g. to
+/// tell whether a device is around
+#[derive(Debug, Default, Clone)]
+pub struct PresenceTracker {
+    last_seen: HashMap<MacAddr, Instant>,
+}
+
+impl PresenceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the hotspots of a scan as seen now, or `last_seen_ms` ago when
+    /// the backend reports how old an entry is. Hotspots without a valid mac
+    /// address are ignored.
+    pub fn observe(&mut self, wifis: &[Wifi]) {
+        self.observe_at(wifis, Instant::now());
+    }
+
+    /// Whether `mac` was seen within the last `within`
+    pub fn is_present(&self, mac: &MacAddr, within: Duration) -> bool {
+        self.is_present_at(mac, within, Instant::now())
+    }
+
+    /// When `mac` was last seen, `None` if it never was
+    pub fn last_seen(&self, mac: &MacAddr) -> Option<Instant> {
+        self.last_seen.get(mac).copied()
+    }
+
+    fn observe_at(&mut self, wifis: &[Wifi], now: Instant) {
+        for wifi in wifis {
+            let Some(mac) = wifi.mac_addr() else {
+                continue;
+            };
+            let age = Duration::from_millis(wifi.last_seen_ms.unwrap_or(0).into());
+            let seen = now.checked_sub(age).unwrap_or(now);
+            let last_seen = self.last_seen.entry(mac).or_insert(seen);
+            *last_seen = (*last_seen).max(seen);
+        }
+    }
+
+    fn is_present_at(&self, mac: &MacAddr, within: Duration, now: Instant) -> bool {
+        self.last_seen(mac)
+            .is_some_and(|seen| now.saturating_duration_since(seen) <= within)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(mac: &str, last_seen_ms: Option<u32>) -> Wifi {
+        Wifi {
+            mac: mac.to_string(),
+            last_seen_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_decay_presence() {
+        let phone: MacAddr = "00:00:00:00:00:01".parse().unwrap();
+        let router: MacAddr = "00:00:00:00:00:02".parse().unwrap();
+        let window = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut tracker = PresenceTracker::new();
+
+        tracker.observe_at(
+            &[
+                wifi("00:00:00:00:00:01", None),
+                wifi("00:00:00:00:00:02", None),
+            ],
+            start,
+        );
+        // the second scan only hears the router, from a 10 s old cache entry
+        let later = start + Duration::from_secs(50);
+        tracker.observe_at(&[wifi("00:00:00:00:00:02", Some(10_000))], later);
+
+        assert!(tracker.is_present_at(&phone, window, later));
+        assert!(tracker.is_present_at(&router, window, later));
+
+        let after = start + Duration::from_secs(90);
+        assert!(!tracker.is_present_at(&phone, window, after));
+        assert!(tracker.is_present_at(&router, window, after));
+        assert_eq!(
+            Some(start + Duration::from_secs(40)),
+            tracker.last_seen(&router)
+        );
+
+        let unknown: MacAddr = "00:00:00:00:00:03".parse().unwrap();
+        assert!(!tracker.is_present_at(&unknown, window, after));
+    }
+}
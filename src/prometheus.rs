@@ -0,0 +1,73 @@
+use std::fmt::Write;
+
+use crate::Wifi;
+
+/// Renders the networks in the Prometheus text exposition format, e.g. for
+/// the node_exporter textfile collector.
+///
+/// Metrics:
+/// - `wifi_networks`: number of networks found
+/// - `wifi_signal_dbm{ssid, bssid, channel}`: signal level of each network,
+///   networks without a parseable signal are left out
+pub fn to_prometheus(wifis: &[Wifi]) -> String {
+    fn escape(value: &str) -> String {
+        value
+            .replace('\\', r"\\")
+            .replace('"', r#"\""#)
+            .replace('\n', r"\n")
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP wifi_networks Number of WiFi networks found.");
+    let _ = writeln!(out, "# TYPE wifi_networks gauge");
+    let _ = writeln!(out, "wifi_networks {}", wifis.len());
+    let _ = writeln!(
+        out,
+        "# HELP wifi_signal_dbm Signal level of a WiFi network in dBm."
+    );
+    let _ = writeln!(out, "# TYPE wifi_signal_dbm gauge");
+    for wifi in wifis {
+        if let Some(dbm) = wifi.signal_dbm() {
+            let _ = writeln!(
+                out,
+                r#"wifi_signal_dbm{{ssid="{}",bssid="{}",channel="{}"}} {}"#,
+                escape(&wifi.ssid),
+                escape(&wifi.mac),
+                escape(&wifi.channel),
+                dbm
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_export_prometheus() {
+        let wifis = vec![
+            Wifi {
+                mac: "11:22:33:44:55:66".to_string(),
+                ssid: r#"my "home" \ net"#.to_string(),
+                channel: "6".to_string(),
+                signal_level: "-67.00".to_string(),
+                ..Default::default()
+            },
+            Wifi {
+                ssid: "no signal".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let expected = r#"# HELP wifi_networks Number of WiFi networks found.
+# TYPE wifi_networks gauge
+wifi_networks 2
+# HELP wifi_signal_dbm Signal level of a WiFi network in dBm.
+# TYPE wifi_signal_dbm gauge
+wifi_signal_dbm{ssid="my \"home\" \\ net",bssid="11:22:33:44:55:66",channel="6"} -67
+"#;
+        assert_eq!(expected, to_prometheus(&wifis));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{SecurityKind, Wifi};
+
+impl Wifi {
+    /// Returns the `WIFI:T:WPA;S:<ssid>;P:;H:false;;` string Wi-Fi QR codes
+    /// encode. A scan can't know the password so it is left blank. Open
+    /// networks are `nopass`, WEP is `WEP` and everything else, including an
+    /// unrecognised security, is `WPA`.
+    pub fn to_qr_wifi_string(&self) -> String {
+        let kind = match self.security_kind() {
+            Some(SecurityKind::Open) => "nopass",
+            Some(SecurityKind::Wep) => "WEP",
+            _ => "WPA",
+        };
+        format!(
+            "WIFI:T:{};S:{};P:;H:{};;",
+            kind,
+            escape_qr(&self.ssid),
+            self.is_hidden()
+        )
+    }
+}
+
+/// Backslash-escapes the characters that delimit fields in a Wi-Fi QR string
+fn escape_qr(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(ssid: &str, security: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            security: security.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_build_qr_wifi_string() {
+        assert_eq!(
+            "WIFI:T:WPA;S:home;P:;H:false;;",
+            wifi("home", "WPA2 WPA3").to_qr_wifi_string()
+        );
+        assert_eq!(
+            "WIFI:T:WEP;S:old;P:;H:false;;",
+            wifi("old", "WEP").to_qr_wifi_string()
+        );
+        assert_eq!(
+            "WIFI:T:WPA;S:;P:;H:true;;",
+            wifi("", "PSK").to_qr_wifi_string()
+        );
+    }
+
+    #[test]
+    fn should_use_nopass_for_open_networks() {
+        assert_eq!(
+            "WIFI:T:nopass;S:Free Wifi;P:;H:false;;",
+            wifi("Free Wifi", "").to_qr_wifi_string()
+        );
+    }
+
+    #[test]
+    fn should_escape_qr_special_characters() {
+        assert_eq!(
+            r#"WIFI:T:WPA;S:a\;b\,c\:d\\e\"f;P:;H:false;;"#,
+            wifi(r#"a;b,c:d\e"f"#, "WPA2").to_qr_wifi_string()
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::{ScanMeta, Wifi, WifiError};
+
+/// What a scan started too soon after the last one does, see
+/// [`set_min_scan_interval`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitMode {
+    /// waits for the interval to be over, then scans
+    #[default]
+    Wait,
+    /// returns the result of the last scan right away, or waits when the last
+    /// scan failed
+    ReuseLast,
+}
+
+/// The hotspots of a scan, how many entries were skipped and how it went
+type Scanned = (Vec<Wifi>, usize, ScanMeta);
+
+struct Limiter {
+    min_interval: Duration,
+    mode: RateLimitMode,
+    /// when the last scan finished, and its result if it succeeded
+    last: Option<(Instant, Option<Scanned>)>,
+}
+
+/// The limit with what waits on it. `limiter` is only held to read or
+/// update it, never while sleeping or scanning, so changing the limit
+/// doesn't wait for a scan.
+struct RateLimit {
+    limiter: Mutex<Limiter>,
+    /// notified when the limit changes, waking the scans waiting for it
+    changed: Condvar,
+    /// held while a limited scan waits and runs, so they take turns
+    scanning: Mutex<()>,
+}
+
+static LIMIT: RateLimit = RateLimit {
+    limiter: Mutex::new(Limiter {
+        min_interval: Duration::ZERO,
+        mode: RateLimitMode::Wait,
+        last: None,
+    }),
+    changed: Condvar::new(),
+    scanning: Mutex::new(()),
+};
+
+/// Sets the shortest time between the end of one scan and the start of the
+/// next for the whole process, as some drivers misbehave or drop the
+/// connection when scanned too often. A scan asked for sooner waits or
+/// reuses the last result, see [`set_rate_limit_mode`]. Applies to
+/// [`crate::scan`] and everything built on it, the scans of one interface,
+/// channel or SSID aren't limited. Zero, the default, turns the limit off.
+///
+/// While the limit is on, scans from several threads run one at a time.
+/// Scans already waiting go by the new interval right away.
+pub fn set_min_scan_interval(interval: Duration) {
+    update(&LIMIT, |limiter| limiter.min_interval = interval);
+}
+
+/// Sets what a scan asked for within [`set_min_scan_interval`] of the last
+/// one does, [`RateLimitMode::Wait`] by default. A reused result comes with
+/// the [`ScanMeta`] of the scan that made it.
+pub fn set_rate_limit_mode(mode: RateLimitMode) {
+    update(&LIMIT, |limiter| limiter.mode = mode);
+}
+
+/// Changes the limit and wakes the scans waiting for it to check again
+fn update(limit: &RateLimit, change: impl FnOnce(&mut Limiter)) {
+    change(&mut lock(&limit.limiter));
+    limit.changed.notify_all();
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Waits on `limit` for `timeout` at most, or until the limit changes
+fn wait_for_change<'a>(
+    limit: &RateLimit,
+    limiter: MutexGuard<'a, Limiter>,
+    timeout: Duration,
+) -> MutexGuard<'a, Limiter> {
+    limit
+        .changed
+        .wait_timeout(limiter, timeout)
+        .map_or_else(|e| e.into_inner().0, |(limiter, _)| limiter)
+}
+
+/// Runs `scan` within the limit set with [`set_min_scan_interval`]
+pub(crate) fn rate_limited(
+    scan: impl FnOnce() -> Result<Scanned, WifiError>,
+) -> Result<Scanned, WifiError> {
+    rate_limited_with(
+        &LIMIT,
+        Instant::now,
+        |limiter, timeout| wait_for_change(&LIMIT, limiter, timeout),
+        scan,
+    )
+}
+
+/// Like [`rate_limited`], waiting with `wait`, which gets the locked limiter
+/// and how long to wait at most and returns it locked again
+fn rate_limited_with<'a>(
+    limit: &'a RateLimit,
+    now: impl Fn() -> Instant,
+    mut wait: impl FnMut(MutexGuard<'a, Limiter>, Duration) -> MutexGuard<'a, Limiter>,
+    scan: impl FnOnce() -> Result<Scanned, WifiError>,
+) -> Result<Scanned, WifiError> {
+    let mut scanning = None;
+    let mut limiter = lock(&limit.limiter);
+    loop {
+        if limiter.min_interval.is_zero() {
+            limiter.last = None;
+            // scans without a limit don't wait for each other
+            drop(limiter);
+            drop(scanning);
+            return scan();
+        }
+        if scanning.is_none() {
+            // wait for the turn without holding the limiter
+            drop(limiter);
+            scanning = Some(lock(&limit.scanning));
+            limiter = lock(&limit.limiter);
+            continue;
+        }
+        let Some((finished, last)) = &limiter.last else {
+            break;
+        };
+        let elapsed = now().saturating_duration_since(*finished);
+        if elapsed >= limiter.min_interval {
+            break;
+        }
+        match (limiter.mode, last) {
+            (RateLimitMode::ReuseLast, Some(last)) => return Ok(last.clone()),
+            _ => {
+                let timeout = limiter.min_interval - elapsed;
+                limiter = wait(limiter, timeout);
+            }
+        }
+    }
+    drop(limiter);
+    let result = scan();
+    lock(&limit.limiter).last = Some((now(), result.as_ref().ok().cloned()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::sync::mpsc;
+    use std::thread;
+
+    fn limiter(min_interval: Duration, mode: RateLimitMode) -> RateLimit {
+        RateLimit {
+            limiter: Mutex::new(Limiter {
+                min_interval,
+                mode,
+                last: None,
+            }),
+            changed: Condvar::new(),
+            scanning: Mutex::new(()),
+        }
+    }
+
+    #[test]
+    fn should_wait_for_min_interval() {
+        let limiter = limiter(Duration::from_secs(10), RateLimitMode::Wait);
+        let start = Instant::now();
+        let elapsed = Cell::new(Duration::ZERO);
+        let now = || start + elapsed.get();
+        let slept = Cell::new(Duration::ZERO);
+        let sleep = |limiter, duration| {
+            slept.set(slept.get() + duration);
+            elapsed.set(elapsed.get() + duration);
+            limiter
+        };
+        let runs = Cell::new(0);
+        let scan = || {
+            runs.set(runs.get() + 1);
+            Ok((vec![Wifi::default(); runs.get()], 0, ScanMeta::default()))
+        };
+
+        rate_limited_with(&limiter, now, sleep, scan).unwrap();
+        assert_eq!(Duration::ZERO, slept.get());
+
+        elapsed.set(Duration::from_secs(3));
+        let (wifis, _, _) = rate_limited_with(&limiter, now, sleep, scan).unwrap();
+        assert_eq!(2, wifis.len());
+        assert_eq!(Duration::from_secs(7), slept.get());
+
+        // the interval counts from the end of the last scan
+        elapsed.set(Duration::from_secs(21));
+        rate_limited_with(&limiter, now, sleep, scan).unwrap();
+        assert_eq!(Duration::from_secs(7), slept.get());
+        assert_eq!(3, runs.get());
+    }
+
+    #[test]
+    fn should_reuse_last_scan_within_min_interval() {
+        let limiter = limiter(Duration::from_secs(10), RateLimitMode::ReuseLast);
+        let start = Instant::now();
+        let elapsed = Cell::new(Duration::ZERO);
+        let now = || start + elapsed.get();
+        let no_sleep = |_, _| panic!("shouldn't wait");
+        let runs = Cell::new(0);
+        let scan = || {
+            runs.set(runs.get() + 1);
+            Ok((vec![Wifi::default(); runs.get()], 0, ScanMeta::default()))
+        };
+
+        rate_limited_with(&limiter, now, no_sleep, scan).unwrap();
+        elapsed.set(Duration::from_secs(5));
+        let (wifis, _, _) = rate_limited_with(&limiter, now, no_sleep, scan).unwrap();
+        assert_eq!(1, wifis.len());
+        assert_eq!(1, runs.get());
+
+        // nothing to reuse after a failed scan, so the next one waits
+        elapsed.set(Duration::from_secs(20));
+        let failed = rate_limited_with(&limiter, now, no_sleep, || Err(WifiError::CommandNotFound));
+        assert!(failed.is_err());
+        let slept = Cell::new(Duration::ZERO);
+        let sleep = |limiter, duration| {
+            slept.set(duration);
+            elapsed.set(elapsed.get() + duration);
+            limiter
+        };
+        rate_limited_with(&limiter, now, sleep, scan).unwrap();
+        assert_eq!(Duration::from_secs(10), slept.get());
+
+        // no limit, no waiting
+        update(&limiter, |limiter| limiter.min_interval = Duration::ZERO);
+        rate_limited_with(&limiter, now, no_sleep, scan).unwrap();
+        assert_eq!(3, runs.get());
+    }
+
+    #[test]
+    fn should_stop_waiting_when_limit_is_turned_off() {
+        // leaked so a scan left waiting can't hold up the test
+        let limit: &'static RateLimit = Box::leak(Box::new(limiter(
+            Duration::from_secs(3600),
+            RateLimitMode::Wait,
+        )));
+        let scan = || Ok((vec![Wifi::default()], 0, ScanMeta::default()));
+        let wait = |limiter, timeout| wait_for_change(limit, limiter, timeout);
+        rate_limited_with(limit, Instant::now, wait, scan).unwrap();
+
+        let (done, scanned) = mpsc::channel();
+        thread::spawn(move || {
+            done.send(rate_limited_with(limit, Instant::now, wait, scan).is_ok())
+        });
+        while limit.scanning.try_lock().is_ok() {
+            thread::yield_now();
+        }
+        update(limit, |limiter| limiter.min_interval = Duration::ZERO);
+        assert_eq!(Ok(true), scanned.recv_timeout(Duration::from_secs(5)));
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{MacAddr, Wifi};
+
+/// Keeps the last signal levels of each BSSID across repeated scans, e.g. to
+/// draw a signal trend
+#[derive(Debug, Clone)]
+pub struct RssiTracker {
+    capacity: usize,
+    samples: HashMap<MacAddr, VecDeque<i32>>,
+}
+
+impl RssiTracker {
+    /// Keeps up to `capacity` samples per BSSID, at least one
+    pub fn new(capacity: usize) -> Self {
+        RssiTracker {
+            capacity: capacity.max(1),
+            samples: HashMap::new(),
+        }
+    }
+
+    /// Records the signal level of each hotspot of a scan, dropping its
+    /// oldest sample once it has `capacity` of them. Hotspots without a valid
+    /// mac address or signal level are ignored.
+    pub fn push(&mut self, wifis: &[Wifi]) {
+        for wifi in wifis {
+            let (Some(mac), Some(dbm)) = (wifi.mac_addr(), wifi.signal_dbm()) else {
+                continue;
+            };
+            let samples = self.samples.entry(mac).or_default();
+            if samples.len() == self.capacity {
+                samples.pop_front();
+            }
+            samples.push_back(dbm);
+            // so history can hand out a single slice
+            samples.make_contiguous();
+        }
+    }
+
+    /// Signal levels of `mac` in dBm, oldest first. Empty if it was never
+    /// seen.
+    pub fn history(&self, mac: &MacAddr) -> &[i32] {
+        self.samples
+            .get(mac)
+            .map_or(&[], |samples| samples.as_slices().0)
+    }
+
+    /// Most samples kept per BSSID
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(mac: &str, signal_level: &str) -> Wifi {
+        Wifi {
+            mac: mac.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_keep_rssi_history() {
+        let router: MacAddr = "00:00:00:00:00:01".parse().unwrap();
+        let phone: MacAddr = "00:00:00:00:00:02".parse().unwrap();
+        let mut tracker = RssiTracker::new(3);
+
+        tracker.push(&[
+            wifi("00:00:00:00:00:01", "-50.00"),
+            wifi("00:00:00:00:00:02", "-70"),
+        ]);
+        tracker.push(&[wifi("00:00:00:00:00:01", "-52"), wifi("nope", "-40")]);
+        tracker.push(&[
+            wifi("00:00:00:00:00:01", "-55"),
+            wifi("00:00:00:00:00:02", ""),
+        ]);
+        assert_eq!(&[-50, -52, -55], tracker.history(&router));
+        assert_eq!(&[-70], tracker.history(&phone));
+
+        // the oldest samples make room for new ones
+        for signal in ["-60", "-61", "-62", "-63"] {
+            tracker.push(&[wifi("00:00:00:00:00:01", signal)]);
+            assert_eq!(3, tracker.history(&router).len());
+        }
+        assert_eq!(&[-61, -62, -63], tracker.history(&router));
+
+        let unknown: MacAddr = "00:00:00:00:00:03".parse().unwrap();
+        assert!(tracker.history(&unknown).is_empty());
+        assert_eq!(1, RssiTracker::new(0).capacity());
+    }
+}
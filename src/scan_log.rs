@@ -0,0 +1,132 @@
+use std::time::SystemTime;
+
+use crate::backend::DefaultBackend;
+use crate::{ScanBackend, Wifi};
+
+/// Append-only record of scans and when they were made, e.g. for an audit
+/// trail of the hotspots seen at a site
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ScanLog {
+    entries: Vec<(SystemTime, Vec<Wifi>)>,
+}
+
+impl ScanLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scans with [`crate::scan`] and appends the result stamped with the
+    /// current time. A failed scan appends nothing.
+    pub fn record(&mut self) -> anyhow::Result<()> {
+        self.record_with(&DefaultBackend)
+    }
+
+    fn record_with(&mut self, backend: &dyn ScanBackend) -> anyhow::Result<()> {
+        let wifis = backend.scan()?;
+        self.entries.push((SystemTime::now(), wifis));
+        Ok(())
+    }
+
+    /// The recorded scans, oldest first
+    pub fn entries(&self) -> &[(SystemTime, Vec<Wifi>)] {
+        &self.entries
+    }
+
+    /// Returns one JSON object per scan and line (JSON Lines), oldest first,
+    /// e.g. `{"time_ms":1700000000000,"networks":[...]}`. `time_ms` is the
+    /// time of the scan in milliseconds since the Unix epoch, the networks
+    /// are written like [`crate::to_ndjson`] writes them.
+    #[cfg(feature = "serde")]
+    pub fn to_jsonl(&self) -> String {
+        use crate::json::{object, Value};
+        use crate::ndjson::wifi_object;
+        use std::time::UNIX_EPOCH;
+
+        self.entries
+            .iter()
+            .map(|(time, wifis)| {
+                let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+                let entry = object(vec![
+                    ("time_ms", Value::Number(since_epoch.as_millis() as f64)),
+                    (
+                        "networks",
+                        Value::Array(wifis.iter().map(wifi_object).collect()),
+                    ),
+                ]);
+                format!("{entry}\n")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReplayBackend;
+
+    fn wifi(ssid: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_record_scans() {
+        let replay = ReplayBackend::new(vec![vec![wifi("home"), wifi("cafe")], vec![wifi("home")]]);
+        let mut log = ScanLog::new();
+        log.record_with(&replay).unwrap();
+        log.record_with(&replay).unwrap();
+
+        let scans: Vec<usize> = log.entries().iter().map(|(_, wifis)| wifis.len()).collect();
+        assert_eq!(vec![2, 1], scans);
+        assert!(log.entries()[0].0 <= log.entries()[1].0);
+    }
+
+    #[test]
+    fn should_record_nothing_for_failed_scan() {
+        let scan = vec![wifi("home"), wifi("cafe")];
+        let replay = ReplayBackend::new(vec![scan.clone()]).fail_at(0);
+        let mut log = ScanLog::new();
+
+        assert!(log.record_with(&replay).is_err());
+        assert!(log.entries().is_empty());
+
+        // the scan after it is stamped with the time it was recorded
+        let before = SystemTime::now();
+        log.record_with(&replay).unwrap();
+        let after = SystemTime::now();
+        assert_eq!(1, log.entries().len());
+        let (time, wifis) = &log.entries()[0];
+        assert_eq!(&scan, wifis);
+        assert!(before <= *time && *time <= after);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_write_log_as_json_lines() {
+        use crate::json::{self, Value};
+        use std::time::UNIX_EPOCH;
+
+        let replay = ReplayBackend::new(vec![vec![wifi("home"), wifi("cafe")], vec![wifi("home")]]);
+        let mut log = ScanLog::new();
+        log.record_with(&replay).unwrap();
+        log.record_with(&replay).unwrap();
+
+        let jsonl = log.to_jsonl();
+        let lines: Vec<Value> = jsonl
+            .lines()
+            .map(|line| json::parse(line).unwrap())
+            .collect();
+        assert_eq!(2, lines.len());
+        for (line, (time, wifis)) in lines.iter().zip(log.entries()) {
+            let millis = time.duration_since(UNIX_EPOCH).unwrap().as_millis() as f64;
+            assert_eq!(Some(&Value::Number(millis)), line.get("time_ms"));
+            let networks = line.get("networks").and_then(Value::as_array).unwrap();
+            assert_eq!(wifis.len(), networks.len());
+        }
+        let ssid = lines[0].get("networks").and_then(Value::as_array).unwrap()[1].get("ssid");
+        assert_eq!(Some("cafe"), ssid.and_then(Value::as_str));
+        assert_eq!("", ScanLog::new().to_jsonl());
+    }
+}
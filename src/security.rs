@@ -0,0 +1,360 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Wifi, WifiError};
+
+/// SSID words [`Wifi::likely_captive_portal`] looks for, matched ignoring
+/// case anywhere in the SSID
+pub const CAPTIVE_PORTAL_KEYWORDS: &[&str] = &[
+    "free", "guest", "wifi", "wi-fi", "hotspot", "public", "airport", "hotel",
+];
+
+/// Security scheme of a hotspot, ordered from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum SecurityKind {
+    Open,
+    Wep,
+    Wpa,
+    Wpa2,
+    Wpa3,
+    /// WPA3-Enterprise 192-bit mode (CNSA Suite B), with GCMP-256 and
+    /// BIP-GMAC-256
+    Wpa3Enterprise192,
+}
+
+/// Whether a hotspot protects its management frames (802.11w), from the
+/// MFP flags of its RSN capabilities. WPA3 requires it, so a hotspot
+/// reporting WPA3 without [`PmfMode::Required`] is either in a transition
+/// mode or misclassified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PmfMode {
+    /// management frames are never protected
+    Disabled,
+    /// protected for clients that support it
+    Capable,
+    /// clients without PMF can't connect
+    Required,
+}
+
+impl SecurityKind {
+    /// Reads the security string of any backend, e.g. `WPA2 WPA3` (nmcli),
+    /// `WPA2(PSK/AES/AES)` (airport), `SAE` (iw) or `wpa2_personal`
+    /// (system_profiler). Mixed modes rank at the stronger scheme, so
+    /// `WPA2/WPA3` is [`SecurityKind::Wpa3`]. The Suite B suites, e.g. iw's
+    /// `IEEE 802.1X/SUITE-B-192`, are [`SecurityKind::Wpa3Enterprise192`].
+    /// Returns `None` for anything it doesn't recognise.
+    pub fn parse(security: &str) -> Option<SecurityKind> {
+        let security = security.trim().to_uppercase();
+        if ["SUITE-B", "SUITE_B", "SUITEB"]
+            .iter()
+            .any(|marker| security.contains(marker))
+            || (security.contains("WPA3") && security.contains("192"))
+        {
+            Some(SecurityKind::Wpa3Enterprise192)
+        } else if security.contains("WPA3") || security.contains("SAE") {
+            Some(SecurityKind::Wpa3)
+        } else if security.contains("WPA2") {
+            Some(SecurityKind::Wpa2)
+        } else if security.contains("WPA") {
+            Some(SecurityKind::Wpa)
+        } else if security.contains("PSK") || security.contains("802.1X") {
+            // iw only lists the authentication suites of the RSN element
+            Some(SecurityKind::Wpa2)
+        } else if security.contains("WEP") {
+            Some(SecurityKind::Wep)
+        } else if matches!(security.as_str(), "" | "--" | "NONE" | "OPEN") {
+            Some(SecurityKind::Open)
+        } else {
+            None
+        }
+    }
+
+    /// Stable numeric rank of the scheme for filters that only compare
+    /// numbers: `Open` 0, `Wep` 1, `Wpa` 2, `Wpa2` 3, `Wpa3` 5 and
+    /// `Wpa3Enterprise192` 6. 4 is left
+    /// for WPA2 Enterprise, see [`Wifi::security_score`]. The ranks won't
+    /// change between releases, so they can be stored.
+    pub fn strength_score(&self) -> u8 {
+        match self {
+            SecurityKind::Open => 0,
+            SecurityKind::Wep => 1,
+            SecurityKind::Wpa => 2,
+            SecurityKind::Wpa2 => 3,
+            SecurityKind::Wpa3 => 5,
+            SecurityKind::Wpa3Enterprise192 => 6,
+        }
+    }
+}
+
+impl fmt::Display for SecurityKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            SecurityKind::Open => "Open",
+            SecurityKind::Wep => "WEP",
+            SecurityKind::Wpa => "WPA",
+            SecurityKind::Wpa2 => "WPA2",
+            SecurityKind::Wpa3 => "WPA3",
+            SecurityKind::Wpa3Enterprise192 => "WPA3-Enterprise-192",
+        })
+    }
+}
+
+impl FromStr for SecurityKind {
+    type Err = WifiError;
+
+    /// Parses a scheme name as a user would type it, in any case: `open` (or
+    /// `none`), `wep`, `wpa` (or `wpa1`), `wpa2`, `wpa3` (or `sae`) or
+    /// `wpa3-enterprise-192` (or `suite-b`). Use
+    /// [`SecurityKind::parse`] for the security strings of the backends.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "open" | "none" => Ok(SecurityKind::Open),
+            "wep" => Ok(SecurityKind::Wep),
+            "wpa" | "wpa1" => Ok(SecurityKind::Wpa),
+            "wpa2" => Ok(SecurityKind::Wpa2),
+            "wpa3" | "sae" => Ok(SecurityKind::Wpa3),
+            "wpa3-enterprise-192" | "suite-b" => Ok(SecurityKind::Wpa3Enterprise192),
+            _ => Err(WifiError::InvalidValue {
+                expected: "security (open, wep, wpa, wpa2, wpa3 or wpa3-enterprise-192)",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl Wifi {
+    /// Returns the security scheme, see [`SecurityKind::parse`]. An 802.1X
+    /// hotspot with [`Wifi::suite_b_ciphers`] is
+    /// [`SecurityKind::Wpa3Enterprise192`] whatever suite it lists, e.g.
+    /// `FT/IEEE 802.1X/SHA-384`.
+    pub fn security_kind(&self) -> Option<SecurityKind> {
+        if self.suite_b_ciphers && self.is_enterprise() {
+            return Some(SecurityKind::Wpa3Enterprise192);
+        }
+        SecurityKind::parse(&self.security)
+    }
+
+    /// Whether the hotspot is at least as secure as `min`. An unrecognised
+    /// security string never is.
+    pub fn is_at_least(&self, min: SecurityKind) -> bool {
+        self.security_kind().is_some_and(|kind| kind >= min)
+    }
+
+    /// [`SecurityKind::strength_score`] of the hotspot, with WPA2 Enterprise
+    /// at 4, between WPA2 and WPA3 Personal. WPA Enterprise keeps the score
+    /// of WPA, its TKIP cipher is the weak part. An unrecognised security
+    /// string scores 0 like an open hotspot, so "at least WPA2" is
+    /// `security_score() >= 3`.
+    pub fn security_score(&self) -> u8 {
+        match self.security_kind() {
+            Some(SecurityKind::Wpa2) if self.is_enterprise() => 4,
+            Some(kind) => kind.strength_score(),
+            None => 0,
+        }
+    }
+
+    /// Whether the hotspot authenticates with 802.1X (WPA Enterprise), e.g.
+    /// `WPA2 802.1X` (nmcli), `WPA2(802.1x/AES/AES)` (airport) or
+    /// `wpa2_enterprise` (system_profiler)
+    pub fn is_enterprise(&self) -> bool {
+        let security = self.security.to_uppercase();
+        ["802.1X", "ENTERPRISE", "EAP"]
+            .iter()
+            .any(|marker| security.contains(marker))
+    }
+
+    /// A rough guess at whether connecting would likely work: the signal is
+    /// at least `min_dbm` and the security scheme is recognised, and not WPA
+    /// Enterprise unless `allow_enterprise`, as that needs credentials beyond
+    /// a passphrase. This is advisory only, it knows nothing about passwords,
+    /// MAC filtering or captive portals.
+    pub fn is_connectable(&self, min_dbm: i32, allow_enterprise: bool) -> bool {
+        self.signal_dbm().is_some_and(|dbm| dbm >= min_dbm)
+            && self.security_kind().is_some()
+            && (allow_enterprise || !self.is_enterprise())
+    }
+
+    /// A guess at whether the hotspot is a captive portal, asking for a login
+    /// or terms to accept in the browser: it is open and its SSID contains
+    /// one of [`CAPTIVE_PORTAL_KEYWORDS`], e.g. `_Free_Airport_WiFi`. Only
+    /// meant for hints in a UI, many portals are named otherwise.
+    pub fn likely_captive_portal(&self) -> bool {
+        self.likely_captive_portal_with(CAPTIVE_PORTAL_KEYWORDS)
+    }
+
+    /// Same as [`Wifi::likely_captive_portal`] matching `keywords` instead
+    pub fn likely_captive_portal_with(&self, keywords: &[&str]) -> bool {
+        let ssid = self.ssid.to_lowercase();
+        self.security_kind() == Some(SecurityKind::Open)
+            && keywords
+                .iter()
+                .any(|keyword| ssid.contains(&keyword.to_lowercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(security: &str) -> Wifi {
+        Wifi {
+            security: security.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_order_by_strength() {
+        assert!(SecurityKind::Open < SecurityKind::Wep);
+        assert!(SecurityKind::Wep < SecurityKind::Wpa);
+        assert!(SecurityKind::Wpa < SecurityKind::Wpa2);
+        assert!(SecurityKind::Wpa2 < SecurityKind::Wpa3);
+        assert!(SecurityKind::Wpa3 < SecurityKind::Wpa3Enterprise192);
+
+        let kinds: Vec<Option<SecurityKind>> = [
+            "",
+            "Open",
+            "WEP",
+            "WPA1",
+            "WPA(PSK/TKIP/TKIP)",
+            "WPA2(PSK/AES/AES)",
+            "wpa2_personal",
+            "WPA2 WPA3",
+            "SAE",
+            "Lemon",
+        ]
+        .map(SecurityKind::parse)
+        .to_vec();
+        assert_eq!(
+            vec![
+                Some(SecurityKind::Open),
+                Some(SecurityKind::Open),
+                Some(SecurityKind::Wep),
+                Some(SecurityKind::Wpa),
+                Some(SecurityKind::Wpa),
+                Some(SecurityKind::Wpa2),
+                Some(SecurityKind::Wpa2),
+                Some(SecurityKind::Wpa3),
+                Some(SecurityKind::Wpa3),
+                None,
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn should_score_security() {
+        let scores: Vec<u8> = [
+            "",
+            "Lemon",
+            "WEP",
+            "WPA(802.1x/TKIP/TKIP)",
+            "WPA1",
+            "WPA2(PSK/AES/AES)",
+            "wpa2_enterprise",
+            "WPA3 802.1X",
+            "SAE",
+            "IEEE 802.1X/SUITE-B-192",
+        ]
+        .map(|security| wifi(security).security_score())
+        .to_vec();
+        assert_eq!(vec![0, 0, 1, 2, 2, 3, 4, 5, 5, 6], scores);
+
+        let kinds = [
+            SecurityKind::Open,
+            SecurityKind::Wep,
+            SecurityKind::Wpa,
+            SecurityKind::Wpa2,
+            SecurityKind::Wpa3,
+            SecurityKind::Wpa3Enterprise192,
+        ];
+        for pair in kinds.windows(2) {
+            assert!(pair[0].strength_score() < pair[1].strength_score());
+        }
+        assert!(kinds
+            .iter()
+            .all(|kind| wifi(&kind.to_string()).security_score() == kind.strength_score()));
+    }
+
+    #[test]
+    fn should_parse_security_name() {
+        let aliases = [
+            ("open", SecurityKind::Open),
+            ("None", SecurityKind::Open),
+            ("WEP", SecurityKind::Wep),
+            ("wpa1", SecurityKind::Wpa),
+            ("wpa2", SecurityKind::Wpa2),
+            (" WPA3 ", SecurityKind::Wpa3),
+            ("sae", SecurityKind::Wpa3),
+            ("Suite-B", SecurityKind::Wpa3Enterprise192),
+        ];
+        for (alias, kind) in aliases {
+            assert_eq!(Ok(kind), alias.parse(), "{alias}");
+            assert_eq!(Ok(kind), kind.to_string().parse());
+        }
+
+        let err = "wpa2_personal".parse::<SecurityKind>().unwrap_err();
+        assert_eq!(
+            "\"wpa2_personal\" is not a valid security (open, wep, wpa, wpa2, wpa3 or wpa3-enterprise-192)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_check_minimum_security() {
+        assert!(wifi("WPA2-Personal").is_at_least(SecurityKind::Wpa2));
+        assert!(wifi("WPA2/WPA3").is_at_least(SecurityKind::Wpa3));
+        assert!(!wifi("WPA").is_at_least(SecurityKind::Wpa2));
+        assert!(!wifi("").is_at_least(SecurityKind::Wep));
+        assert!(!wifi("Lemon").is_at_least(SecurityKind::Open));
+    }
+
+    #[test]
+    fn should_guess_connectable() {
+        let network = |security: &str, signal_level: &str| Wifi {
+            security: security.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        };
+
+        assert!(network("WPA2", "-60").is_connectable(-70, false));
+        assert!(network("", "-70").is_connectable(-70, false));
+
+        // too weak, or no signal at all
+        assert!(!network("WPA2", "-80").is_connectable(-70, false));
+        assert!(!network("WPA2", "").is_connectable(-70, false));
+
+        // enterprise needs to be allowed
+        for enterprise in ["WPA2 802.1X", "WPA2(802.1x/AES/AES)", "wpa2_enterprise"] {
+            let wifi = network(enterprise, "-50");
+            assert!(wifi.is_enterprise(), "{enterprise}");
+            assert!(!wifi.is_connectable(-70, false), "{enterprise}");
+            assert!(wifi.is_connectable(-70, true), "{enterprise}");
+        }
+
+        // unrecognised security
+        assert!(!network("FancyAuth", "-50").is_connectable(-70, true));
+    }
+
+    #[test]
+    fn should_guess_captive_portal() {
+        let network = |ssid: &str, security: &str| Wifi {
+            ssid: ssid.to_string(),
+            security: security.to_string(),
+            ..Default::default()
+        };
+
+        assert!(network("_Free_Airport_WiFi", "").likely_captive_portal());
+        assert!(network("Hotel Lobby", "NONE").likely_captive_portal());
+        assert!(network("Cafe GUEST", "Open").likely_captive_portal());
+
+        // secured, or no keyword
+        assert!(!network("Hotel Lobby", "WPA2").likely_captive_portal());
+        assert!(!network("home", "").likely_captive_portal());
+
+        let wifi = network("Bibliothek", "");
+        assert!(!wifi.likely_captive_portal());
+        assert!(wifi.likely_captive_portal_with(&["BIBLIO"]));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::Wifi;
+
+/// Coarse signal quality label, see [`Wifi::signal_quality`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignalQuality {
+    /// -50 dBm or stronger
+    Excellent,
+    /// -60 dBm up to -51 dBm
+    Good,
+    /// -70 dBm up to -61 dBm
+    Fair,
+    /// weaker than -70 dBm
+    Weak,
+    /// the signal level couldn't be parsed
+    Unknown,
+}
+
+/// Converts a power level in dBm to milliwatts, `10^(dbm/10)`
+pub fn dbm_to_mw(dbm: f64) -> f64 {
+    10f64.powf(dbm / 10.0)
+}
+
+/// Converts a power in milliwatts to dBm, the inverse of [`dbm_to_mw`]
+pub fn mw_to_dbm(mw: f64) -> f64 {
+    10.0 * mw.log10()
+}
+
+impl Wifi {
+    /// Returns the signal level in dBm, rounded to the nearest integer.
+    pub fn signal_dbm(&self) -> Option<i32> {
+        let dbm: f64 = self.signal_level.trim().parse().ok()?;
+        Some(dbm.round() as i32)
+    }
+
+    /// Returns the signal level in milliwatts, `None` if it couldn't be
+    /// parsed.
+    ///
+    /// dBm is logarithmic, so averaging dBm values directly understates
+    /// strong readings: -30 and -90 dBm average to -33 dBm, not -60. Sum or
+    /// average in mW and convert back with [`mw_to_dbm`].
+    pub fn signal_mw(&self) -> Option<f64> {
+        let dbm: f64 = self.signal_level.trim().parse().ok()?;
+        Some(dbm_to_mw(dbm))
+    }
+
+    /// Buckets the signal level into a [`SignalQuality`].
+    ///
+    /// The thresholds are inclusive: -50 dBm and above is `Excellent`, -60 dBm
+    /// and above is `Good`, -70 dBm and above is `Fair`, anything weaker is
+    /// `Weak`.
+    pub fn signal_quality(&self) -> SignalQuality {
+        match self.signal_dbm() {
+            Some(dbm) if dbm >= -50 => SignalQuality::Excellent,
+            Some(dbm) if dbm >= -60 => SignalQuality::Good,
+            Some(dbm) if dbm >= -70 => SignalQuality::Fair,
+            Some(_) => SignalQuality::Weak,
+            None => SignalQuality::Unknown,
+        }
+    }
+
+    /// Compares every field but those that change from one scan to the next:
+    /// `signal_level`, `noise_dbm`, `uptime`, `last_seen_ms`, the rates,
+    /// `tpc_tx_power_dbm` and `link_margin_db`.
+    pub fn eq_ignoring_signal(&self, other: &Wifi) -> bool {
+        #[allow(deprecated)]
+        let without_signal = |wifi: &Wifi| Wifi {
+            signal_level: String::new(),
+            noise_dbm: None,
+            uptime: None,
+            last_seen_ms: None,
+            max_rate_mbps: None,
+            tx_rate_mbps: None,
+            rx_rate_mbps: None,
+            tpc_tx_power_dbm: None,
+            link_margin_db: None,
+            ..wifi.clone()
+        };
+        without_signal(self) == without_signal(other)
+    }
+}
+
+/// Draws the signal quality of `wifi` as a bar `width` characters long,
+/// e.g. `██████░░` for a good signal. Each [`SignalQuality`] above `Unknown`
+/// fills another quarter of the bar, an unknown signal leaves it empty.
+pub fn signal_bars(wifi: &Wifi, width: usize) -> String {
+    let quarters = match wifi.signal_quality() {
+        SignalQuality::Excellent => 4,
+        SignalQuality::Good => 3,
+        SignalQuality::Fair => 2,
+        SignalQuality::Weak => 1,
+        SignalQuality::Unknown => 0,
+    };
+    let filled = (width * quarters + 2) / 4;
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_compare_ignoring_signal() {
+        let a = Wifi {
+            mac: "11:22:33:44:55:66".to_string(),
+            ssid: "hello".to_string(),
+            signal_level: "-67.00".to_string(),
+            ..Default::default()
+        };
+        let b = Wifi {
+            signal_level: "-71.00".to_string(),
+            ..a.clone()
+        };
+        assert_ne!(a, b);
+        assert!(a.eq_ignoring_signal(&b));
+
+        let c = Wifi {
+            ssid: "world".to_string(),
+            ..b.clone()
+        };
+        assert!(!a.eq_ignoring_signal(&c));
+    }
+
+    #[test]
+    fn should_compare_rescans_of_same_hotspot() {
+        let read = |name: &str| {
+            let data = std::fs::read_to_string(format!("tests/fixtures/iw/{name}")).unwrap();
+            crate::parse_iw(&data).unwrap()
+        };
+        let first = read("iw_dev_scan_tpc.txt");
+        let later = read("iw_dev_scan_tpc_later.txt");
+
+        assert_ne!(first[0], later[0]);
+        assert!(first[0].eq_ignoring_signal(&later[0]));
+        assert!(!first[1].eq_ignoring_signal(&later[0]));
+    }
+
+    #[test]
+    fn should_parse_signal_dbm() {
+        let mut wifi = Wifi {
+            signal_level: "-67.00".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(Some(-67), wifi.signal_dbm());
+
+        wifi.signal_level = "-70".to_string();
+        assert_eq!(Some(-70), wifi.signal_dbm());
+
+        wifi.signal_level = String::new();
+        assert_eq!(None, wifi.signal_dbm());
+    }
+
+    #[test]
+    fn should_convert_to_milliwatts() {
+        let wifi = |signal_level: &str| Wifi {
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        };
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+
+        assert!(close(0.001, wifi("-30").signal_mw().unwrap()));
+        assert!(close(1.0, wifi("0").signal_mw().unwrap()));
+        assert!(close(1e-9, wifi("-90.00").signal_mw().unwrap()));
+        assert_eq!(None, wifi("").signal_mw());
+
+        assert!((mw_to_dbm(0.001) + 30.0).abs() < 1e-9);
+        assert!((mw_to_dbm(dbm_to_mw(-67.5)) + 67.5).abs() < 1e-9);
+
+        let average = (dbm_to_mw(-30.0) + dbm_to_mw(-90.0)) / 2.0;
+        assert_eq!(-33, mw_to_dbm(average).round() as i32);
+    }
+
+    #[test]
+    fn should_bucket_signal_quality() {
+        let quality = |signal_level: &str| {
+            Wifi {
+                signal_level: signal_level.to_string(),
+                ..Default::default()
+            }
+            .signal_quality()
+        };
+
+        assert_eq!(SignalQuality::Excellent, quality("-30"));
+        assert_eq!(SignalQuality::Excellent, quality("-50"));
+        assert_eq!(SignalQuality::Good, quality("-51"));
+        assert_eq!(SignalQuality::Good, quality("-60"));
+        assert_eq!(SignalQuality::Fair, quality("-61"));
+        assert_eq!(SignalQuality::Fair, quality("-70"));
+        assert_eq!(SignalQuality::Weak, quality("-71"));
+        assert_eq!(SignalQuality::Weak, quality("-92"));
+        assert_eq!(SignalQuality::Unknown, quality(""));
+    }
+
+    #[test]
+    fn should_draw_signal_bars() {
+        let bars = |signal_level: &str, width: usize| {
+            let wifi = Wifi {
+                signal_level: signal_level.to_string(),
+                ..Default::default()
+            };
+            signal_bars(&wifi, width)
+        };
+
+        assert_eq!("████████", bars("-40", 8));
+        assert_eq!("██████░░", bars("-55", 8));
+        assert_eq!("████░░░░", bars("-65.00", 8));
+        assert_eq!("██░░░░░░", bars("-85", 8));
+        assert_eq!("░░░░░░░░", bars("", 8));
+        // rounded to the nearest character
+        assert_eq!("██░", bars("-55", 3));
+        assert_eq!("", bars("-40", 0));
+    }
+}
@@ -0,0 +1,168 @@
+use std::cmp::Reverse;
+
+use crate::Wifi;
+
+/// How a hotspot shows its SSID element, which tells a network cloaked with
+/// a blank SSID from one leaving the element out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HiddenReason {
+    /// the SSID element names the network
+    Visible,
+    /// the SSID element is there but blank or NUL bytes, see
+    /// [`Wifi::is_hidden`]
+    Empty,
+    /// the frames have no SSID element at all
+    Absent,
+}
+
+impl Wifi {
+    /// Whether the hotspot hides its SSID. `iw` shows hidden SSIDs as an
+    /// empty string or as escaped NUL bytes (`\x00\x00...`).
+    pub fn is_hidden(&self) -> bool {
+        is_hidden_ssid(&self.ssid)
+    }
+}
+
+/// Whether `ssid` is empty or only NUL bytes, raw or escaped, see
+/// [`Wifi::is_hidden`]
+pub(crate) fn is_hidden_ssid(ssid: &str) -> bool {
+    ssid.replace("\\x00", "").trim_matches('\0').is_empty()
+}
+
+/// Sorts alphabetically by SSID, ignoring case, then by channel number.
+/// Hidden networks go last. The sort is stable.
+pub fn sort_by_ssid(wifis: &mut [Wifi]) {
+    wifis.sort_by_key(|wifi| {
+        (
+            wifi.is_hidden(),
+            wifi.ssid.to_lowercase(),
+            wifi.channel_number().unwrap_or(u16::MAX),
+        )
+    });
+}
+
+/// Sorts strongest signal first, networks without a parseable signal go last.
+/// The sort is stable.
+pub fn sort_by_signal(wifis: &mut [Wifi]) {
+    wifis.sort_by_key(|wifi| Reverse(wifi.signal_dbm().unwrap_or(i32::MIN)));
+}
+
+/// Brings scan results into a canonical form, so equal scans compare and
+/// serialize the same whichever backend made them and in whatever order it
+/// listed the hotspots, e.g. for snapshot tests. MAC addresses are written
+/// lowercase with colons, SSIDs lose their surrounding whitespace, then the
+/// hotspots are sorted by MAC address, SSID and channel.
+pub fn canonicalize(wifis: &mut [Wifi]) {
+    for wifi in wifis.iter_mut() {
+        wifi.mac = match wifi.mac_addr() {
+            Some(mac) => mac.to_string(),
+            None => wifi.mac.trim().to_lowercase(),
+        };
+        wifi.ssid = wifi.ssid.trim().to_string();
+    }
+    wifis.sort_by_cached_key(|wifi| {
+        (
+            wifi.mac.clone(),
+            wifi.ssid.clone(),
+            wifi.channel_number().unwrap_or(u16::MAX),
+            wifi.channel.clone(),
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(ssid: &str, channel: &str, signal_level: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            channel: channel.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_sort_by_ssid() {
+        let mut wifis = vec![
+            wifi("beta", "11", ""),
+            wifi("", "1", ""),
+            wifi("Alpha", "36", ""),
+            wifi("alpha", "6", ""),
+            wifi("Beta", "11", "-40"),
+            wifi("ALPHA", "6", ""),
+        ];
+        sort_by_ssid(&mut wifis);
+
+        let order: Vec<(&str, &str)> = wifis
+            .iter()
+            .map(|w| (w.ssid.as_str(), w.channel.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("alpha", "6"),
+                ("ALPHA", "6"),
+                ("Alpha", "36"),
+                ("beta", "11"),
+                ("Beta", "11"),
+                ("", "1"),
+            ],
+            order
+        );
+    }
+
+    #[test]
+    fn should_detect_hidden_networks() {
+        assert!(wifi("", "1", "").is_hidden());
+        assert!(wifi("\\x00\\x00\\x00", "1", "").is_hidden());
+        assert!(wifi("\0\0", "1", "").is_hidden());
+        assert!(!wifi("hello", "1", "").is_hidden());
+    }
+
+    #[test]
+    fn should_canonicalize() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_scan_01.txt").unwrap();
+        let mut expected = crate::parse::parse_iw(&data).unwrap();
+        canonicalize(&mut expected);
+        let macs: Vec<&str> = expected.iter().map(|w| w.mac.as_str()).collect();
+        assert_eq!(
+            vec![
+                "11:22:33:44:55:66",
+                "22:33:44:55:66:77",
+                "33:44:55:66:77:88",
+                "44:55:66:77:88:99",
+                "55:66:77:88:99:aa",
+                "66:77:88:99:aa:bb",
+            ],
+            macs
+        );
+
+        // the same scan listed in another order and written differently
+        let mut shuffled = crate::parse::parse_iw(&data).unwrap();
+        shuffled.reverse();
+        shuffled.rotate_left(2);
+        shuffled[0].mac = shuffled[0].mac.to_uppercase().replace(':', "-");
+        shuffled[1].ssid = format!(" {} ", shuffled[1].ssid);
+        canonicalize(&mut shuffled);
+        assert_eq!(expected, shuffled);
+
+        // the same BSSID on two channels, unparseable channels go last
+        let mut wifis = vec![wifi("a", "", ""), wifi("a", "11", ""), wifi("a", "6", "")];
+        canonicalize(&mut wifis);
+        let channels: Vec<&str> = wifis.iter().map(|w| w.channel.as_str()).collect();
+        assert_eq!(vec!["6", "11", ""], channels);
+    }
+
+    #[test]
+    fn should_sort_by_signal() {
+        let mut wifis = vec![
+            wifi("a", "1", "-70"),
+            wifi("b", "1", ""),
+            wifi("c", "1", "-40"),
+        ];
+        sort_by_signal(&mut wifis);
+        let order: Vec<&str> = wifis.iter().map(|w| w.ssid.as_str()).collect();
+        assert_eq!(vec!["c", "a", "b"], order);
+    }
+}
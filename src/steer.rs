@@ -0,0 +1,83 @@
+use crate::{Band, Wifi};
+
+/// How much weaker, in dB, a hotspot on the preferred band may be than the
+/// strongest one and still be picked by [`best_ap_for_ssid`]
+pub const DEFAULT_BAND_MARGIN_DB: i32 = 8;
+
+/// Returns the hotspot of `ssid` to steer toward: the strongest one on
+/// `prefer_band` if it is within [`DEFAULT_BAND_MARGIN_DB`] of the strongest
+/// overall, otherwise the strongest overall. `None` if no hotspot has the
+/// SSID.
+pub fn best_ap_for_ssid(wifis: &[Wifi], ssid: &str, prefer_band: Option<Band>) -> Option<Wifi> {
+    best_ap_for_ssid_with_margin(wifis, ssid, prefer_band, DEFAULT_BAND_MARGIN_DB)
+}
+
+/// Same as [`best_ap_for_ssid`] but the hotspot on the preferred band may be
+/// up to `margin_db` weaker.
+pub fn best_ap_for_ssid_with_margin(
+    wifis: &[Wifi],
+    ssid: &str,
+    prefer_band: Option<Band>,
+    margin_db: i32,
+) -> Option<Wifi> {
+    let candidates = || wifis.iter().filter(|wifi| wifi.ssid == ssid);
+    let best = strongest(candidates())?;
+    let preferred = prefer_band
+        .and_then(|band| strongest(candidates().filter(|wifi| wifi.band() == Some(band))));
+    match preferred {
+        Some(preferred) if signal(preferred).saturating_add(margin_db) >= signal(best) => {
+            Some(preferred.clone())
+        }
+        _ => Some(best.clone()),
+    }
+}
+
+fn signal(wifi: &Wifi) -> i32 {
+    wifi.signal_dbm().unwrap_or(i32::MIN)
+}
+
+/// Returns the first of the strongest hotspots
+fn strongest<'a>(wifis: impl DoubleEndedIterator<Item = &'a Wifi>) -> Option<&'a Wifi> {
+    // max_by_key keeps the last of equal elements
+    wifis.rev().max_by_key(|wifi| signal(wifi))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(ssid: &str, channel: &str, signal_level: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            channel: channel.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_prefer_band_within_margin() {
+        let wifis = vec![
+            wifi("home", "6", "-50"),
+            wifi("home", "36", "-55"),
+            wifi("home", "149", "-70"),
+            wifi("neighbour", "1", "-30"),
+        ];
+
+        let best = best_ap_for_ssid(&wifis, "home", Some(Band::Band5GHz)).unwrap();
+        assert_eq!("36", best.channel);
+
+        let best = best_ap_for_ssid(&wifis, "home", None).unwrap();
+        assert_eq!("6", best.channel);
+
+        // the 5 GHz hotspot is too much weaker with a tight margin
+        let best = best_ap_for_ssid_with_margin(&wifis, "home", Some(Band::Band5GHz), 3).unwrap();
+        assert_eq!("6", best.channel);
+
+        // no hotspot on the preferred band
+        let best = best_ap_for_ssid(&wifis, "home", Some(Band::Band6GHz)).unwrap();
+        assert_eq!("6", best.channel);
+
+        assert_eq!(None, best_ap_for_ssid(&wifis, "office", None));
+    }
+}
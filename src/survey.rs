@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+/// Airtime statistics the driver collected for one channel, returned by
+/// [`channel_survey`]
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct ChannelSurvey {
+    /// centre frequency of the channel in MHz
+    pub frequency: u32,
+    /// noise floor in dBm
+    pub noise_dbm: Option<i32>,
+    /// how long the radio has listened on the channel
+    pub active_time: Option<Duration>,
+    /// how much of `active_time` the channel was sensed busy
+    pub busy_time: Option<Duration>,
+}
+
+impl ChannelSurvey {
+    /// Share of the active time the channel was busy, from 0 to 1
+    pub fn utilization(&self) -> Option<f64> {
+        let active = self.active_time?.as_secs_f64();
+        let busy = self.busy_time?.as_secs_f64();
+        (active > 0.0).then(|| busy / active)
+    }
+}
+
+/// Returns what the driver knows about each channel's airtime, e.g. to avoid
+/// busy DFS channels - (Linux) uses `iw dev <interface> survey dump`. Not
+/// every driver reports the times, or anything past the frequency.
+#[cfg(target_os = "linux")]
+pub fn channel_survey() -> anyhow::Result<Vec<ChannelSurvey>> {
+    crate::sys::channel_survey()
+}
+
+/// Parses the output of `iw dev <interface> survey dump`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn parse_iw_survey(survey: &str) -> Vec<ChannelSurvey> {
+    survey
+        .split("Survey data from ")
+        .skip(1)
+        .filter_map(|block| {
+            let mut channel: Option<ChannelSurvey> = None;
+            let mut noise_dbm = None;
+            let mut active_time = None;
+            let mut busy_time = None;
+            for line in block.lines().skip(1) {
+                let Some((key, value)) = line.trim().split_once(':') else {
+                    continue;
+                };
+                let number = value.split_whitespace().next().unwrap_or_default();
+                match key {
+                    "frequency" => {
+                        channel = number.parse().ok().map(|frequency| ChannelSurvey {
+                            frequency,
+                            ..Default::default()
+                        })
+                    }
+                    "noise" => noise_dbm = number.parse().ok(),
+                    "channel active time" => active_time = number.parse().ok(),
+                    "channel busy time" => busy_time = number.parse().ok(),
+                    _ => {}
+                }
+            }
+            let mut channel = channel?;
+            channel.noise_dbm = noise_dbm;
+            channel.active_time = active_time.map(Duration::from_millis);
+            channel.busy_time = busy_time.map(Duration::from_millis);
+            Some(channel)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_iw_survey() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_survey_dump_01.txt").unwrap();
+        let surveys = parse_iw_survey(&data);
+        assert_eq!(
+            vec![2412, 2417, 5260, 5500],
+            surveys.iter().map(|s| s.frequency).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            ChannelSurvey {
+                frequency: 5260,
+                noise_dbm: Some(-101),
+                active_time: Some(Duration::from_millis(1815466)),
+                busy_time: Some(Duration::from_millis(241786)),
+            },
+            surveys[2]
+        );
+
+        // frequencies the driver never sampled only have the frequency
+        assert_eq!(
+            ChannelSurvey {
+                frequency: 2417,
+                ..Default::default()
+            },
+            surveys[1]
+        );
+        assert_eq!(None, surveys[1].utilization());
+
+        let utilization = surveys[0].utilization().unwrap();
+        assert!((utilization - 97.0 / 412.0).abs() < 1e-9);
+    }
+}
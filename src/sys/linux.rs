@@ -1,18 +1,125 @@
 use anyhow::Context;
 use std::env;
+use std::fs;
 use std::process::Command;
+use std::sync::Arc;
 
-use crate::Wifi;
+use crate::backend::{command_exists, ScanBackend};
+use crate::best_effort::to_wifi_error;
+use crate::interfaces::{ensure_interface, scan_concurrently, with_interface_lock};
+use crate::output::{parse_output, run};
+use crate::parse::{extract_value, parse_nmcli_partial};
+use crate::survey::{parse_iw_survey, ChannelSurvey};
+use crate::{
+    channel_to_frequency, frequency_to_channel, AllInterfacesScan, BackendCapabilities, Band,
+    FullScan, InterfaceScan, MacAddr, Platform, Wifi, WifiError, CHANNELS_2_4, CHANNELS_5,
+    CHANNELS_6,
+};
 
-/// Returns a list of WiFi hotspots in your area - (Linux). uses `nmcli` or `iw`.
-pub(crate) fn scan() -> anyhow::Result<Vec<Wifi>> {
-    scan_nm().or_else(|_| scan_iw())
+/// The Linux backends in the order `scan` tries them
+pub(crate) fn builtin_backends() -> Vec<Arc<dyn ScanBackend>> {
+    vec![
+        Arc::new(Nmcli),
+        Arc::new(Iw),
+        Arc::new(Iwctl),
+        Arc::new(Iwlist),
+    ]
+}
+
+struct Nmcli;
+
+impl ScanBackend for Nmcli {
+    fn name(&self) -> &str {
+        "nmcli"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("nmcli")
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        Ok(self.scan_partial()?.0)
+    }
+
+    fn scan_partial(&self) -> Result<(Vec<Wifi>, usize), WifiError> {
+        scan_nm().map_err(to_wifi_error)
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::LinuxNmcli.capabilities()
+    }
+}
+
+struct Iw;
+
+impl ScanBackend for Iw {
+    fn name(&self) -> &str {
+        "iw"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("iw")
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        scan_iw().map_err(to_wifi_error)
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::LinuxIw.capabilities()
+    }
+}
+
+/// `iwd`'s client, on systems where it manages Wi-Fi instead of
+/// NetworkManager
+struct Iwctl;
+
+impl ScanBackend for Iwctl {
+    fn name(&self) -> &str {
+        "iwctl"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("iwctl")
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        scan_using_iwctl().map_err(to_wifi_error)
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::LinuxIwctl.capabilities()
+    }
+}
+
+struct Iwlist;
+
+impl ScanBackend for Iwlist {
+    fn name(&self) -> &str {
+        "iwlist"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("iwlist")
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        scan_using_iwlist().map_err(to_wifi_error)
+    }
+
+    fn is_deprecated(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::LinuxIwlist.capabilities()
+    }
 }
 
 /// Returns a list of WiFi hotspots in your area - (Linux) uses `nmcli`
-fn scan_nm() -> anyhow::Result<Vec<Wifi>> {
+fn scan_nm() -> anyhow::Result<(Vec<Wifi>, usize)> {
     tracing::info!("Scanning using nmcli...");
-    let output = Command::new("nmcli")
+    let output = run(Command::new("nmcli")
         .arg("--color")
         .arg("no")
         .arg("--terse")
@@ -20,143 +127,423 @@ fn scan_nm() -> anyhow::Result<Vec<Wifi>> {
         .arg("ssid,chan,signal,security,bssid")
         .arg("dev")
         .arg("wifi")
-        .arg("list")
-        .output()?;
+        .arg("list"))?;
 
     let data = String::from_utf8_lossy(&output.stdout);
     tracing::debug!(">> {data}");
 
-    let mut result = vec![];
-    for line in data.lines() {
-        let mut wifi = Wifi::default();
-        let mut fs = line.splitn(5, ':');
-        if let Some(ssid) = fs.next() {
-            wifi.ssid = ssid.to_string();
-        } else {
-            continue;
-        }
-        if let Some(channel) = fs.next() {
-            wifi.channel = channel.to_string();
-        } else {
-            continue;
-        }
-        if let Some(signal_level) = fs.next() {
-            wifi.signal_level = signal_level.to_string();
-        } else {
-            continue;
-        }
-        if let Some(security) = fs.next() {
-            wifi.security = security.to_string();
-        } else {
-            continue;
-        }
-        if let Some(mac) = fs.next() {
-            wifi.mac = mac.replace(r"\:", ":").to_string();
-        } else {
-            continue;
-        }
-        result.push(wifi);
+    let (wifis, skipped) = parse_nmcli_partial(&data);
+    if skipped > 0 {
+        tracing::warn!("skipped {skipped} nmcli lines that couldn't be parsed");
     }
-    Ok(result)
+    Ok((wifis, skipped))
 }
 
-/// Returns a list of WiFi hotspots in your area - (Linux) uses `iw`
-fn scan_iw() -> anyhow::Result<Vec<Wifi>> {
+/// Returns a command that also looks for `program` in the sbin directories,
+/// which often aren't in a regular user's `PATH`.
+fn sbin_command(program: &str) -> Command {
     const PATH_ENV: &str = "PATH";
     let path_system = "/usr/sbin:/sbin";
     let path = env::var_os(PATH_ENV).map_or(path_system.to_string(), |v| {
         format!("{}:{}", v.to_string_lossy().into_owned(), path_system)
     });
 
-    let output = Command::new("iw")
-        .env(PATH_ENV, path.clone())
-        .arg("dev")
-        .output()?;
+    let mut command = Command::new(program);
+    command.env(PATH_ENV, path);
+    command
+}
+
+fn iw() -> Command {
+    sbin_command("iw")
+}
+
+/// Returns the networks `iwd` knows of - (Linux) uses `iwctl`
+fn scan_using_iwctl() -> anyhow::Result<Vec<Wifi>> {
+    let interface = interface_names()?
+        .into_iter()
+        .next()
+        .context("No wireless interface found")?;
+    // only asks iwd to scan, get-networks lists what it has found so far
+    if let Err(e) = run(Command::new("iwctl").args(["station", &interface, "scan"])) {
+        tracing::debug!("iwctl scan failed ({e}), listing known networks");
+    }
+    let output =
+        run(Command::new("iwctl").args(["station", &interface, "get-networks", "rssi-dbms"]))?;
+
     let data = String::from_utf8_lossy(&output.stdout);
-    let interface = parse_iw_dev(&data)?;
+    parse_output(Platform::LinuxIwctl, &data)
+}
 
-    let output = Command::new("iw")
-        .env(PATH_ENV, path)
-        .arg("dev")
-        .arg(interface)
-        .arg("scan")
-        .output()?;
+/// Returns the hotspots seen by every wireless interface - (Linux) uses the
+/// deprecated `iwlist`, for systems without `nmcli` and `iw`
+fn scan_using_iwlist() -> anyhow::Result<Vec<Wifi>> {
+    let output = run(sbin_command("iwlist").arg("scan"))?;
+
+    let data = String::from_utf8_lossy(&output.stdout);
+    parse_output(Platform::LinuxIwlist, &data)
+}
+
+/// Returns the first wireless interface reported by `iw dev`
+fn iw_interface() -> anyhow::Result<String> {
+    let output = run(iw().arg("dev"))?;
+    let data = String::from_utf8_lossy(&output.stdout);
+    parse_iw_dev(&data)
+}
+
+/// Returns a list of WiFi hotspots in your area - (Linux) uses `iw`
+fn scan_iw() -> anyhow::Result<Vec<Wifi>> {
+    scan_iw_interface(&iw_interface()?)
+}
+
+/// Returns the hotspots seen by each interface `iw dev` lists - (Linux) uses `iw`
+pub(crate) fn scan_with_interfaces() -> anyhow::Result<Vec<InterfaceScan>> {
+    let output = run(iw().arg("dev"))?;
+    let data = String::from_utf8_lossy(&output.stdout);
+    parse_iw_dev_interfaces(&data)
+        .into_iter()
+        .map(|(interface, own_mac)| {
+            Ok(InterfaceScan {
+                networks: scan_iw_interface(&interface)?,
+                interface,
+                own_mac,
+            })
+        })
+        .collect()
+}
+
+/// Returns the hotspots seen by `interface` - (Linux) uses `iw`
+pub(crate) fn scan_interface(interface: &str) -> anyhow::Result<Vec<Wifi>> {
+    ensure_interface(&interface_names()?, interface)?;
+    scan_iw_interface(interface)
+}
+
+/// Scans every interface at the same time - (Linux) uses `iw`
+pub(crate) fn scan_all_interfaces(max_threads: usize) -> anyhow::Result<AllInterfacesScan> {
+    let output = run(iw().arg("dev"))?;
+    let data = String::from_utf8_lossy(&output.stdout);
+    Ok(scan_concurrently(
+        parse_iw_dev_interfaces(&data),
+        max_threads,
+        scan_iw_interface,
+    ))
+}
+
+/// Returns the scan, the connected network and the interfaces - (Linux) uses
+/// the backends, then `iw` or `/proc/net/wireless`
+pub(crate) fn scan_full() -> anyhow::Result<FullScan> {
+    Ok(FullScan {
+        networks: crate::scan()?,
+        connected: current_connection()?,
+        interfaces: interface_names()?,
+    })
+}
+
+/// Returns the interfaces `iw dev` lists, or those in `/proc/net/wireless`
+/// when `iw` isn't available
+pub(crate) fn interface_names() -> anyhow::Result<Vec<String>> {
+    match run(iw().arg("dev")) {
+        Ok(output) => Ok(
+            parse_iw_dev_interfaces(&String::from_utf8_lossy(&output.stdout))
+                .into_iter()
+                .map(|(interface, _)| interface)
+                .collect(),
+        ),
+        Err(e) => {
+            tracing::debug!("iw dev failed ({e}), reading {PROC_NET_WIRELESS}");
+            let data = fs::read_to_string(PROC_NET_WIRELESS)?;
+            Ok(parse_proc_net_wireless(&data)
+                .into_iter()
+                .map(|link| link.interface)
+                .collect())
+        }
+    }
+}
+
+/// Returns the hotspots the kernel has cached from earlier scans, with their
+/// age in `last_seen_ms` - (Linux) uses `iw ... scan dump`
+pub(crate) fn scan_cached() -> anyhow::Result<Vec<Wifi>> {
+    let interface = iw_interface()?;
+    let output = run(iw().arg("dev").arg(interface).arg("scan").arg("dump"))?;
+
+    let data = String::from_utf8_lossy(&output.stdout);
+    parse_output(Platform::LinuxIw, &data)
+}
+
+/// Returns the airtime statistics of each channel - (Linux) uses
+/// `iw ... survey dump`
+pub(crate) fn channel_survey() -> anyhow::Result<Vec<ChannelSurvey>> {
+    let interface = iw_interface()?;
+    let output = run(iw().arg("dev").arg(interface).arg("survey").arg("dump"))?;
+
+    let data = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_iw_survey(&data))
+}
+
+fn scan_iw_interface(interface: &str) -> anyhow::Result<Vec<Wifi>> {
+    scan_iw_args(&iw_scan_args(interface, None, &[]))
+}
+
+/// Returns the hotspots found by a scan probing for `ssid`, which also
+/// finds hidden networks with that SSID - (Linux) uses `iw ... scan ssid`
+pub(crate) fn scan_for_ssid(ssid: &str) -> anyhow::Result<Vec<Wifi>> {
+    scan_iw_args(&iw_scan_args(&iw_interface()?, Some(ssid), &[]))
+}
+
+/// Returns the hotspots found by scanning only `frequency` (in MHz) - (Linux)
+/// uses `iw ... scan freq`
+pub(crate) fn scan_frequency(frequency: u32) -> anyhow::Result<Vec<Wifi>> {
+    let frequency = frequency.to_string();
+    scan_iw_args(&iw_scan_args(&iw_interface()?, None, &[&frequency]))
+}
+
+/// Returns the hotspots found by scanning only the channels the regulatory
+/// domain allows - (Linux) uses `iw reg get` and `iw ... scan freq`
+pub(crate) fn scan_regulatory_channels() -> anyhow::Result<Vec<Wifi>> {
+    let output = run(iw().arg("reg").arg("get"))?;
+    let ranges = parse_iw_reg(&String::from_utf8_lossy(&output.stdout));
+    let frequencies: Vec<String> = allowed_frequencies(&ranges)
+        .iter()
+        .map(u32::to_string)
+        .collect();
+    anyhow::ensure!(
+        !frequencies.is_empty(),
+        "the regulatory domain allows no Wi-Fi channel"
+    );
+    let frequencies: Vec<&str> = frequencies.iter().map(String::as_str).collect();
+    scan_iw_args(&iw_scan_args(&iw_interface()?, None, &frequencies))
+}
+
+fn scan_iw_args(args: &[&str]) -> anyhow::Result<Vec<Wifi>> {
+    // iw_scan_args puts the interface second
+    let interface = args.get(1).copied().unwrap_or_default();
+    let output =
+        with_interface_lock(interface, || run(iw().args(args))).map_err(iw_permission_error)?;
+
+    let data = String::from_utf8_lossy(&output.stdout);
+    parse_output(Platform::LinuxIw, &data)
+}
+
+/// Turns the failure of an `iw` scan lacking the permission, which prints
+/// `command failed: Operation not permitted (-1)`, into a
+/// [`WifiError::PermissionDenied`]. Other errors are returned as they are.
+fn iw_permission_error(error: anyhow::Error) -> anyhow::Error {
+    match error.downcast_ref::<WifiError>() {
+        Some(WifiError::CommandFailed { reason, .. })
+            if ["Operation not permitted", "-EPERM", "(-1)"]
+                .iter()
+                .any(|message| reason.contains(message)) =>
+        {
+            WifiError::PermissionDenied {
+                hint: "iw needs CAP_NET_ADMIN to scan, run as root or with sudo, or grant it \
+                       with `setcap cap_net_admin+ep $(which iw)`"
+                    .to_string(),
+            }
+            .into()
+        }
+        _ => error,
+    }
+}
+
+/// Arguments of `iw` scanning on `interface`, probing for `ssid` if given
+/// and only on `frequencies` (in MHz) unless there are none
+fn iw_scan_args<'a>(
+    interface: &'a str,
+    ssid: Option<&'a str>,
+    frequencies: &[&'a str],
+) -> Vec<&'a str> {
+    let mut args = vec!["dev", interface, "scan"];
+    if !frequencies.is_empty() {
+        args.push("freq");
+        args.extend(frequencies);
+    }
+    if let Some(ssid) = ssid {
+        args.extend(["ssid", ssid]);
+    }
+    args
+}
+
+/// Returns the network the machine is connected to - (Linux) uses `iw` and
+/// falls back to `/proc/net/wireless` when `iw` isn't available.
+pub(crate) fn current_connection() -> anyhow::Result<Option<Wifi>> {
+    current_connection_iw().or_else(|e| {
+        tracing::debug!("iw link failed ({e}), reading {PROC_NET_WIRELESS}");
+        let data = fs::read_to_string(PROC_NET_WIRELESS)?;
+        Ok(proc_net_connection(&data))
+    })
+}
 
+/// The associated interface of `/proc/net/wireless` with the best link. An
+/// interface that isn't associated has a link quality of 0 and a level of
+/// -256, it doesn't count.
+fn proc_net_connection(data: &str) -> Option<Wifi> {
+    let link = parse_proc_net_wireless(data)
+        .into_iter()
+        .filter(|link| link.link_quality > 0.0 && link.signal_dbm > -256.0)
+        .max_by(|a, b| a.link_quality.total_cmp(&b.link_quality))?;
+    tracing::debug!("using {} from {PROC_NET_WIRELESS}", link.interface);
+    Some(link.into_wifi())
+}
+
+fn current_connection_iw() -> anyhow::Result<Option<Wifi>> {
+    let interface = iw_interface()?;
+    let output = iw().arg("dev").arg(interface).arg("link").output()?;
     anyhow::ensure!(
         output.status.success(),
         "iw command failed: {}, {}",
         output.status,
-        String::from_utf8_lossy(&output.stderr).to_string()
+        String::from_utf8_lossy(&output.stderr)
     );
     let data = String::from_utf8_lossy(&output.stdout);
-    parse_iw_dev_scan(&data)
+    Ok(parse_iw_link(&data))
 }
 
-fn parse_iw_dev(interfaces: &str) -> anyhow::Result<String> {
-    interfaces
-        .split("\tInterface ")
-        .take(2)
-        .last()
-        .context("No value")?
-        .lines()
-        .next()
-        .context("No value")
-        .map(|text| text.to_string())
-}
-
-fn parse_iw_dev_scan(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
-    let mut wifis: Vec<Wifi> = Vec::new();
-    let mut wifi = Wifi::default();
-    for line in network_list.lines() {
-        if let Ok(mac) = extract_value(line, "BSS ", Some("(")) {
-            if !wifi.mac.is_empty()
-                && !wifi.signal_level.is_empty()
-                && !wifi.channel.is_empty()
-                && !wifi.ssid.is_empty()
-            {
-                wifis.push(wifi);
-                wifi = Wifi::default();
+fn parse_iw_link(link: &str) -> Option<Wifi> {
+    let mut lines = link.lines();
+    let mac = lines
+        .next()?
+        .strip_prefix("Connected to ")?
+        .split(' ')
+        .next()?;
+    let mut wifi = Wifi {
+        mac: mac.to_string(),
+        ..Default::default()
+    };
+    for line in lines {
+        if let Ok(ssid) = extract_value(line, "\tSSID: ", None) {
+            wifi.ssid = ssid;
+        } else if let Ok(freq) = extract_value(line, "\tfreq: ", None) {
+            if let Some(channel) = freq.trim().parse().ok().and_then(frequency_to_channel) {
+                wifi.channel = channel.to_string();
             }
-            wifi.mac = mac;
         } else if let Ok(signal) = extract_value(line, "\tsignal: ", Some(" dBm")) {
             wifi.signal_level = signal;
-        } else if let Ok(channel) = extract_value(line, "\t\t * primary channel: ", None) {
-            wifi.channel = channel;
-        } else if let Ok(ssid) = extract_value(line, "\tSSID: ", None) {
-            wifi.ssid = ssid;
-        } else if let Ok(security) = extract_value(line, "\t\t * Authentication suites: ", None) {
-            wifi.security = security;
+        } else if let Ok(rate) = extract_value(line, "\ttx bitrate: ", Some(" MBit/s")) {
+            wifi.tx_rate_mbps = rate.trim().parse().ok();
+        } else if let Ok(rate) = extract_value(line, "\trx bitrate: ", Some(" MBit/s")) {
+            wifi.rx_rate_mbps = rate.trim().parse().ok();
         }
     }
-    // push the last wifi
-    if !wifi.mac.is_empty()
-        && !wifi.signal_level.is_empty()
-        && !wifi.channel.is_empty()
-        && !wifi.ssid.is_empty()
-    {
-        wifis.push(wifi);
-    }
+    Some(wifi)
+}
 
-    Ok(wifis)
+/// Frequencies in MHz a regulatory domain rule allows
+#[derive(Debug, PartialEq)]
+struct FrequencyRange {
+    start_mhz: u32,
+    end_mhz: u32,
 }
 
-fn extract_value(
-    line: &str,
-    pattern_start: &str,
-    pattern_end: Option<&str>,
-) -> anyhow::Result<String> {
-    let start = pattern_start.len();
-    if start < line.len() && &line[0..start] == pattern_start {
-        let end = match pattern_end {
-            Some(end) => line.find(end).context("No value")?,
-            None => line.len(),
+/// Parses the rules of the first country `iw reg get` lists, the global
+/// domain, e.g. `\t(5150 - 5250 @ 80), (N/A, 23), (N/A), NO-OUTDOOR`. The
+/// domains of self-managed phys listed after it are ignored.
+fn parse_iw_reg(reg: &str) -> Vec<FrequencyRange> {
+    let mut ranges = Vec::new();
+    for line in reg.lines() {
+        let Some(rule) = line.strip_prefix("\t(") else {
+            if !ranges.is_empty() && line.trim().is_empty() {
+                break;
+            }
+            continue;
+        };
+        let Some((range, _)) = rule.split_once(" @ ") else {
+            continue;
         };
-        Ok(line[start..end].to_string())
-    } else {
-        anyhow::bail!("No value");
+        let Some((start, end)) = range.split_once(" - ") else {
+            continue;
+        };
+        let mhz = |value: &str| value.trim().parse::<f64>().ok().map(|mhz| mhz as u32);
+        if let (Some(start_mhz), Some(end_mhz)) = (mhz(start), mhz(end)) {
+            ranges.push(FrequencyRange { start_mhz, end_mhz });
+        }
+    }
+    ranges
+}
+
+/// Centre frequencies of the 20 MHz Wi-Fi channels lying wholly in one of
+/// `ranges`, in ascending order
+fn allowed_frequencies(ranges: &[FrequencyRange]) -> Vec<u32> {
+    [
+        (Band::Band2_4GHz, CHANNELS_2_4),
+        (Band::Band5GHz, CHANNELS_5),
+        (Band::Band6GHz, CHANNELS_6),
+    ]
+    .into_iter()
+    .flat_map(|(band, channels)| {
+        channels
+            .iter()
+            .filter_map(move |&channel| channel_to_frequency(channel, band))
+    })
+    .filter(|&mhz| {
+        ranges
+            .iter()
+            .any(|range| range.start_mhz + 10 <= mhz && mhz + 10 <= range.end_mhz)
+    })
+    .collect()
+}
+
+const PROC_NET_WIRELESS: &str = "/proc/net/wireless";
+
+/// An active interface listed in `/proc/net/wireless`
+#[derive(Debug, PartialEq)]
+struct WirelessLink {
+    interface: String,
+    link_quality: f32,
+    signal_dbm: f32,
+}
+
+impl WirelessLink {
+    /// `/proc/net/wireless` knows nothing about the network itself, so only
+    /// the signal level is filled in.
+    fn into_wifi(self) -> Wifi {
+        Wifi {
+            signal_level: self.signal_dbm.to_string(),
+            ..Default::default()
+        }
     }
 }
 
+/// Parses the interface lines of `/proc/net/wireless`, skipping the two
+/// header lines, e.g. ` wlp2s0: 0000   54.  -56.  -256 ...`
+fn parse_proc_net_wireless(data: &str) -> Vec<WirelessLink> {
+    data.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (interface, stats) = line.split_once(':')?;
+            let mut fields = stats.split_whitespace().skip(1);
+            let mut number = || fields.next()?.trim_end_matches('.').parse::<f32>().ok();
+            Some(WirelessLink {
+                interface: interface.trim().to_string(),
+                link_quality: number()?,
+                signal_dbm: number()?,
+            })
+        })
+        .collect()
+}
+
+fn parse_iw_dev(interfaces: &str) -> anyhow::Result<String> {
+    parse_iw_dev_interfaces(interfaces)
+        .into_iter()
+        .next()
+        .map(|(interface, _)| interface)
+        .context("No value")
+}
+
+/// Returns the name and `addr` of every interface in `iw dev`
+fn parse_iw_dev_interfaces(interfaces: &str) -> Vec<(String, Option<MacAddr>)> {
+    interfaces
+        .split("\tInterface ")
+        .skip(1)
+        .filter_map(|block| {
+            let mut lines = block.lines();
+            let name = lines.next()?.trim().to_string();
+            let own_mac = lines
+                .find_map(|line| line.trim().strip_prefix("addr "))
+                .and_then(|mac| mac.parse().ok());
+            Some((name, own_mac))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,32 +577,147 @@ mod tests {
     }
 
     #[test]
-    fn should_parse_iw_dev_scan() {
-        let expected: Vec<Wifi> = vec![
-            Wifi {
-                mac: "11:22:33:44:55:66".to_string(),
-                ssid: "hello".to_string(),
-                channel: "10".to_string(),
-                signal_level: "-67.00".to_string(),
-                security: "PSK".to_string(),
-            },
-            Wifi {
-                mac: "66:77:88:99:aa:bb".to_string(),
-                ssid: "hello-world-foo-bar".to_string(),
-                channel: "8".to_string(),
-                signal_level: "-89.00".to_string(),
-                security: "PSK".to_string(),
-            },
-        ];
+    fn should_parse_iw_dev_interfaces() {
+        let data = std::fs::read_to_string("tests/fixtures/iw/iw_dev_01.txt").unwrap();
+        assert_eq!(
+            vec![(
+                "wlp2s0".to_string(),
+                Some(MacAddr([0x11, 0x22, 0x33, 0x44, 0x55, 0x66]))
+            )],
+            parse_iw_dev_interfaces(&data)
+        );
+    }
+
+    #[test]
+    fn should_build_iw_scan_args() {
+        assert_eq!(
+            vec!["dev", "wlp2s0", "scan"],
+            iw_scan_args("wlp2s0", None, &[])
+        );
+        assert_eq!(
+            vec!["dev", "wlp2s0", "scan", "ssid", "my network"],
+            iw_scan_args("wlp2s0", Some("my network"), &[])
+        );
 
-        // FIXME: should be a better way to create test fixtures
-        let path = PathBuf::from("tests/fixtures/iw/iw_dev_scan_01.txt");
-        let mut file = File::open(path).unwrap();
-        let mut filestr = String::new();
-        let _ = file.read_to_string(&mut filestr).unwrap();
+        let frequency = crate::channel_to_frequency(36, crate::Band::Band5GHz).unwrap();
+        assert_eq!(
+            vec!["dev", "wlp2s0", "scan", "freq", "5180"],
+            iw_scan_args("wlp2s0", None, &[&frequency.to_string()])
+        );
+        assert_eq!(
+            vec!["dev", "wlp2s0", "scan", "freq", "2412", "2437"],
+            iw_scan_args("wlp2s0", None, &["2412", "2437"])
+        );
+    }
+
+    #[test]
+    fn should_parse_iw_reg() {
+        let data = fs::read_to_string("tests/fixtures/iw/iw_reg_get_01.txt").unwrap();
+        let ranges = parse_iw_reg(&data);
+        let range = |start_mhz, end_mhz| FrequencyRange { start_mhz, end_mhz };
+        assert_eq!(
+            vec![
+                range(2400, 2483),
+                range(5150, 5250),
+                range(5250, 5350),
+                range(5470, 5725),
+                range(5725, 5875),
+                range(5945, 6425),
+                range(57000, 66000),
+            ],
+            ranges
+        );
+        assert!(parse_iw_reg("global\ncountry 00: DFS-UNSET\n").is_empty());
+
+        let frequencies = allowed_frequencies(&ranges);
+        // channels 1 to 13 but not 14, 2484 MHz is outside the 2.4 GHz rule
+        assert_eq!(2412, frequencies[0]);
+        assert!(frequencies.contains(&2472));
+        assert!(!frequencies.contains(&2484));
+        assert!(frequencies.contains(&5180));
+        // channel 169, 5845 MHz, is allowed but 177, 5885 MHz, isn't
+        assert!(frequencies.contains(&5845));
+        assert!(!frequencies.contains(&5885));
+        // 6 GHz up to channel 93, 6415 MHz
+        assert_eq!(Some(&6415), frequencies.last());
+        assert!(allowed_frequencies(&[]).is_empty());
+    }
+
+    #[test]
+    fn should_parse_iw_link() {
+        let link = "Connected to 22:33:44:55:66:77 (on wlp2s0)\n\tSSID: world\n\tfreq: 2412\n\tsignal: -42 dBm\n";
+        let wifi = parse_iw_link(link).unwrap();
+        assert_eq!("22:33:44:55:66:77", wifi.mac);
+        assert_eq!("world", wifi.ssid);
+        assert_eq!("1", wifi.channel);
+        assert_eq!("-42", wifi.signal_level);
+
+        assert_eq!(None, parse_iw_link("Not connected.\n"));
+    }
+
+    #[test]
+    fn should_parse_iw_link_rates() {
+        let link = fs::read_to_string("tests/fixtures/iw/iw_dev_link_01.txt").unwrap();
+        let wifi = parse_iw_link(&link).unwrap();
+        assert_eq!("36", wifi.channel);
+        assert_eq!(Some(650.0), wifi.tx_rate_mbps);
+        assert_eq!(Some(866.7), wifi.rx_rate_mbps);
+    }
+
+    #[test]
+    fn should_parse_proc_net_wireless() {
+        let data = fs::read_to_string("tests/fixtures/proc/net_wireless_01.txt").unwrap();
+        let links = parse_proc_net_wireless(&data);
+        assert_eq!(
+            vec![
+                WirelessLink {
+                    interface: "wlp2s0".to_string(),
+                    link_quality: 54.0,
+                    signal_dbm: -56.0,
+                },
+                WirelessLink {
+                    interface: "wlx00c0ca".to_string(),
+                    link_quality: 70.0,
+                    signal_dbm: -40.0,
+                },
+            ],
+            links
+        );
+
+        let wifi = links.into_iter().next().unwrap().into_wifi();
+        assert_eq!(Some(-56), wifi.signal_dbm());
+
+        // the link with the best quality
+        let wifi = proc_net_connection(&data).unwrap();
+        assert_eq!(Some(-40), wifi.signal_dbm());
+    }
+
+    #[test]
+    fn should_not_report_unassociated_interface() {
+        let data = fs::read_to_string("tests/fixtures/proc/net_wireless_idle.txt").unwrap();
+        assert_eq!(1, parse_proc_net_wireless(&data).len());
+        assert_eq!(None, proc_net_connection(&data));
+    }
+
+    #[test]
+    fn should_detect_iw_permission_error() {
+        use crate::output::check_status;
+        use crate::test_util::exit_status;
+
+        let command: Vec<String> = ["iw", "dev", "wlan0", "scan"].map(String::from).to_vec();
+        let stderr = b"command failed: Operation not permitted (-1)\n";
+        let error = check_status(&command, exit_status(255), b"", stderr).unwrap_err();
+        let error = to_wifi_error(iw_permission_error(error.into()));
+        match error {
+            WifiError::PermissionDenied { hint } => assert!(hint.contains("CAP_NET_ADMIN")),
+            error => panic!("expected PermissionDenied, got {error:?}"),
+        }
 
-        let result = parse_iw_dev_scan(&filestr).unwrap();
-        assert_eq!(expected[0], result[0]);
-        assert_eq!(expected[1], result[4]);
+        let stderr = b"command failed: Device or resource busy (-16)\n";
+        let error = check_status(&command, exit_status(240), b"", stderr).unwrap_err();
+        assert!(matches!(
+            to_wifi_error(iw_permission_error(error.into())),
+            WifiError::CommandFailed { .. }
+        ));
     }
 }
@@ -1,115 +1,335 @@
-use crate::Wifi;
+use crate::backend::{add_command_time, command_exists, ScanBackend};
+use crate::best_effort::to_wifi_error;
+use crate::output::{check_status, command_line, drain, max_output_bytes, parse_output, run};
+use crate::parse::{parse_airport_info, parse_systemprofiler_interfaces};
+use crate::{
+    AllInterfacesScan, BackendCapabilities, FullScan, InterfaceScan, Platform, Wifi, WifiError,
+};
 use anyhow::Context;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-/// Returns a list of WiFi hotspots in your area - (OSX/MacOS) uses `airport`
-pub(crate) fn scan() -> anyhow::Result<Vec<Wifi>> {
-    use std::process::Command;
+const AIRPORT_PATH: &str = "/System/Library/PrivateFrameworks/Apple80211.\
+                            framework/Versions/Current/Resources/airport";
 
-    let output = Command::new(
-        "/System/Library/PrivateFrameworks/Apple80211.\
-         framework/Versions/Current/Resources/airport",
-    )
-    .arg("-s")
-    .output()?;
+/// `airport` can block forever when the Wi-Fi power is off, so it is killed
+/// after this long.
+const AIRPORT_TIMEOUT: Duration = Duration::from_secs(10);
 
-    let data = String::from_utf8_lossy(&output.stdout);
+/// The macOS backends in the order `scan` tries them: `airport`, which
+/// tells the BSSIDs, falling back to `system_profiler` on releases without
+/// `airport`, then to `wdutil`
+pub(crate) fn builtin_backends() -> Vec<Arc<dyn ScanBackend>> {
+    vec![
+        Arc::new(Airport),
+        Arc::new(SystemProfiler),
+        Arc::new(Wdutil),
+    ]
+}
+
+struct SystemProfiler;
+
+impl ScanBackend for SystemProfiler {
+    fn name(&self) -> &str {
+        "system_profiler"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("system_profiler")
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        scan_using_systemprofiler().map_err(to_wifi_error)
+    }
 
-    parse_airport(&data)
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::MacOsSystemProfiler.capabilities()
+    }
 }
 
-fn parse_airport(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
-    let mut wifis: Vec<Wifi> = Vec::new();
-    let mut lines = network_list.lines();
-    let headers = match lines.next() {
-        Some(v) => v,
-        // return an empty list of WiFi if the network_list is empty
-        None => return Ok(vec![]),
-    };
+struct Airport;
+
+impl ScanBackend for Airport {
+    fn name(&self) -> &str {
+        "airport"
+    }
+
+    fn is_available(&self) -> bool {
+        Path::new(AIRPORT_PATH).exists()
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        scan_using_airport(Path::new(AIRPORT_PATH), AIRPORT_TIMEOUT).map_err(to_wifi_error)
+    }
 
-    let headers_string = String::from(headers);
-    let col_headers = ["BSSID", "RSSI", "CHANNEL", "HT", "SECURITY"]
-        .iter()
-        .map(|header| {
-            headers_string
-                .find(header)
-                .context("HeaderNotFound in {header:?}")
+    fn is_deprecated(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::MacOsAirport.capabilities()
+    }
+}
+
+/// `wdutil` can't scan, it only reports the connected network
+struct Wdutil;
+
+impl ScanBackend for Wdutil {
+    fn name(&self) -> &str {
+        "wdutil"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("wdutil")
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        scan_using_wdutil().map_err(to_wifi_error)
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::MacOsWdutil.capabilities()
+    }
+}
+
+/// Returns the network this machine is connected to - (OSX/MacOS) uses
+/// `system_profiler`, falling back to `airport -I`, then to `wdutil`
+pub(crate) fn current_connection() -> anyhow::Result<Option<Wifi>> {
+    let associated = |wifis: Vec<Wifi>| wifis.into_iter().find(|wifi| wifi.associated);
+    scan_using_systemprofiler()
+        .map(associated)
+        .or_else(|e| {
+            tracing::debug!("system_profiler failed ({e}), trying airport -I");
+            let output = run(Command::new(AIRPORT_PATH).arg("-I"))?;
+            parse_airport_info(&String::from_utf8_lossy(&output.stdout))
         })
-        .collect::<anyhow::Result<Vec<_>>>()?;
-    let col_mac = col_headers[0];
-    let col_rrsi = col_headers[1];
-    let col_channel = col_headers[2];
-    let col_ht = col_headers[3];
-    let col_security = col_headers[4];
+        .or_else(|e| {
+            tracing::debug!("airport -I failed ({e}), trying wdutil");
+            scan_using_wdutil().map(associated)
+        })
+}
 
-    for line in lines {
-        let ssid = &line[..col_mac].trim();
-        let mac = &line[col_mac..col_rrsi].trim();
-        let signal_level = &line[col_rrsi..col_channel].trim();
-        let channel = &line[col_channel..col_ht].trim();
-        let security = &line[col_security..].trim();
+/// Same as [`crate::scan`], there is no cache to read from
+pub(crate) fn scan_cached() -> anyhow::Result<Vec<Wifi>> {
+    crate::scan()
+}
+
+/// Returns the hotspots in your area per interface - (OSX/MacOS) uses `system_profiler`
+pub(crate) fn scan_with_interfaces() -> anyhow::Result<Vec<InterfaceScan>> {
+    let interfaces =
+        systemprofiler_output("-json").and_then(|output| parse_systemprofiler_interfaces(&output));
+    #[cfg(feature = "plist")]
+    let interfaces = interfaces.or_else(|e| {
+        tracing::debug!("system_profiler -json failed ({e}), trying -xml");
+        crate::parse::parse_systemprofiler_plist_interfaces(&systemprofiler_output("-xml")?)
+    });
+    interfaces
+}
+
+/// Returns the interfaces `system_profiler` reports - (OSX/MacOS) uses
+/// `system_profiler`
+pub(crate) fn interface_names() -> anyhow::Result<Vec<String>> {
+    Ok(scan_with_interfaces()?
+        .into_iter()
+        .map(|scan| scan.interface)
+        .collect())
+}
+
+/// Returns the hotspots `interface` saw, `system_profiler` scans all of
+/// them in one go - (OSX/MacOS) uses `system_profiler`
+pub(crate) fn scan_interface(interface: &str) -> anyhow::Result<Vec<Wifi>> {
+    scan_with_interfaces()?
+        .into_iter()
+        .find(|scan| scan.interface == interface)
+        .map(|scan| scan.networks)
+        .ok_or_else(|| WifiError::InterfaceNotFound(interface.to_string()).into())
+}
+
+/// Same as [`scan_with_interfaces`], `system_profiler` scans all
+/// interfaces in one go - (OSX/MacOS) uses `system_profiler`
+pub(crate) fn scan_all_interfaces(_max_threads: usize) -> anyhow::Result<AllInterfacesScan> {
+    Ok((scan_with_interfaces()?, Vec::new()))
+}
+
+/// Returns everything from one `system_profiler` run - (OSX/MacOS) uses
+/// `system_profiler`
+pub(crate) fn scan_full() -> anyhow::Result<FullScan> {
+    Ok(FullScan::from_interfaces(scan_with_interfaces()?))
+}
+
+/// `wdutil` needs root, so this fails for regular users
+fn scan_using_wdutil() -> anyhow::Result<Vec<Wifi>> {
+    let output = run(Command::new("wdutil").arg("info"))?;
+    let data = String::from_utf8_lossy(&output.stdout);
+    parse_output(Platform::MacOsWdutil, &data)
+}
+
+fn scan_using_systemprofiler() -> anyhow::Result<Vec<Wifi>> {
+    Ok(scan_with_interfaces()?
+        .into_iter()
+        .flat_map(|interface| interface.networks)
+        .collect())
+}
+
+/// Runs `system_profiler` printing in `format`, `-json` or `-xml`
+fn systemprofiler_output(format: &str) -> anyhow::Result<String> {
+    let output = run(Command::new("system_profiler").args(["SPAirPortDataType", format]))?;
 
-        wifis.push(Wifi {
-            mac: mac.to_string(),
-            ssid: ssid.to_string(),
-            channel: channel.to_string(),
-            signal_level: signal_level.to_string(),
-            security: security.to_string(),
-            ..Default::default()
-        });
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Runs `airport -s`, killing it and returning [`WifiError::Timeout`] if it
+/// hasn't exited within `timeout`.
+fn scan_using_airport(airport: &Path, timeout: Duration) -> anyhow::Result<Vec<Wifi>> {
+    let mut command = Command::new(airport);
+    command.arg("-s");
+    let start = Instant::now();
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // drain the pipes on separate threads so a chatty child can't fill them
+    // and block while we wait on it
+    let limit = max_output_bytes();
+    let stdout = drain(child.stdout.take().context("No stdout")?, limit);
+    let stderr = drain(child.stderr.take().context("No stderr")?, limit);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Err(WifiError::Timeout(timeout).into());
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    let join = |reader: thread::JoinHandle<std::io::Result<Vec<u8>>>| {
+        reader
+            .join()
+            .map_err(|_| anyhow::anyhow!("airport reader thread panicked"))
+    };
+    let output = join(stdout)??;
+    let mut errors = join(stderr)??;
+    add_command_time(start.elapsed());
+    if output.len() > limit {
+        return Err(WifiError::OutputTooLarge(limit).into());
     }
+    errors.truncate(limit);
+    let errors = strip_deprecation_warning(&errors);
+    check_status(&command_line(&command), status, &output, &errors)?;
+    let data = String::from_utf8_lossy(&output);
+
+    parse_output(Platform::MacOsAirport, &data)
+}
 
-    Ok(wifis)
+/// Drops the lines of the warning recent `airport` releases print on every
+/// run, e.g. `WARNING: The airport command line tool is deprecated and will
+/// be removed in a future release.` and its pointer to `wdutil`, so an
+/// otherwise silent run isn't taken for a failure.
+fn strip_deprecation_warning(stderr: &[u8]) -> Vec<u8> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .filter(|line| {
+            let line = line.to_lowercase();
+            let warning = line.contains("deprecated")
+                || line.contains("wireless diagnostics")
+                || line.contains("wdutil");
+            if warning {
+                tracing::debug!("ignoring airport warning: {line}");
+            }
+            !warning
+        })
+        .flat_map(|line| [line.as_bytes(), b"\n"].concat())
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::Read;
     use std::path::PathBuf;
 
-    #[test]
-    fn should_parse_airport() {
-        let mut expected: Vec<Wifi> = Vec::new();
-        expected.push(Wifi {
-            mac: "00:35:1a:90:56:03".to_string(),
-            ssid: "OurTest".to_string(),
-            channel: "112".to_string(),
-            signal_level: "-70".to_string(),
-            security: "WPA2(PSK/AES/AES)".to_string(),
-        });
+    const DEPRECATION_WARNING: &str = "WARNING: The airport command line tool is deprecated \
+        and will be removed in a future release.\nFor diagnosing Wi-Fi related issues, use \
+        the Wireless Diagnostics app or wdutil command line tool.";
 
-        expected.push(Wifi {
-            mac: "00:35:1a:90:56:00".to_string(),
-            ssid: "TEST-Wifi".to_string(),
-            channel: "1".to_string(),
-            signal_level: "-67".to_string(),
-            security: "WPA2(PSK/AES/AES)".to_string(),
-        });
+    /// Writes an executable `airport` running `body` into a directory of its
+    /// own, returning the directory and the script
+    fn airport_stub(name: &str, body: &str) -> (PathBuf, PathBuf) {
+        use std::os::unix::fs::PermissionsExt;
 
-        let path = PathBuf::from("tests/fixtures/airport/airport01.txt");
+        let dir = std::env::temp_dir().join(format!("wifiscanner-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("airport");
+        std::fs::write(&script, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        (dir, script)
+    }
 
-        let file_path = path.as_os_str();
+    #[test]
+    fn should_ignore_deprecation_warning() {
+        let fixture = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/airport/airport01.txt"
+        );
+        let timeout = Duration::from_secs(5);
+        let warn = format!("printf '%s\\n' '{DEPRECATION_WARNING}' >&2");
 
-        let mut file = File::open(&file_path).unwrap();
+        let (dir, script) = airport_stub("airport-warning", &format!("{warn}\ncat {fixture}"));
+        let wifis = scan_using_airport(&script, timeout).unwrap();
+        assert!(!wifis.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
 
-        let mut filestr = String::new();
-        let _ = file.read_to_string(&mut filestr).unwrap();
+        // nothing in range
+        let (dir, script) = airport_stub("airport-warning-empty", &warn);
+        assert!(scan_using_airport(&script, timeout).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
 
-        let result = parse_airport(&filestr).unwrap();
-        let last = result.len() - 1;
-        assert_eq!(expected[0], result[0]);
-        assert_eq!(expected[1], result[last]);
+        // genuine errors still fail the scan
+        let (dir, script) = airport_stub(
+            "airport-warning-error",
+            &format!("{warn}\necho 'Wi-Fi is turned off' >&2"),
+        );
+        let err = scan_using_airport(&script, timeout).unwrap_err();
+        match err.downcast_ref::<WifiError>() {
+            Some(WifiError::CommandFailed { reason, .. }) => {
+                assert_eq!("Wi-Fi is turned off", reason)
+            }
+            other => panic!("expected CommandFailed, got {other:?}"),
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    #[should_panic]
-    fn should_not_parse_other() {
-        let path = PathBuf::from("tests/fixtures/iw/iw_dev_01.txt");
-        let file_path = path.as_os_str();
-        let mut file = File::open(&file_path).unwrap();
-        let mut filestr = String::new();
-        file.read_to_string(&mut filestr).unwrap();
-        parse_airport(&filestr).unwrap(); // must panic
+    fn should_kill_airport_on_timeout() {
+        let (dir, script) = airport_stub(
+            "airport",
+            "echo $$ > \"$(dirname \"$0\")/pid\"\nexec sleep 30",
+        );
+        let pid_file = dir.join("pid");
+
+        let timeout = Duration::from_millis(500);
+        let err = scan_using_airport(&script, timeout).unwrap_err();
+        assert_eq!(
+            Some(&WifiError::Timeout(timeout)),
+            err.downcast_ref::<WifiError>()
+        );
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let alive = Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!alive.success(), "airport should have been killed");
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
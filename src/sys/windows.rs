@@ -1,34 +1,129 @@
 #![cfg(windows)]
 use regex::Regex;
 
-use anyhow::Context;
 use std::os::windows::process::CommandExt;
 use std::process::Command;
+use std::sync::Arc;
 
-use crate::Wifi;
+use crate::backend::ScanBackend;
+use crate::best_effort::to_wifi_error;
+use crate::interfaces::ensure_interface;
+use crate::output::{decode_text, parse_output, run};
+use crate::{
+    AllInterfacesScan, BackendCapabilities, FullScan, InterfaceScan, Platform, Wifi, WifiError,
+};
 
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+pub(crate) fn builtin_backends() -> Vec<Arc<dyn ScanBackend>> {
+    vec![Arc::new(Netsh)]
+}
+
+struct Netsh;
+
+impl ScanBackend for Netsh {
+    fn name(&self) -> &str {
+        "netsh"
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        scan_netsh().map_err(to_wifi_error)
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        Platform::Windows.capabilities()
+    }
+}
+
 /// Returns a list of WiFi hotspots in your area - (Windows) uses `netsh`
-pub fn scan() -> anyhow::Result<Vec<Wifi>> {
-    let output = Command::new("netsh.exe")
+fn scan_netsh() -> anyhow::Result<Vec<Wifi>> {
+    let output = run(Command::new("netsh.exe").args(["wlan", "show", "networks", "mode=Bssid"]))?;
+
+    let data = decode_text(&output.stdout);
+
+    parse_output(Platform::Windows, &data)
+}
+
+/// Returns the interfaces `netsh` lists - (Windows) uses `netsh`
+pub(crate) fn interface_names() -> anyhow::Result<Vec<String>> {
+    Ok(parse_netsh_interface_names(&netsh_interfaces_output()?))
+}
+
+/// Returns the hotspots seen by `interface` - (Windows) uses `netsh`
+pub(crate) fn scan_interface(interface: &str) -> anyhow::Result<Vec<Wifi>> {
+    ensure_interface(&interface_names()?, interface)?;
+    let output = run(Command::new("netsh.exe")
         .args(["wlan", "show", "networks", "mode=Bssid"])
-        .output()?;
+        .arg(format!("interface={interface}"))
+        .creation_flags(CREATE_NO_WINDOW))?;
+
+    let data = decode_text(&output.stdout);
+
+    parse_output(Platform::Windows, &data)
+}
+
+/// Same as [`crate::scan`], there is no cache to read from
+pub(crate) fn scan_cached() -> anyhow::Result<Vec<Wifi>> {
+    crate::scan()
+}
 
-    let data = String::from_utf8_lossy(&output.stdout);
+/// Returns the hotspots in your area under a single unnamed interface, as
+/// `netsh` doesn't say which interface saw them - (Windows) uses `netsh`
+pub(crate) fn scan_with_interfaces() -> anyhow::Result<Vec<InterfaceScan>> {
+    Ok(vec![InterfaceScan {
+        networks: scan_netsh()?,
+        ..Default::default()
+    }])
+}
 
-    parse_netsh_network_list(&data)
+/// Same as [`scan_with_interfaces`], `netsh` doesn't scan per interface -
+/// (Windows) uses `netsh`
+pub(crate) fn scan_all_interfaces(_max_threads: usize) -> anyhow::Result<AllInterfacesScan> {
+    Ok((scan_with_interfaces()?, Vec::new()))
 }
 
 /// Returns a list of WiFi interfaces - (Windows) uses `netsh`  
 pub fn show_interfaces() -> anyhow::Result<Vec<Wifi>> {
-    let output = Command::new("netsh.exe")
+    parse_netsh_interface_list(&netsh_interfaces_output()?)
+}
+
+fn netsh_interfaces_output() -> anyhow::Result<String> {
+    let output = run(Command::new("netsh.exe")
         .args(["wlan", "show", "interfaces"])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output()?;
+        .creation_flags(CREATE_NO_WINDOW))?;
+
+    Ok(decode_text(&output.stdout).into_owned())
+}
+
+/// Returns the network the first connected interface is using - (Windows) uses `netsh`
+pub(crate) fn current_connection() -> anyhow::Result<Option<Wifi>> {
+    Ok(connected(show_interfaces()?))
+}
 
-    let data = String::from_utf8_lossy(&output.stdout);
-    parse_netsh_interface_list(&data)
+fn connected(interfaces: Vec<Wifi>) -> Option<Wifi> {
+    interfaces.into_iter().find(|wifi| !wifi.ssid.is_empty())
+}
+
+/// Returns the scan, the connected network and the interfaces, the last two
+/// from a single `netsh wlan show interfaces` - (Windows) uses `netsh`
+pub(crate) fn scan_full() -> anyhow::Result<FullScan> {
+    let interfaces = netsh_interfaces_output()?;
+    Ok(FullScan {
+        networks: crate::scan()?,
+        connected: connected(parse_netsh_interface_list(&interfaces)?),
+        interfaces: parse_netsh_interface_names(&interfaces),
+    })
+}
+
+/// Returns the value of every `Name : ...` line of `netsh wlan show interfaces`
+fn parse_netsh_interface_names(interface_list: &str) -> Vec<String> {
+    interface_list
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "Name").then(|| value.trim().to_string())
+        })
+        .collect()
 }
 
 fn parse_netsh_interface_list(interface_list: &str) -> anyhow::Result<Vec<Wifi>> {
@@ -43,9 +138,15 @@ fn parse_netsh_interface_list(interface_list: &str) -> anyhow::Result<Vec<Wifi>>
         let mut wifi_security = String::new();
         let mut wifi_rssi = 0i32;
         let mut wifi_channel = String::new();
+        let mut rates = (None, None);
 
         for line in block.lines() {
-            if line.contains("Authentication") {
+            let rate = || line.split_once(':')?.1.trim().parse().ok();
+            if line.contains("Transmit rate") {
+                rates.0 = rate();
+            } else if line.contains("Receive rate") {
+                rates.1 = rate();
+            } else if line.contains("Authentication") {
                 wifi_security = line.split(':').nth(1).unwrap_or("").trim().to_string();
             } else if line.contains("BSSID") {
                 wifi_bssid = line
@@ -81,104 +182,25 @@ fn parse_netsh_interface_list(interface_list: &str) -> anyhow::Result<Vec<Wifi>>
             channel: wifi_channel.to_string(),
             signal_level: wifi_rssi.to_string(),
             security: wifi_security.to_string(),
+            tx_rate_mbps: rates.0,
+            rx_rate_mbps: rates.1,
+            ..Default::default()
         });
     }
     Ok(wifis)
 }
 
-fn parse_netsh_network_list(network_list: &str) -> anyhow::Result<Vec<Wifi>> {
-    let mut wifis = Vec::new();
-
-    // Regex for matching split, SSID and MAC, since these aren't pulled directly
-    let split_regex = Regex::new("\nSSID")?;
-    let ssid_regex = Regex::new("^ [0-9]* : ")?;
-    let mac_regex = Regex::new("[a-fA-F0-9:]{17}")?;
-
-    for block in split_regex.split(network_list) {
-        let mut wifi_macs = Vec::new();
-        let mut wifi_ssid = String::new();
-        let mut wifi_channels = Vec::new();
-        let mut wifi_rssi = Vec::new();
-        let mut wifi_security = String::new();
-
-        for line in block.lines() {
-            if ssid_regex.is_match(line) {
-                wifi_ssid = line.split(':').nth(1).unwrap_or("").trim().to_string();
-            } else if line.contains("Authentication") {
-                wifi_security = line.split(':').nth(1).unwrap_or("").trim().to_string();
-            } else if line.contains("BSSID") {
-                let captures = mac_regex.captures(line).context("RegexSyntaxError")?;
-                wifi_macs.push(captures.get(0).context("SyntaxRegexError")?);
-            } else if line.contains("Signal") {
-                let percent = line.split(':').nth(1).unwrap_or("").trim().replace('%', "");
-                let percent: i32 = percent.parse()?;
-                wifi_rssi.push(percent / 2 - 100);
-            } else if line.contains("Channel") {
-                wifi_channels.push(line.split(':').nth(1).unwrap_or("").trim().to_string());
-            }
-        }
-
-        for (mac, channel, rssi) in izip!(wifi_macs, wifi_channels, wifi_rssi) {
-            wifis.push(Wifi {
-                mac: mac.as_str().to_string(),
-                ssid: wifi_ssid.to_string(),
-                channel: channel.to_string(),
-                signal_level: rssi.to_string(),
-                security: wifi_security.to_string(),
-            });
-        }
-    }
-
-    Ok(wifis)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn should_parse_netsh() {
-        use std::fs;
-
-        // Note: formula for % to dBm is (% / 100) - 100
-        let expected = vec![
-            Wifi {
-                mac: "ab:cd:ef:01:23:45".to_string(),
-                ssid: "Vodafone Hotspot".to_string(),
-                channel: "6".to_string(),
-                signal_level: "-92".to_string(),
-                security: "Open".to_string(),
-            },
-            Wifi {
-                mac: "ab:cd:ef:01:23:45".to_string(),
-                ssid: "Vodafone Hotspot".to_string(),
-                channel: "6".to_string(),
-                signal_level: "-73".to_string(),
-                security: "Open".to_string(),
-            },
-            Wifi {
-                mac: "ab:cd:ef:01:23:45".to_string(),
-                ssid: "EdaBox".to_string(),
-                channel: "11".to_string(),
-                signal_level: "-82".to_string(),
-                security: "WPA2-Personal".to_string(),
-            },
-            Wifi {
-                mac: "ab:cd:ef:01:23:45".to_string(),
-                ssid: "FRITZ!Box 2345 Cable".to_string(),
-                channel: "1".to_string(),
-                signal_level: "-50".to_string(),
-                security: "WPA2-Personal".to_string(),
-            },
-        ];
-
-        // Load test fixtures
-        let fixture = fs::read_to_string("tests/fixtures/netsh/netsh01_windows81.txt").unwrap();
-
-        let result = parse_netsh_network_list(&fixture).unwrap();
-        assert_eq!(expected[0], result[0]);
-        assert_eq!(expected[1], result[1]);
-        assert_eq!(expected[2], result[2]);
-        assert_eq!(expected[3], result[3]);
+    fn should_parse_netsh_interface_rates() {
+        let data = std::fs::read_to_string("tests/fixtures/netsh/netsh_interfaces_01.txt").unwrap();
+        let wifi = connected(parse_netsh_interface_list(&data).unwrap()).unwrap();
+        assert_eq!("Home Network", wifi.ssid);
+        assert_eq!("00:11:22:33:44:0a", wifi.mac);
+        assert_eq!(Some(650.0), wifi.tx_rate_mbps);
+        assert_eq!(Some(866.7), wifi.rx_rate_mbps);
     }
 }
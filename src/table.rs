@@ -0,0 +1,83 @@
+use crate::fields::FIELDS;
+use crate::Wifi;
+
+/// Renders networks as a box-drawn table with a header row, or a
+/// `no networks found` line when there are none. The columns are the
+/// [`Wifi::field_names`], in that order, less those empty for every network.
+pub fn format_pretty_table(wifis: &[Wifi]) -> String {
+    if wifis.is_empty() {
+        return "no networks found\n".to_string();
+    }
+
+    let values: Vec<Vec<String>> = wifis.iter().map(Wifi::field_values).collect();
+    let columns: Vec<usize> = (0..FIELDS.len())
+        .filter(|&column| values.iter().any(|row| !row[column].is_empty()))
+        .collect();
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|&column| FIELDS[column].title.to_string())
+        .collect();
+    let rows: Vec<Vec<String>> = values
+        .iter()
+        .map(|row| columns.iter().map(|&column| row[column].clone()).collect())
+        .collect();
+
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let rule = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+        format!("{left}{}{right}\n", segments.join(middle))
+    };
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {cell:width$} "))
+            .collect();
+        format!("│{}│\n", cells.join("│"))
+    };
+
+    let mut out = rule("┌", "┬", "┐");
+    out.push_str(&line(&headers));
+    out.push_str(&rule("├", "┼", "┤"));
+    for row in &rows {
+        out.push_str(&line(row));
+    }
+    out.push_str(&rule("└", "┴", "┘"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_nmcli;
+
+    #[test]
+    fn should_format_pretty_table() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli01.txt").unwrap();
+        let wifis = parse_nmcli(&data).unwrap();
+
+        let table = format_pretty_table(&wifis[..1]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            vec![
+                "┌───────────────────┬───────┬─────────┬────────┬──────────┬───────────┬─────────┐",
+                "│ BSSID             │ SSID  │ Channel │ Signal │ Security │ Frequency │ Band    │",
+                "├───────────────────┼───────┼─────────┼────────┼──────────┼───────────┼─────────┤",
+                "│ 11:22:33:44:55:66 │ hello │ 6       │ -58    │ WPA2     │ 2437      │ 2.4 GHz │",
+                "└───────────────────┴───────┴─────────┴────────┴──────────┴───────────┴─────────┘",
+            ],
+            lines
+        );
+
+        assert_eq!("no networks found\n", format_pretty_table(&[]));
+    }
+}
@@ -0,0 +1,166 @@
+//! Helpers for testing code that uses this crate.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{ScanBackend, Wifi, WifiError};
+
+/// Asserts both slices hold the same networks in the same order, comparing
+/// them with [`Wifi::eq_ignoring_signal`].
+#[track_caller]
+pub fn assert_networks_eq_ignoring_signal(a: &[Wifi], b: &[Wifi]) {
+    assert_eq!(a.len(), b.len(), "different number of networks");
+    for (i, (a, b)) in a.iter().zip(b).enumerate() {
+        assert!(
+            a.eq_ignoring_signal(b),
+            "networks at index {i} differ:\n{a:?}\n{b:?}"
+        );
+    }
+}
+
+/// Backend replaying a fixed sequence of scans, one per call to
+/// [`ScanBackend::scan`], so an app reacting to networks appearing and
+/// disappearing can be tested deterministically. After the last scan it
+/// keeps returning that one, or starts over with [`ReplayBackend::cycle`].
+/// [`ReplayBackend::fail_at`] makes some of the calls fail, without using up
+/// a scan.
+///
+/// ```
+/// # use wifiscanner::{register_backend, ReplayBackend, Wifi};
+/// let home = Wifi { ssid: "home".to_string(), ..Default::default() };
+/// register_backend(ReplayBackend::new(vec![vec![], vec![home]]));
+/// ```
+#[derive(Debug)]
+pub struct ReplayBackend {
+    snapshots: Vec<Vec<Wifi>>,
+    cycle: bool,
+    failures: Vec<usize>,
+    calls: AtomicUsize,
+    next: AtomicUsize,
+}
+
+impl ReplayBackend {
+    pub fn new(snapshots: Vec<Vec<Wifi>>) -> Self {
+        ReplayBackend {
+            snapshots,
+            cycle: false,
+            failures: Vec::new(),
+            calls: AtomicUsize::new(0),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Starts over with the first scan after the last one
+    pub fn cycle(mut self, cycle: bool) -> Self {
+        self.cycle = cycle;
+        self
+    }
+
+    /// Fails the `call`th call to [`ScanBackend::scan`], counting from 0,
+    /// with [`WifiError::CommandNotFound`]. The next call returns the scan
+    /// this one would have.
+    pub fn fail_at(mut self, call: usize) -> Self {
+        self.failures.push(call);
+        self
+    }
+}
+
+impl ScanBackend for ReplayBackend {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    fn scan(&self) -> Result<Vec<Wifi>, WifiError> {
+        let call = self.calls.fetch_add(1, Ordering::Relaxed);
+        if self.failures.contains(&call) {
+            return Err(WifiError::CommandNotFound);
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        let count = self.snapshots.len();
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let index = if self.cycle {
+            index % count
+        } else {
+            index.min(count - 1)
+        };
+        Ok(self.snapshots[index].clone())
+    }
+}
+
+/// Returns the status a process exiting with `code` has.
+#[cfg(all(test, unix))]
+pub(crate) fn exit_status(code: i32) -> std::process::ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    std::process::ExitStatus::from_raw(code << 8)
+}
+
+/// Returns the status a process exiting with `code` has.
+#[cfg(all(test, windows))]
+pub(crate) fn exit_status(code: i32) -> std::process::ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    std::process::ExitStatus::from_raw(code as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wifi(ssid: &str, signal_level: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_replay_scans() {
+        let cafe = Wifi {
+            mac: "00:00:00:00:00:01".to_string(),
+            ssid: "cafe".to_string(),
+            ..Default::default()
+        };
+        let snapshots = vec![vec![], vec![cafe.clone()], vec![]];
+
+        let replay = ReplayBackend::new(snapshots.clone());
+        let scans: Vec<Vec<Wifi>> = (0..4).map(|_| replay.scan().unwrap()).collect();
+        let appeared = crate::diff(&scans[0], &scans[1]);
+        assert_eq!(vec![cafe.clone()], appeared.appeared);
+        assert!(appeared.disappeared.is_empty());
+        let disappeared = crate::diff(&scans[1], &scans[2]);
+        assert_eq!(vec![cafe.clone()], disappeared.disappeared);
+        assert!(disappeared.appeared.is_empty());
+        // stays on the last scan
+        assert!(scans[3].is_empty());
+
+        let replay = ReplayBackend::new(snapshots).cycle(true);
+        let sizes: Vec<usize> = (0..4).map(|_| replay.scan().unwrap().len()).collect();
+        assert_eq!(vec![0, 1, 0, 0], sizes);
+        assert_eq!(vec![cafe.clone()], replay.scan().unwrap());
+
+        assert!(ReplayBackend::new(vec![]).scan().unwrap().is_empty());
+
+        // a failure doesn't use up a scan
+        let replay = ReplayBackend::new(vec![vec![], vec![cafe.clone()]]).fail_at(1);
+        let results: Vec<_> = (0..3).map(|_| replay.scan().ok()).collect();
+        assert_eq!(vec![Some(vec![]), None, Some(vec![cafe])], results);
+    }
+
+    #[test]
+    fn should_accept_signal_jitter() {
+        assert_networks_eq_ignoring_signal(
+            &[wifi("a", "-40"), wifi("b", "-70")],
+            &[wifi("a", "-42"), wifi("b", "-69")],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "networks at index 1 differ")]
+    fn should_reject_other_differences() {
+        assert_networks_eq_ignoring_signal(
+            &[wifi("a", "-40"), wifi("b", "-70")],
+            &[wifi("a", "-40"), wifi("c", "-70")],
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use crate::{is_valid_channel, Band, MacAddr, Wifi};
+
+/// A sign that a scan result is wrong, found by [`validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// the same BSSID was reported with different SSIDs, either a parsing
+    /// error or someone spoofing the access point. The SSIDs are in the order
+    /// they were seen.
+    ConflictingSsidForBssid { mac: MacAddr, ssids: Vec<String> },
+    /// the channel number isn't a channel on any band, `mac` and `channel`
+    /// are as the backend reported them
+    ImpossibleChannel { mac: String, channel: String },
+}
+
+/// Checks a scan for entries that can't be right. Hidden networks don't
+/// conflict with the SSID their BSSID is otherwise seen with, and entries
+/// without a channel number aren't checked for an impossible channel.
+///
+/// The impossible channels come first, in scan order, then the conflicts in
+/// the order their BSSID first appeared.
+pub fn validate(wifis: &[Wifi]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut order: Vec<MacAddr> = Vec::new();
+    let mut ssids: HashMap<MacAddr, Vec<String>> = HashMap::new();
+    for wifi in wifis {
+        if let Some(channel) = wifi.channel_number() {
+            let bands = [Band::Band2_4GHz, Band::Band5GHz, Band::Band6GHz];
+            if !bands.iter().any(|&band| is_valid_channel(channel, band)) {
+                issues.push(ValidationIssue::ImpossibleChannel {
+                    mac: wifi.mac.clone(),
+                    channel: wifi.channel.clone(),
+                });
+            }
+        }
+        let Some(mac) = wifi.mac_addr() else {
+            continue;
+        };
+        if wifi.is_hidden() {
+            continue;
+        }
+        let seen = ssids.entry(mac).or_insert_with(|| {
+            order.push(mac);
+            Vec::new()
+        });
+        if !seen.contains(&wifi.ssid) {
+            seen.push(wifi.ssid.clone());
+        }
+    }
+    for mac in order {
+        let ssids = ssids.remove(&mac).unwrap_or_default();
+        if ssids.len() > 1 {
+            issues.push(ValidationIssue::ConflictingSsidForBssid { mac, ssids });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_nmcli;
+
+    #[test]
+    fn should_report_conflicts_and_impossible_channels() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_conflicting.txt").unwrap();
+        let issues = validate(&parse_nmcli(&data).unwrap());
+        assert_eq!(
+            vec![
+                ValidationIssue::ImpossibleChannel {
+                    mac: "AA:00:00:00:00:03".to_string(),
+                    channel: "15".to_string(),
+                },
+                ValidationIssue::ConflictingSsidForBssid {
+                    mac: "aa:00:00:00:00:01".parse().unwrap(),
+                    ssids: vec![
+                        "office".to_string(),
+                        "guest".to_string(),
+                        "Office".to_string(),
+                    ],
+                },
+            ],
+            issues
+        );
+
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli01.txt").unwrap();
+        assert!(validate(&parse_nmcli(&data).unwrap()).is_empty());
+    }
+}
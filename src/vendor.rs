@@ -0,0 +1,393 @@
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use anyhow::Context;
+
+use crate::{MacAddr, Wifi};
+
+/// A small built-in subset of the IEEE OUI registry, covering the common
+/// access point manufacturers. Sorted by OUI for the binary search.
+const OUI_VENDORS: &[([u8; 3], &str)] = &[
+    ([0x00, 0x00, 0x0c], "Cisco Systems"),
+    ([0x00, 0x03, 0x93], "Apple"),
+    ([0x00, 0x04, 0x0e], "AVM"),
+    ([0x00, 0x09, 0x5b], "Netgear"),
+    ([0x00, 0x0a, 0x95], "Apple"),
+    ([0x00, 0x0b, 0x86], "Aruba Networks"),
+    ([0x00, 0x14, 0x6c], "Netgear"),
+    ([0x00, 0x15, 0x6d], "Ubiquiti"),
+    ([0x00, 0x17, 0xf2], "Apple"),
+    ([0x00, 0x18, 0x0a], "Cisco Meraki"),
+    ([0x00, 0x1a, 0x1e], "Aruba Networks"),
+    ([0x00, 0x1b, 0x2f], "Netgear"),
+    ([0x00, 0x1e, 0x2a], "Netgear"),
+    ([0x00, 0x1e, 0xc2], "Apple"),
+    ([0x00, 0x22, 0x3f], "Netgear"),
+    ([0x00, 0x24, 0xb2], "Netgear"),
+    ([0x00, 0x25, 0xc4], "Ruckus Wireless"),
+    ([0x00, 0x27, 0x22], "Ubiquiti"),
+    ([0x04, 0x18, 0xd6], "Ubiquiti"),
+    ([0x04, 0xd4, 0xc4], "ASUSTek"),
+    ([0x0c, 0x8d, 0xdb], "Cisco Meraki"),
+    ([0x14, 0xcc, 0x20], "TP-Link"),
+    ([0x18, 0xe8, 0x29], "Ubiquiti"),
+    ([0x20, 0x4e, 0x7f], "Netgear"),
+    ([0x24, 0x5a, 0x4c], "Ubiquiti"),
+    ([0x24, 0x65, 0x11], "AVM"),
+    ([0x24, 0xa4, 0x3c], "Ubiquiti"),
+    ([0x24, 0xde, 0xc6], "Aruba Networks"),
+    ([0x28, 0xc6, 0x8e], "Netgear"),
+    ([0x28, 0xcf, 0xe9], "Apple"),
+    ([0x2c, 0x4d, 0x54], "ASUSTek"),
+    ([0x2c, 0x5d, 0x93], "Ruckus Wireless"),
+    ([0x30, 0xb5, 0xc2], "TP-Link"),
+    ([0x38, 0x10, 0xd5], "AVM"),
+    ([0x3c, 0x07, 0x54], "Apple"),
+    ([0x3c, 0x5a, 0xb4], "Google"),
+    ([0x44, 0xd9, 0xe7], "Ubiquiti"),
+    ([0x50, 0x46, 0x5d], "ASUSTek"),
+    ([0x50, 0xc7, 0xbf], "TP-Link"),
+    ([0x54, 0x60, 0x09], "Google"),
+    ([0x58, 0xb6, 0x33], "Ruckus Wireless"),
+    ([0x60, 0xe3, 0x27], "TP-Link"),
+    ([0x64, 0x70, 0x02], "TP-Link"),
+    ([0x68, 0x72, 0x51], "Ubiquiti"),
+    ([0x6c, 0xf3, 0x7f], "Aruba Networks"),
+    ([0x70, 0xa7, 0x41], "Ubiquiti"),
+    ([0x74, 0x83, 0xc2], "Ubiquiti"),
+    ([0x78, 0x8a, 0x20], "Ubiquiti"),
+    ([0x7c, 0xff, 0x4d], "AVM"),
+    ([0x80, 0x2a, 0xa8], "Ubiquiti"),
+    ([0x88, 0x15, 0x44], "Cisco Meraki"),
+    ([0x94, 0xb4, 0x0f], "Aruba Networks"),
+    ([0x98, 0xda, 0xc4], "TP-Link"),
+    ([0xa0, 0x40, 0xa0], "Netgear"),
+    ([0xa0, 0xf3, 0xc1], "TP-Link"),
+    ([0xa4, 0x83, 0xe7], "Apple"),
+    ([0xac, 0x17, 0xc8], "Cisco Meraki"),
+    ([0xac, 0x22, 0x0b], "ASUSTek"),
+    ([0xac, 0x8b, 0xa9], "Ubiquiti"),
+    ([0xb4, 0xfb, 0xe4], "Ubiquiti"),
+    ([0xc0, 0x25, 0x06], "AVM"),
+    ([0xc0, 0x4a, 0x00], "TP-Link"),
+    ([0xc4, 0x10, 0x8a], "Ruckus Wireless"),
+    ([0xd8, 0xc7, 0xc8], "Aruba Networks"),
+    ([0xdc, 0x9f, 0xdb], "Ubiquiti"),
+    ([0xe0, 0x28, 0x6d], "AVM"),
+    ([0xe0, 0x55, 0x3d], "Cisco Meraki"),
+    ([0xe0, 0x63, 0xda], "Ubiquiti"),
+    ([0xe0, 0x91, 0xf5], "Netgear"),
+    ([0xec, 0x08, 0x6b], "TP-Link"),
+    ([0xec, 0x58, 0xea], "Ruckus Wireless"),
+    ([0xf0, 0x18, 0x98], "Apple"),
+    ([0xf0, 0x9f, 0xc2], "Ubiquiti"),
+    ([0xf4, 0xf2, 0x6d], "TP-Link"),
+    ([0xf4, 0xf5, 0xd8], "Google"),
+    ([0xf4, 0xf5, 0xe8], "Google"),
+    ([0xf8, 0x32, 0xe4], "ASUSTek"),
+    ([0xfc, 0xec, 0xda], "Ubiquiti"),
+];
+
+/// An OUI table, sorted for the binary search
+type OuiTable = Vec<([u8; 3], Box<str>)>;
+
+/// Where [`set_oui_database`] keeps the OUIs it loaded
+type OuiStore = RwLock<Option<Arc<OuiTable>>>;
+
+/// OUIs loaded by [`set_oui_database`]
+static LOADED_VENDORS: OuiStore = RwLock::new(None);
+
+/// Loads the OUI registry at `path` for [`MacAddr::vendor`] to look up,
+/// before the built-in table. Loading another file replaces the entries
+/// loaded before, a call that fails keeps them. Returns how many entries
+/// were loaded.
+///
+/// The file is the IEEE MA-L registry, either `oui.txt` or `oui.csv` from
+/// <https://standards-oui.ieee.org>. Of `oui.txt` only the `(hex)` lines
+/// are read, e.g. `00-00-0C   (hex)\t\tCisco Systems, Inc`, of `oui.csv`
+/// the rows after the header, e.g. `MA-L,00000C,"Cisco Systems, Inc",...`.
+/// Other lines are skipped.
+pub fn set_oui_database(path: &Path) -> anyhow::Result<usize> {
+    load_oui_database(&LOADED_VENDORS, path)
+}
+
+fn load_oui_database(loaded: &OuiStore, path: &Path) -> anyhow::Result<usize> {
+    let data =
+        std::fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+    let mut entries: OuiTable = parse_oui_database(&data)
+        .into_iter()
+        .map(|(oui, name)| (oui, name.into_boxed_str()))
+        .collect();
+    anyhow::ensure!(
+        !entries.is_empty(),
+        "{} holds no OUI entries",
+        path.display()
+    );
+    // stable, so the first entry of a repeated OUI wins
+    entries.sort_by_key(|(oui, _)| *oui);
+    entries.dedup_by_key(|(oui, _)| *oui);
+    let count = entries.len();
+    *loaded.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(entries));
+    Ok(count)
+}
+
+/// Looks `oui` up in a table sorted by OUI
+fn look_up<S: AsRef<str>>(table: &[([u8; 3], S)], oui: [u8; 3]) -> Option<&str> {
+    table
+        .binary_search_by_key(&oui, |(oui, _)| *oui)
+        .ok()
+        .map(|i| table[i].1.as_ref())
+}
+
+/// Reads the OUIs and vendor names of an `oui.txt` or `oui.csv`, see
+/// [`set_oui_database`]
+fn parse_oui_database(data: &str) -> Vec<([u8; 3], String)> {
+    data.lines()
+        .filter_map(|line| {
+            let (oui, name) = match line.split_once("(hex)") {
+                Some((oui, name)) => (oui.trim().replace('-', ""), name.trim().to_string()),
+                None => {
+                    let fields = csv_fields(line);
+                    match &fields[..] {
+                        [registry, oui, name, ..] if registry == "MA-L" => {
+                            (oui.clone(), name.trim().to_string())
+                        }
+                        _ => return None,
+                    }
+                }
+            };
+            if oui.len() != 6 || name.is_empty() {
+                return None;
+            }
+            let octet = |at: usize| u8::from_str_radix(oui.get(at..at + 2)?, 16).ok();
+            Some(([octet(0)?, octet(2)?, octet(4)?], name))
+        })
+        .collect()
+}
+
+/// Splits a CSV line, unquoting the fields in double quotes
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+impl MacAddr {
+    /// Returns the first three octets, the manufacturer's identifier
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    /// Looks up the manufacturer in the registry loaded by
+    /// [`set_oui_database`], then in the built-in OUI table. Locally
+    /// administered addresses and manufacturers missing from both give
+    /// `None`.
+    pub fn vendor(&self) -> Option<Cow<'static, str>> {
+        self.vendor_in(&LOADED_VENDORS)
+    }
+
+    fn vendor_in(&self, loaded: &OuiStore) -> Option<Cow<'static, str>> {
+        if self.is_locally_administered() {
+            return None;
+        }
+        let oui = self.oui();
+        let loaded = loaded
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(name) = loaded.as_deref().and_then(|table| look_up(table, oui)) {
+            return Some(Cow::Owned(name.to_string()));
+        }
+        look_up(OUI_VENDORS, oui).map(Cow::Borrowed)
+    }
+}
+
+impl Wifi {
+    /// Returns the manufacturer of the hotspot, see [`MacAddr::vendor`]
+    pub fn vendor(&self) -> Option<Cow<'static, str>> {
+        self.mac_addr()?.vendor()
+    }
+
+    /// Whether the manufacturer contains `name`, ignoring case
+    pub(crate) fn has_vendor(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.vendor()
+            .is_some_and(|vendor| vendor.to_lowercase().contains(&name))
+    }
+}
+
+/// Scans and keeps the hotspots whose manufacturer contains `name`, ignoring
+/// case, e.g. `ubiquiti`. Hotspots without a MAC address or with an unknown
+/// manufacturer are left out.
+pub fn scan_by_vendor(name: &str) -> anyhow::Result<Vec<Wifi>> {
+    Ok(filter_by_vendor(crate::scan()?, name))
+}
+
+fn filter_by_vendor(wifis: Vec<Wifi>, name: &str) -> Vec<Wifi> {
+    wifis
+        .into_iter()
+        .filter(|wifi| wifi.has_vendor(name))
+        .collect()
+}
+
+/// Name of the [`group_by_vendor`] group of hotspots whose manufacturer
+/// isn't known
+pub const UNKNOWN_VENDOR: &str = "Unknown vendor";
+
+/// Groups the hotspots by manufacturer (see [`Wifi::vendor`]), the biggest
+/// group first and groups of the same size by name. Hotspots without a MAC
+/// address or with an unknown manufacturer go to the [`UNKNOWN_VENDOR`]
+/// group. The hotspots of a group keep their order.
+pub fn group_by_vendor(wifis: &[Wifi]) -> Vec<(String, Vec<Wifi>)> {
+    let mut groups: Vec<(String, Vec<Wifi>)> = Vec::new();
+    for wifi in wifis {
+        let vendor = wifi.vendor().unwrap_or(Cow::Borrowed(UNKNOWN_VENDOR));
+        match groups.iter_mut().find(|(name, _)| *name == vendor) {
+            Some((_, members)) => members.push(wifi.clone()),
+            None => groups.push((vendor.to_string(), vec![wifi.clone()])),
+        }
+    }
+    groups.sort_by(|(a, a_members), (b, b_members)| {
+        b_members.len().cmp(&a_members.len()).then_with(|| a.cmp(b))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_nmcli;
+
+    #[test]
+    fn should_keep_oui_table_sorted() {
+        assert!(OUI_VENDORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn should_look_up_vendor() {
+        let mac: MacAddr = "24:a4:3c:01:02:03".parse().unwrap();
+        assert_eq!(Some("Ubiquiti"), mac.vendor().as_deref());
+        // same OUI with the locally administered bit set
+        let mac: MacAddr = "26:a4:3c:01:02:03".parse().unwrap();
+        assert_eq!(None, mac.vendor());
+    }
+
+    #[test]
+    fn should_parse_oui_database() {
+        let txt = std::fs::read_to_string("tests/fixtures/oui/oui.txt").unwrap();
+        let csv = std::fs::read_to_string("tests/fixtures/oui/oui.csv").unwrap();
+        let expected = vec![
+            ([0x00, 0x1b, 0xd4], "Cisco Systems, Inc".to_string()),
+            (
+                [0x3c, 0x84, 0x6a],
+                "TP-LINK TECHNOLOGIES CO.,LTD.".to_string(),
+            ),
+            (
+                [0x70, 0xb3, 0xd5],
+                "Example \"Quoted\" Networks".to_string(),
+            ),
+        ];
+        assert_eq!(expected, parse_oui_database(&txt));
+        assert_eq!(expected, parse_oui_database(&csv));
+        assert!(parse_oui_database("not a registry\n").is_empty());
+    }
+
+    #[test]
+    fn should_replace_loaded_oui_database() {
+        // a store of its own, so the other tests see the built-in names
+        let loaded = OuiStore::default();
+        let fixture = |name: &str| Path::new("tests/fixtures").join(name);
+        let cisco: MacAddr = "00:1b:d4:01:02:03".parse().unwrap();
+        let built_in: MacAddr = "24:a4:3c:01:02:03".parse().unwrap();
+
+        assert_eq!(
+            3,
+            load_oui_database(&loaded, &fixture("oui/oui.txt")).unwrap()
+        );
+        let vendor = cisco.vendor_in(&loaded);
+        assert_eq!(Some("Cisco Systems, Inc"), vendor.as_deref());
+        assert_eq!(Some("Ubiquiti"), built_in.vendor_in(&loaded).as_deref());
+        // failed loads keep the entries loaded before
+        assert!(load_oui_database(&loaded, &fixture("oui/missing.txt")).is_err());
+        assert!(load_oui_database(&loaded, &fixture("nmcli/nmcli01.txt")).is_err());
+        assert!(cisco.vendor_in(&loaded).is_some());
+
+        assert_eq!(
+            1,
+            load_oui_database(&loaded, &fixture("oui/oui_lab.txt")).unwrap()
+        );
+        assert_eq!(None, cisco.vendor_in(&loaded));
+    }
+
+    #[test]
+    fn should_look_up_loaded_vendor() {
+        // no other test uses this OUI, loading it doesn't change their names
+        let wifi = Wifi {
+            mac: "00:50:c2:01:02:03".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(None, wifi.vendor());
+        assert_eq!(
+            1,
+            set_oui_database(Path::new("tests/fixtures/oui/oui_lab.txt")).unwrap()
+        );
+        assert_eq!(Some("Lab Radio Works"), wifi.vendor().as_deref());
+    }
+
+    #[test]
+    fn should_filter_by_vendor() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_vendors.txt").unwrap();
+        let wifis = parse_nmcli(&data).unwrap();
+        assert_eq!(6, wifis.len());
+
+        let ssids: Vec<String> = filter_by_vendor(wifis.clone(), "UBIQUITI")
+            .into_iter()
+            .map(|wifi| wifi.ssid)
+            .collect();
+        assert_eq!(vec!["unifi-lobby", "unifi-warehouse"], ssids);
+
+        assert_eq!(1, filter_by_vendor(wifis.clone(), "tp-link").len());
+        assert!(filter_by_vendor(wifis, "Nokia").is_empty());
+    }
+
+    #[test]
+    fn should_group_by_vendor() {
+        let data = std::fs::read_to_string("tests/fixtures/nmcli/nmcli_vendors.txt").unwrap();
+        let wifis = parse_nmcli(&data).unwrap();
+
+        let groups = group_by_vendor(&wifis);
+        let summary: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(vendor, members)| {
+                let ssids = members.iter().map(|wifi| wifi.ssid.as_str()).collect();
+                (vendor.as_str(), ssids)
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                // locally administered, random and missing BSSIDs
+                (
+                    UNKNOWN_VENDOR,
+                    vec!["guest-unifi", "mystery-ap", "no-bssid"]
+                ),
+                ("Ubiquiti", vec!["unifi-lobby", "unifi-warehouse"]),
+                ("TP-Link", vec!["home-archer"]),
+            ],
+            summary
+        );
+        assert!(group_by_vendor(&[]).is_empty());
+    }
+}
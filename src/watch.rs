@@ -0,0 +1,237 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::backend::DefaultBackend;
+use crate::{MacAddr, ScanBackend, Wifi};
+
+/// Scans over and over, waiting `interval` between the end of one scan and
+/// the start of the next. The first scan runs right away. A failed scan is
+/// yielded as an error and doesn't stop the iterator, which never ends.
+pub fn scan_continuously(interval: Duration) -> impl Iterator<Item = anyhow::Result<Vec<Wifi>>> {
+    repeat_scan(interval, crate::scan)
+}
+
+fn repeat_scan<F>(
+    interval: Duration,
+    mut scan: F,
+) -> impl Iterator<Item = anyhow::Result<Vec<Wifi>>>
+where
+    F: FnMut() -> anyhow::Result<Vec<Wifi>>,
+{
+    let mut first = true;
+    std::iter::from_fn(move || {
+        if !first {
+            thread::sleep(interval);
+        }
+        first = false;
+        Some(scan())
+    })
+}
+
+/// What a [`Watcher`] looks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Ssid(String),
+    Bssid(MacAddr),
+}
+
+/// Change reported by [`Watcher::poll`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchEvent {
+    /// the network came into range, with its strongest hotspot
+    InRange(Box<Wifi>),
+    /// the network went out of range
+    OutOfRange,
+    /// the network is still in range, or still out of it
+    Unchanged,
+}
+
+/// Watches a network across scans, e.g. to reconnect once the home network
+/// comes back into range. The network is in range once its signal reaches
+/// the enter threshold and stays so until the signal drops below the exit
+/// threshold, or the network isn't seen at all, so a signal hovering around
+/// a single threshold doesn't flap. Hotspots without a signal level are
+/// ignored.
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    target: Target,
+    enter_dbm: i32,
+    exit_dbm: i32,
+    in_range: bool,
+}
+
+impl Watcher {
+    /// Watches the hotspots named `ssid`, the strongest one counts. An
+    /// `exit_dbm` above `enter_dbm` is lowered to it.
+    pub fn for_ssid(ssid: impl Into<String>, enter_dbm: i32, exit_dbm: i32) -> Self {
+        Self::new(Target::Ssid(ssid.into()), enter_dbm, exit_dbm)
+    }
+
+    /// Watches the hotspot `bssid`. An `exit_dbm` above `enter_dbm` is
+    /// lowered to it.
+    pub fn for_bssid(bssid: MacAddr, enter_dbm: i32, exit_dbm: i32) -> Self {
+        Self::new(Target::Bssid(bssid), enter_dbm, exit_dbm)
+    }
+
+    fn new(target: Target, enter_dbm: i32, exit_dbm: i32) -> Self {
+        Watcher {
+            target,
+            enter_dbm,
+            exit_dbm: exit_dbm.min(enter_dbm),
+            in_range: false,
+        }
+    }
+
+    /// Whether the network was in range at the last scan
+    pub fn is_in_range(&self) -> bool {
+        self.in_range
+    }
+
+    /// Scans with [`crate::scan`] and reports how the network's range
+    /// changed, see [`Watcher::observe`]. A failed scan is returned as an
+    /// error and leaves the state alone.
+    pub fn poll(&mut self) -> anyhow::Result<WatchEvent> {
+        self.poll_with(&DefaultBackend)
+    }
+
+    fn poll_with(&mut self, backend: &dyn ScanBackend) -> anyhow::Result<WatchEvent> {
+        Ok(self.observe(&backend.scan()?))
+    }
+
+    /// Reports how the network's range changed with the hotspots of a scan
+    /// made elsewhere
+    pub fn observe(&mut self, wifis: &[Wifi]) -> WatchEvent {
+        let strongest = wifis
+            .iter()
+            .filter(|wifi| match &self.target {
+                Target::Ssid(ssid) => wifi.ssid == *ssid,
+                Target::Bssid(bssid) => wifi.mac_addr() == Some(*bssid),
+            })
+            .filter_map(|wifi| Some((wifi.signal_dbm()?, wifi)))
+            .max_by_key(|(dbm, _)| *dbm);
+
+        match strongest {
+            Some((dbm, wifi)) if !self.in_range && dbm >= self.enter_dbm => {
+                self.in_range = true;
+                WatchEvent::InRange(Box::new(wifi.clone()))
+            }
+            Some((dbm, _)) if self.in_range && dbm >= self.exit_dbm => WatchEvent::Unchanged,
+            _ if self.in_range => {
+                self.in_range = false;
+                WatchEvent::OutOfRange
+            }
+            _ => WatchEvent::Unchanged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReplayBackend;
+
+    #[test]
+    fn should_keep_scanning_after_errors() {
+        let mut calls = 0;
+        let results: Vec<_> = repeat_scan(Duration::ZERO, || {
+            calls += 1;
+            if calls == 2 {
+                anyhow::bail!("scan failed");
+            }
+            Ok(vec![Wifi::default(); calls])
+        })
+        .take(3)
+        .collect();
+
+        assert_eq!(1, results[0].as_ref().unwrap().len());
+        assert!(results[1].is_err());
+        assert_eq!(3, results[2].as_ref().unwrap().len());
+    }
+
+    fn wifi(ssid: &str, mac: &str, signal_level: &str) -> Wifi {
+        Wifi {
+            ssid: ssid.to_string(),
+            mac: mac.to_string(),
+            signal_level: signal_level.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_watch_with_hysteresis() {
+        let mut watcher = Watcher::for_ssid("Home", -70, -80);
+        let home = |signal_level| wifi("Home", "00:00:00:00:00:01", signal_level);
+        let events: Vec<WatchEvent> = [
+            vec![home("-75")],
+            vec![home("-69"), wifi("Home", "00:00:00:00:00:02", "-60")],
+            vec![home("-78")],
+            vec![home("-72")],
+            vec![home("-81")],
+            vec![home("-75")],
+            vec![home("-70")],
+            vec![wifi("Office", "00:00:00:00:00:03", "-40"), home("")],
+        ]
+        .iter()
+        .map(|wifis| watcher.observe(wifis))
+        .collect();
+
+        assert_eq!(
+            vec![
+                // below the enter threshold
+                WatchEvent::Unchanged,
+                // the strongest hotspot is reported
+                WatchEvent::InRange(Box::new(wifi("Home", "00:00:00:00:00:02", "-60"))),
+                // between the thresholds
+                WatchEvent::Unchanged,
+                WatchEvent::Unchanged,
+                WatchEvent::OutOfRange,
+                WatchEvent::Unchanged,
+                WatchEvent::InRange(Box::new(home("-70"))),
+                // gone, or without a signal
+                WatchEvent::OutOfRange,
+            ],
+            events
+        );
+        assert!(!watcher.is_in_range());
+    }
+
+    #[test]
+    fn should_watch_bssid() {
+        let bssid: MacAddr = "00:00:00:00:00:02".parse().unwrap();
+        // the exit threshold can't be above the enter one
+        let mut watcher = Watcher::for_bssid(bssid, -70, -60);
+        let scan = vec![
+            wifi("Home", "00:00:00:00:00:01", "-40"),
+            wifi("Home", "00:00:00:00:00:02", "-65"),
+        ];
+        assert_eq!(
+            WatchEvent::InRange(Box::new(scan[1].clone())),
+            watcher.observe(&scan)
+        );
+        assert_eq!(
+            WatchEvent::Unchanged,
+            watcher.observe(&[wifi("", "00:00:00:00:00:02", "-70")])
+        );
+    }
+
+    #[test]
+    fn should_keep_range_across_failed_polls() {
+        let mut watcher = Watcher::for_ssid("Home", -70, -80);
+        let home = wifi("Home", "00:00:00:00:00:01", "-60");
+        let replay =
+            ReplayBackend::new(vec![vec![home.clone()], vec![home.clone()], vec![]]).fail_at(1);
+        let events: Vec<Option<WatchEvent>> =
+            (0..4).map(|_| watcher.poll_with(&replay).ok()).collect();
+
+        assert_eq!(
+            vec![
+                Some(WatchEvent::InRange(Box::new(home))),
+                // a failed scan isn't the network going out of range
+                None,
+                Some(WatchEvent::Unchanged),
+                Some(WatchEvent::OutOfRange),
+            ],
+            events
+        );
+    }
+}
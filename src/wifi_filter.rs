@@ -0,0 +1,194 @@
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use std::collections::HashSet;
+
+use crate::{Band, MacAddr, SecurityKind, Wifi};
+
+/// Constraints a hotspot must all meet to pass [`Wifi::matches`]. A `None`
+/// or empty field doesn't constrain anything, so the default matches every
+/// hotspot.
+///
+/// ```
+/// use wifiscanner::{Band, SecurityKind, WifiFilter};
+///
+/// let filter = WifiFilter {
+///     band: Some(Band::Band5GHz),
+///     min_dbm: Some(-70),
+///     min_security: Some(SecurityKind::Wpa2),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WifiFilter {
+    /// the exact SSID
+    pub ssid: Option<String>,
+    /// a regular expression the SSID matches, unanchored
+    #[cfg(feature = "regex")]
+    pub ssid_regex: Option<Regex>,
+    pub mac: Option<MacAddr>,
+    /// the weakest signal allowed, hotspots without a signal never match
+    pub min_dbm: Option<i32>,
+    pub band: Option<Band>,
+    /// the weakest security allowed, see [`Wifi::is_at_least`]
+    pub min_security: Option<SecurityKind>,
+    /// part of the manufacturer's name, ignoring case, see [`Wifi::vendor`]
+    pub vendor: Option<String>,
+}
+
+impl Wifi {
+    /// Whether the hotspot meets every constraint of `filter`
+    pub fn matches(&self, filter: &WifiFilter) -> bool {
+        let non_empty = |field: &Option<String>| field.clone().filter(|s| !s.is_empty());
+        if non_empty(&filter.ssid).is_some_and(|ssid| self.ssid != ssid) {
+            return false;
+        }
+        #[cfg(feature = "regex")]
+        if filter
+            .ssid_regex
+            .as_ref()
+            .is_some_and(|regex| !regex.is_match(&self.ssid))
+        {
+            return false;
+        }
+        if filter.mac.is_some() && self.mac_addr() != filter.mac {
+            return false;
+        }
+        if filter
+            .min_dbm
+            .is_some_and(|min| self.signal_dbm().is_none_or(|dbm| dbm < min))
+        {
+            return false;
+        }
+        if filter.band.is_some() && self.band() != filter.band {
+            return false;
+        }
+        if filter
+            .min_security
+            .is_some_and(|min| !self.is_at_least(min))
+        {
+            return false;
+        }
+        non_empty(&filter.vendor).is_none_or(|vendor| self.has_vendor(&vendor))
+    }
+}
+
+/// Scans and keeps the hotspots matching `filter`, see [`Wifi::matches`]
+pub fn scan_filtered(filter: &WifiFilter) -> anyhow::Result<Vec<Wifi>> {
+    Ok(crate::scan()?
+        .into_iter()
+        .filter(|wifi| wifi.matches(filter))
+        .collect())
+}
+
+/// Returns the hotspots broadcasting `ssid` from a BSSID outside
+/// `allowed_bssids`, e.g. an evil twin of the office network. Hotspots
+/// without a valid BSSID can't be checked and are returned too. The SSID
+/// must match exactly, an empty one looks for hidden networks.
+pub fn find_rogues(wifis: &[Wifi], ssid: &str, allowed_bssids: &HashSet<MacAddr>) -> Vec<Wifi> {
+    wifis
+        .iter()
+        .filter(|wifi| wifi.ssid == ssid)
+        .filter(|wifi| {
+            wifi.mac_addr()
+                .is_none_or(|mac| !allowed_bssids.contains(&mac))
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_airport;
+
+    fn matching(filter: &WifiFilter) -> Vec<String> {
+        let data = std::fs::read_to_string("tests/fixtures/airport/airport_filter.txt").unwrap();
+        parse_airport(&data)
+            .unwrap()
+            .into_iter()
+            .filter(|wifi| wifi.matches(filter))
+            .map(|wifi| wifi.mac)
+            .collect()
+    }
+
+    #[test]
+    fn should_match_everything_by_default() {
+        assert_eq!(6, matching(&WifiFilter::default()).len());
+        let empty = WifiFilter {
+            ssid: Some(String::new()),
+            vendor: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(6, matching(&empty).len());
+    }
+
+    #[test]
+    fn should_combine_constraints() {
+        let filter = WifiFilter {
+            vendor: Some("ubiquiti".to_string()),
+            band: Some(Band::Band5GHz),
+            min_security: Some(SecurityKind::Wpa2),
+            ..Default::default()
+        };
+        assert_eq!(vec!["24:a4:3c:10:20:30"], matching(&filter));
+
+        let filter = WifiFilter {
+            ssid: Some("unifi-lobby".to_string()),
+            min_dbm: Some(-60),
+            ..Default::default()
+        };
+        assert_eq!(vec!["24:a4:3c:10:20:30"], matching(&filter));
+
+        let filter = WifiFilter {
+            mac: "24:A4:3C:10:20:31".parse().ok(),
+            min_dbm: Some(-60),
+            ..Default::default()
+        };
+        assert!(matching(&filter).is_empty());
+
+        let filter = WifiFilter {
+            min_security: Some(SecurityKind::Wpa3),
+            band: Some(Band::Band5GHz),
+            ..Default::default()
+        };
+        assert_eq!(vec!["50:c7:bf:44:55:66"], matching(&filter));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn should_match_ssid_regex() {
+        let filter = WifiFilter {
+            ssid_regex: Some(Regex::new("^unifi-(lobby|old)$").unwrap()),
+            band: Some(Band::Band2_4GHz),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["24:a4:3c:10:20:31", "24:a4:3c:10:20:33"],
+            matching(&filter)
+        );
+    }
+
+    #[test]
+    fn should_find_rogues() {
+        let wifi = |ssid: &str, mac: &str| Wifi {
+            ssid: ssid.to_string(),
+            mac: mac.to_string(),
+            ..Default::default()
+        };
+        let wifis = vec![
+            wifi("Office", "00:11:22:33:44:55"),
+            wifi("Office", "DE:AD:BE:EF:00:01"),
+            wifi("Guest", "de:ad:be:ef:00:02"),
+            wifi("office", "de:ad:be:ef:00:03"),
+            wifi("Office", ""),
+        ];
+        let allowed: HashSet<MacAddr> = ["00:11:22:33:44:55".parse().unwrap()].into();
+
+        assert_eq!(
+            vec![wifi("Office", "DE:AD:BE:EF:00:01"), wifi("Office", "")],
+            find_rogues(&wifis, "Office", &allowed)
+        );
+        assert!(find_rogues(&wifis, "Lab", &allowed).is_empty());
+    }
+}
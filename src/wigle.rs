@@ -0,0 +1,165 @@
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{SecurityKind, Wifi};
+
+/// Column header of a WiGLE 1.4 CSV file
+const HEADER: &str = "MAC,SSID,AuthMode,FirstSeen,Channel,RSSI,CurrentLatitude,CurrentLongitude,\
+                      AltitudeMeters,AccuracyMeters,Type";
+
+/// Same as [`to_wigle_csv_at`] for a scan made just now
+pub fn to_wigle_csv(wifis: &[Wifi]) -> String {
+    to_wigle_csv_at(wifis, SystemTime::now())
+}
+
+/// Returns the hotspots of a scan made at `scanned_at` as a WiGLE 1.4 CSV
+/// file, ready to upload to wigle.net. After the `WigleWifi-1.4` line and
+/// the header comes one row per hotspot:
+///
+/// - `MAC` is [`Wifi::mac`] and `SSID` is [`Wifi::ssid`]
+/// - `AuthMode` is WiGLE's flags for the [`Wifi::security_kind`], e.g.
+///   `[WPA2-PSK][ESS]`, and empty when the security isn't recognised
+/// - `FirstSeen` is `scanned_at` less [`Wifi::last_seen_ms`], in UTC
+/// - `Channel` is [`Wifi::channel_number`] and `RSSI` is [`Wifi::signal_dbm`]
+/// - `CurrentLatitude` and `CurrentLongitude` come from [`Wifi::location`],
+///   blank without one, as are `AltitudeMeters` and `AccuracyMeters` which
+///   the crate doesn't know
+/// - `Type` is always `WIFI`
+pub fn to_wigle_csv_at(wifis: &[Wifi], scanned_at: SystemTime) -> String {
+    let mut csv = format!(
+        "WigleWifi-1.4,appRelease={},model=,release=,device=wifiscanner,display=,board=,brand=\n\
+         {HEADER}\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    for wifi in wifis {
+        let age = Duration::from_millis(wifi.last_seen_ms.unwrap_or(0).into());
+        let seen = scanned_at.checked_sub(age).unwrap_or(scanned_at);
+        let number = |n: Option<String>| n.unwrap_or_default();
+        let (latitude, longitude) = match wifi.location {
+            Some((lat, lon)) => (lat.to_string(), lon.to_string()),
+            None => (String::new(), String::new()),
+        };
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{latitude},{longitude},,,WIFI",
+            field(&wifi.mac),
+            field(&wifi.ssid),
+            auth_mode(wifi),
+            utc_timestamp(seen),
+            number(wifi.channel_number().map(|c| c.to_string())),
+            number(wifi.signal_dbm().map(|dbm| dbm.to_string())),
+        );
+    }
+    csv
+}
+
+/// Quotes a CSV field holding a comma, a quote or a line break
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// WiGLE's capability flags for the security of `wifi`
+fn auth_mode(wifi: &Wifi) -> &'static str {
+    let enterprise = wifi.is_enterprise();
+    match wifi.security_kind() {
+        Some(SecurityKind::Open) => "[ESS]",
+        Some(SecurityKind::Wep) => "[WEP][ESS]",
+        Some(SecurityKind::Wpa) if enterprise => "[WPA-EAP][ESS]",
+        Some(SecurityKind::Wpa) => "[WPA-PSK][ESS]",
+        Some(SecurityKind::Wpa2) if enterprise => "[WPA2-EAP][ESS]",
+        Some(SecurityKind::Wpa2) => "[WPA2-PSK][ESS]",
+        Some(SecurityKind::Wpa3) if enterprise => "[WPA3-EAP][ESS]",
+        Some(SecurityKind::Wpa3) => "[WPA3-SAE][ESS]",
+        Some(SecurityKind::Wpa3Enterprise192) => "[WPA3-EAP-SUITE-B-192][ESS]",
+        None => "",
+    }
+}
+
+/// Formats `time` as `YYYY-MM-DD HH:MM:SS` in UTC, times before 1970 as the
+/// epoch
+pub(crate) fn utc_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    // days since 1970-01-01 to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_export_wigle_csv() {
+        let wifis = vec![
+            Wifi {
+                mac: "00:11:22:33:44:55".to_string(),
+                ssid: "Cafe, \"Main\"".to_string(),
+                channel: "6".to_string(),
+                signal_level: "-61.00".to_string(),
+                security: "WPA2(PSK/AES/AES)".to_string(),
+                last_seen_ms: Some(2500),
+                location: Some((51.5007, -0.1246)),
+                ..Default::default()
+            },
+            Wifi {
+                mac: "66:77:88:99:aa:bb".to_string(),
+                ssid: "Lobby".to_string(),
+                channel: "149,+1".to_string(),
+                security: "".to_string(),
+                ..Default::default()
+            },
+        ];
+        // 2024-02-29 23:59:30 UTC
+        let scanned_at = UNIX_EPOCH + Duration::from_secs(1_709_251_170);
+
+        let csv = to_wigle_csv_at(&wifis, scanned_at);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("WigleWifi-1.4,appRelease="));
+        assert_eq!(
+            "MAC,SSID,AuthMode,FirstSeen,Channel,RSSI,CurrentLatitude,CurrentLongitude,\
+             AltitudeMeters,AccuracyMeters,Type",
+            lines[1]
+        );
+        assert_eq!(
+            "00:11:22:33:44:55,\"Cafe, \"\"Main\"\"\",[WPA2-PSK][ESS],2024-02-29 23:59:27,6,-61,\
+             51.5007,-0.1246,,,WIFI",
+            lines[2]
+        );
+        assert_eq!(
+            "66:77:88:99:aa:bb,Lobby,[ESS],2024-02-29 23:59:30,149,,,,,,WIFI",
+            lines[3]
+        );
+        assert_eq!(4, lines.len());
+    }
+
+    #[test]
+    fn should_format_utc_timestamps() {
+        let at = |seconds| utc_timestamp(UNIX_EPOCH + Duration::from_secs(seconds));
+        assert_eq!("1970-01-01 00:00:00", at(0));
+        assert_eq!("2000-03-01 00:00:00", at(951_868_800));
+        assert_eq!("2023-12-31 23:59:59", at(1_704_067_199));
+    }
+}
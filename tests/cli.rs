@@ -0,0 +1,102 @@
+//! Runs the `wifiscanner` binary against a fake `iw` printing a fixture.
+#![cfg(target_os = "linux")]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Directory holding an `iw` that lists one interface and prints the EHT
+/// scan fixture when asked to scan
+fn fake_iw(name: &str) -> PathBuf {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/iw");
+    let dir = std::env::temp_dir().join(format!("wifiscanner-cli-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("iw");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\n\
+             case \"$*\" in\n\
+             dev) cat {fixtures}/iw_dev_01.txt ;;\n\
+             *) cat {fixtures}/iw_dev_scan_eht.txt ;;\n\
+             esac\n"
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    dir
+}
+
+fn wifiscanner(name: &str, args: &[&str]) -> Output {
+    let dir = fake_iw(name);
+    let output = Command::new(env!("CARGO_BIN_EXE_wifiscanner"))
+        .args(args)
+        .env("PATH", format!("{}:/usr/bin:/bin", dir.display()))
+        .env("WIFISCANNER_BACKEND", "iw")
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    output
+}
+
+#[test]
+fn should_filter_by_signal_and_band() {
+    let rows = |output: Output| -> Vec<String> {
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split_whitespace().nth(1).unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        vec!["wifi7-lab", "wifi6-office", "wifi5-cafe"],
+        rows(wifiscanner("all", &[]))
+    );
+    assert_eq!(
+        vec!["wifi7-lab"],
+        rows(wifiscanner("five", &["--min-signal", "-70", "--band", "5"]))
+    );
+    assert_eq!(
+        vec!["wifi6-office"],
+        rows(wifiscanner(
+            "two",
+            &["--band", "2.4", "--min-signal", "-65"]
+        ))
+    );
+    assert!(rows(wifiscanner("none", &["--band", "6"])).is_empty());
+}
+
+#[test]
+fn should_reject_invalid_filters() {
+    let output = wifiscanner("invalid", &["--band", "3"]);
+    assert_eq!(Some(2), output.status.code());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("invalid --band"), "{stderr}");
+}
+
+#[test]
+fn should_list_interfaces() {
+    let output = wifiscanner("list", &["--list-interfaces"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!("wlp2s0\n", String::from_utf8(output.stdout).unwrap());
+}
+
+#[test]
+fn should_scan_named_interface() {
+    let output = wifiscanner("named", &["--interface", "wlp2s0"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("wifi7-lab"));
+
+    let output = wifiscanner("missing", &["--interface", "wlan9"]);
+    assert_eq!(Some(1), output.status.code());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(
+        "Cannot scan network: No wireless interface named \"wlan9\"\n",
+        stderr
+    );
+}